@@ -13,6 +13,22 @@ blueprint! {
             package.into()
         }
 
+        /// Publishes a new version of a package, authorized by its upgrade badge.
+        pub fn publish_package_upgrade(code: Vec<u8>, previous: Address, auth: BucketRef) -> Address {
+            let package = Package::new_version(previous, &code, Some(auth));
+            package.into()
+        }
+
+        /// Migrates a component to a newer version of its package.
+        pub fn migrate_component(component: Address, package: Address, auth: BucketRef) {
+            Component::from(component).migrate(package, auth)
+        }
+
+        /// Claims the royalties collected by a component.
+        pub fn claim_royalties(component: Address, auth: BucketRef) -> Bucket {
+            Component::from(component).claim_royalties(auth)
+        }
+
         /// Creates a resource with mutable supply, and returns the resource definition address.
         pub fn new_resource_mutable(metadata: HashMap<String, String>, mint_auth: Address) -> Address {
             let resource_def = ResourceDef::new_mutable(metadata, mint_auth);
@@ -1,5 +1,7 @@
 use sbor::*;
 use scrypto::rust::fmt;
+use scrypto::rust::string::String;
+use scrypto::rust::vec::Vec;
 use scrypto::types::*;
 use wasmi::*;
 
@@ -62,6 +64,15 @@ pub enum RuntimeError {
     /// Package does not exist.
     PackageNotFound(Address),
 
+    /// The blueprint has no function or method of this name (blueprint name, function name).
+    FunctionNotFound(String, String),
+
+    /// The call arguments don't match the blueprint ABI.
+    InvalidCallArgs(ValidateError),
+
+    /// The call output doesn't match the blueprint ABI.
+    InvalidCallOutput(ValidateError),
+
     /// Component does not exist.
     ComponentNotFound(Address),
 
@@ -80,6 +91,15 @@ pub enum RuntimeError {
     /// Bucket ref does not exist.
     BucketRefNotFound(Rid),
 
+    /// The package is not a newer version of the component's package.
+    InvalidPackageUpgrade(Address, Address),
+
+    /// Publishing a newer version is not authorized by the previous package or its upgrade badge.
+    UnauthorizedPackageUpgrade(Address),
+
+    /// Not enough XRD with the caller to pay the royalty of a method.
+    InsufficientRoyalty(Address, String),
+
     /// Not a package address.
     InvalidPackageAddress(Address),
 
@@ -127,6 +147,20 @@ pub enum RuntimeError {
 
     /// Resource check failure.
     ResourceCheckFailure,
+
+    /// Vaults created in the transaction are not reachable from any component state.
+    ///
+    /// Each entry lists the vault id, resource definition and the balance which would be lost.
+    OrphanedVaults(Vec<(Vid, Address, Amount)>),
+
+    /// The supply change of a resource doesn't match the balance change of all vaults.
+    ResourceNotConserved(Address),
+
+    /// The transaction context holds less of a resource than asserted (resource def, expected, actual).
+    ContextAssertionFailed(Address, Amount, Amount),
+
+    /// A bucket holds less than asserted (bucket id, expected, actual).
+    BucketAssertionFailed(Bid, Amount, Amount),
 }
 
 impl fmt::Display for RuntimeError {
@@ -1,7 +1,9 @@
 use colored::*;
 use sbor::any::*;
+use sbor::describe::Type;
 use sbor::rust::boxed::Box;
 use sbor::*;
+use scrypto::abi;
 use scrypto::buffer::*;
 use scrypto::kernel::*;
 use scrypto::rust::borrow::ToOwned;
@@ -19,6 +21,7 @@ use wasmi::*;
 use crate::engine::*;
 use crate::ledger::*;
 use crate::model::*;
+use crate::utils::*;
 
 macro_rules! trace {
     ($proc:expr, $($args: expr),+) => {
@@ -64,6 +67,7 @@ pub struct Process<'r, 'l, L: Ledger> {
     moving_bucket_refs: HashMap<Rid, BucketRef>,
     temp_buckets: HashMap<Bid, Bucket>,
     temp_bucket_refs: HashMap<Rid, BucketRef>,
+    locked_temp_bids: HashSet<Bid>,
     reserved_bids: HashSet<Bid>,
     reserved_rids: HashSet<Rid>,
     vm: Option<Interpreter>,
@@ -80,6 +84,8 @@ pub struct Interpreter {
 #[derive(Debug, Clone)]
 pub struct Invocation {
     package: Address,
+    component: Option<Address>,
+    royalty: Option<Amount>,
     export: String,
     function: String,
     args: Vec<Vec<u8>>,
@@ -99,6 +105,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             moving_bucket_refs: HashMap::new(),
             temp_buckets: HashMap::new(),
             temp_bucket_refs: HashMap::new(),
+            locked_temp_bids: HashSet::new(),
             reserved_bids: HashSet::new(),
             reserved_rids: HashSet::new(),
             vm: None,
@@ -119,6 +126,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         rid
     }
 
+    /// Takes resource out of the buckets of this process, in bucket id order.
     fn withdraw_resource(
         &mut self,
         amount: Amount,
@@ -131,7 +139,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             .map(|(k, _)| *k)
             .collect();
 
-        let mut needed = amount;
+        let mut needed = amount.clone();
         for candidate in candidates {
             if needed.is_zero() {
                 break;
@@ -142,13 +150,22 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
                 self.buckets
                     .get_mut(&candidate)
                     .unwrap()
-                    .take(needed)
+                    .take(needed.clone())
                     .unwrap();
                 needed = Amount::zero();
-            } else {
+            } else if self.depth == 0 {
                 debug!(self, "Withdrawing all from {:?}", candidate);
                 self.buckets.remove(&candidate);
                 needed -= available;
+            } else {
+                // a blueprint still refers to its bucket, so leave it empty
+                debug!(self, "Withdrawing all from {:?}", candidate);
+                self.buckets
+                    .get_mut(&candidate)
+                    .unwrap()
+                    .take(available.clone())
+                    .unwrap();
+                needed -= available;
             }
         }
 
@@ -177,7 +194,34 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             return Err(RuntimeError::BucketNotReserved);
         }
 
-        self.withdraw_resource(amount, resource_def)?;
+        self.withdraw_resource(amount.clone(), resource_def)?;
+
+        self.temp_buckets
+            .insert(bid, Bucket::new(amount, resource_def));
+
+        Ok(())
+    }
+
+    /// Takes all of a resource from this context to a temporary bucket.
+    pub fn take_all_from_context(
+        &mut self,
+        resource_def: Address,
+        bid: Bid,
+    ) -> Result<(), RuntimeError> {
+        debug!(
+            self,
+            "Creating bucket: resource_def = {:?}, bid = {:?}", resource_def, bid
+        );
+        if !self.reserved_bids.remove(&bid) {
+            return Err(RuntimeError::BucketNotReserved);
+        }
+
+        let amount = self
+            .buckets
+            .values()
+            .filter(|b| b.resource_def() == resource_def)
+            .fold(Amount::zero(), |sum, b| sum + b.amount());
+        self.withdraw_resource(amount.clone(), resource_def)?;
 
         self.temp_buckets
             .insert(bid, Bucket::new(amount, resource_def));
@@ -185,6 +229,91 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         Ok(())
     }
 
+    /// Returns a temporary bucket to this context.
+    pub fn return_to_context(&mut self, bid: Bid) -> Result<(), RuntimeError> {
+        debug!(self, "Returning bucket: bid = {:?}", bid);
+        let bucket = self
+            .temp_buckets
+            .remove(&bid)
+            .ok_or(RuntimeError::BucketNotFound(bid))?;
+        self.buckets.insert(bid, bucket);
+
+        Ok(())
+    }
+
+    /// Creates a temporary bucket ref from a temporary bucket.
+    pub fn create_bucket_ref_from_bucket(
+        &mut self,
+        bid: Bid,
+        rid: Rid,
+    ) -> Result<(), RuntimeError> {
+        debug!(self, "Borrowing: bid = {:?}, rid = {:?}", bid, rid);
+        if !self.reserved_rids.remove(&rid) {
+            return Err(RuntimeError::BucketRefNotReserved);
+        }
+
+        match self.locked_buckets.get(&bid) {
+            Some(bucket_rc) => {
+                // re-borrow
+                self.temp_bucket_refs.insert(rid, bucket_rc.clone());
+            }
+            None => {
+                // first time borrow
+                let bucket = BucketRef::new(LockedBucket::new(
+                    bid,
+                    self.temp_buckets
+                        .remove(&bid)
+                        .ok_or(RuntimeError::BucketNotFound(bid))?,
+                ));
+                self.locked_buckets.insert(bid, bucket.clone());
+                self.locked_temp_bids.insert(bid);
+                self.temp_bucket_refs.insert(rid, bucket);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Clones a temporary bucket ref.
+    pub fn clone_bucket_ref(&mut self, rid: Rid, to: Rid) -> Result<(), RuntimeError> {
+        debug!(self, "Cloning bucket ref: rid = {:?}, to = {:?}", rid, to);
+        if !self.reserved_rids.remove(&to) {
+            return Err(RuntimeError::BucketRefNotReserved);
+        }
+
+        let bucket_ref = self
+            .temp_bucket_refs
+            .get(&rid)
+            .ok_or(RuntimeError::BucketRefNotFound(rid))?
+            .clone();
+        self.temp_bucket_refs.insert(to, bucket_ref);
+
+        Ok(())
+    }
+
+    /// Drops all bucket refs held by this context.
+    ///
+    /// Buckets no longer referenced are unlocked and returned to the context.
+    pub fn drop_all_bucket_refs(&mut self) -> Result<(), RuntimeError> {
+        debug!(self, "Dropping all bucket refs");
+        self.bucket_refs.clear();
+        self.temp_bucket_refs.clear();
+
+        let locked: Vec<(Bid, BucketRef)> = self.locked_buckets.drain().collect();
+        for (bid, bucket_rc) in locked {
+            match Rc::try_unwrap(bucket_rc) {
+                Ok(bucket) => {
+                    self.unlock_bucket(bid, bucket.into());
+                }
+                Err(bucket_rc) => {
+                    self.locked_buckets.insert(bid, bucket_rc);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Borrows resource from this context to a temporary bucket ref.
     ///
     /// A bucket will be created to support the reference.
@@ -205,7 +334,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             return Err(RuntimeError::BucketRefNotReserved);
         }
 
-        self.withdraw_resource(amount, resource_def)?;
+        self.withdraw_resource(amount.clone(), resource_def)?;
 
         let bid = self.track.new_bid();
         let bucket = BucketRef::new(LockedBucket::new(bid, Bucket::new(amount, resource_def)));
@@ -215,6 +344,63 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         Ok(())
     }
 
+    /// Asserts that this context holds at least the given amount of a resource.
+    pub fn assert_context_contains(
+        &self,
+        amount: Amount,
+        resource_def: Address,
+    ) -> Result<(), RuntimeError> {
+        let actual = self
+            .buckets
+            .values()
+            .filter(|b| b.resource_def() == resource_def)
+            .fold(Amount::zero(), |sum, b| sum + b.amount());
+        debug!(
+            self,
+            "Asserting context contains: amount = {:?}, resource_def = {:?}, actual = {:?}",
+            amount,
+            resource_def,
+            actual
+        );
+
+        if actual >= amount {
+            Ok(())
+        } else {
+            Err(RuntimeError::ContextAssertionFailed(
+                resource_def,
+                amount,
+                actual,
+            ))
+        }
+    }
+
+    /// Asserts that a temporary bucket holds at least the given amount.
+    pub fn assert_bucket_amount_at_least(
+        &self,
+        bid: Bid,
+        amount: Amount,
+    ) -> Result<(), RuntimeError> {
+        let actual = self
+            .temp_buckets
+            .get(&bid)
+            .or_else(|| self.buckets.get(&bid))
+            .ok_or(RuntimeError::BucketNotFound(bid))?
+            .amount();
+        debug!(
+            self,
+            "Asserting bucket amount: bid = {:?}, amount = {:?}, actual = {:?}",
+            bid,
+            amount,
+            actual
+        );
+
+        if actual >= amount {
+            Ok(())
+        } else {
+            Err(RuntimeError::BucketAssertionFailed(bid, amount, actual))
+        }
+    }
+
     /// Puts buckets and bucket refs into this process.
     pub fn put_resources(
         &mut self,
@@ -299,6 +485,8 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
     ) -> Result<Invocation, RuntimeError> {
         Ok(Invocation {
             package,
+            component: None,
+            royalty: None,
             export: format!("{}_main", name),
             function: function.to_owned(),
             args,
@@ -312,16 +500,19 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         method: &str,
         args: Vec<Vec<u8>>,
     ) -> Result<Invocation, RuntimeError> {
-        let com = self
+        let (package, name, royalty) = self
             .track
             .get_component(component)
-            .ok_or(RuntimeError::ComponentNotFound(component))?
-            .clone();
+            .map(|c| (c.package(), c.name().to_owned(), c.royalty(method)))
+            .ok_or(RuntimeError::ComponentNotFound(component))?;
 
         let mut self_args = vec![scrypto_encode(&component)];
         self_args.extend(args);
 
-        self.prepare_call_function(com.package(), com.name(), method, self_args)
+        let mut invocation = self.prepare_call_function(package, &name, method, self_args)?;
+        invocation.component = Some(component);
+        invocation.royalty = royalty;
+        Ok(invocation)
     }
 
     /// Prepares an ABI call.
@@ -332,6 +523,8 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
     ) -> Result<Invocation, RuntimeError> {
         Ok(Invocation {
             package: package,
+            component: None,
+            royalty: None,
             export: format!("{}_abi", name),
             function: String::new(),
             args: Vec::new(),
@@ -341,8 +534,15 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
     /// Calls a function/method.
     pub fn call(&mut self, invocation: Invocation) -> Result<Vec<u8>, RuntimeError> {
         // move resource
+        let limits = args_decode_limits(invocation.package, &invocation.function);
         for arg in &invocation.args {
-            self.process_data(arg, Self::move_buckets, Self::move_bucket_refs)?;
+            self.process_data_with_limits(arg, limits, Self::move_buckets, Self::move_bucket_refs)?;
+        }
+
+        // pay royalty from what the caller keeps, so argument buckets are never taken
+        if let (Some(component), Some(amount)) = (invocation.component, invocation.royalty.clone())
+        {
+            self.charge_royalty(component, &invocation.function, amount)?;
         }
         let (buckets_out, bucket_refs_out) = self.take_moving_resources();
         let mut process = Process::new(self.depth + 1, self.trace, self.track);
@@ -367,7 +567,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             debug!(self, "Changing bucket {:?} to unlocked state", bid);
             let bucket_rc = self.locked_buckets.remove(&bid).unwrap();
             let bucket = Rc::try_unwrap(bucket_rc).unwrap();
-            self.buckets.insert(bid, bucket.into());
+            self.unlock_bucket(bid, bucket.into());
         }
 
         Ok(result)
@@ -382,8 +582,19 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         args: Vec<Vec<u8>>,
     ) -> Result<Vec<u8>, RuntimeError> {
         debug!(self, "Call function started");
+        let (inputs, output) = self
+            .export_abi(package, name)?
+            .0
+            .into_iter()
+            .find(|f| f.name == function)
+            .map(|f| (f.inputs, f.output))
+            .ok_or_else(|| RuntimeError::FunctionNotFound(name.to_owned(), function.to_owned()))?;
+        Self::validate_args(&args, &inputs, args_decode_limits(package, function))?;
         let invocation = self.prepare_call_function(package, name, function, args)?;
-        let result = self.call(invocation);
+        let result = self.call(invocation).and_then(|rtn| {
+            validate_untrusted(&rtn, &output).map_err(RuntimeError::InvalidCallOutput)?;
+            Ok(rtn)
+        });
         debug!(self, "Call function ended");
         result
     }
@@ -396,8 +607,24 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         args: Vec<Vec<u8>>,
     ) -> Result<Vec<u8>, RuntimeError> {
         debug!(self, "Call method started");
+        let (package, name) = self
+            .track
+            .get_component(component)
+            .map(|c| (c.package(), c.name().to_owned()))
+            .ok_or(RuntimeError::ComponentNotFound(component))?;
+        let (inputs, output) = self
+            .export_abi(package, &name)?
+            .1
+            .into_iter()
+            .find(|m| m.name == method)
+            .map(|m| (m.inputs, m.output))
+            .ok_or(RuntimeError::FunctionNotFound(name, method.to_owned()))?;
+        Self::validate_args(&args, &inputs, args_decode_limits(package, method))?;
         let invocation = self.prepare_call_method(component, method, args)?;
-        let result = self.call(invocation);
+        let result = self.call(invocation).and_then(|rtn| {
+            validate_untrusted(&rtn, &output).map_err(RuntimeError::InvalidCallOutput)?;
+            Ok(rtn)
+        });
         debug!(self, "Call method ended");
         result
     }
@@ -411,13 +638,52 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         result
     }
 
+    /// Exports the functions and methods of a blueprint.
+    ///
+    /// The ABI is cached by the track, so the blueprint's ABI export only runs once per transaction.
+    fn export_abi(
+        &mut self,
+        package: Address,
+        name: &str,
+    ) -> Result<(Vec<abi::Function>, Vec<abi::Method>), RuntimeError> {
+        if let Some(abi) = self.track.get_abi(package, name) {
+            return Ok(abi.clone());
+        }
+
+        let rtn = self.call_abi(package, name)?;
+        let abi: (Vec<abi::Function>, Vec<abi::Method>) =
+            decode_untrusted(&rtn).map_err(RuntimeError::InvalidData)?;
+        self.track.put_abi(package, name.to_owned(), abi.clone());
+        Ok(abi)
+    }
+
+    /// Checks call arguments against the input types of a function or method.
+    fn validate_args(
+        args: &[Vec<u8>],
+        inputs: &[Type],
+        limits: DecodeLimits,
+    ) -> Result<(), RuntimeError> {
+        if args.len() != inputs.len() {
+            return Err(RuntimeError::InvalidCallArgs(ValidateError::Mismatch {
+                path: "args".to_owned(),
+                expected: format!("{} args", inputs.len()),
+                actual: format!("{} args", args.len()),
+            }));
+        }
+        for (i, (arg, ty)) in args.iter().zip(inputs).enumerate() {
+            validate_untrusted_with_limits(arg, ty, limits)
+                .map_err(|e| RuntimeError::InvalidCallArgs(e.at(&format!("args[{}]", i))))?;
+        }
+        Ok(())
+    }
+
     /// Checks resource leak.
     pub fn check_resource(&self) -> Result<(), RuntimeError> {
         debug!(self, "Resource check started");
         let mut success = true;
 
         for (bid, bucket) in &self.locked_buckets {
-            re_warn!(self, "Dangling locked bucket: {:?}, {:?}", bid, bucket);
+            warn!(self, "Dangling locked bucket: {:?}, {:?}", bid, bucket);
             success = false;
         }
         for (bid, bucket) in &self.buckets {
@@ -471,6 +737,14 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             .map(|vm| vm.invocation.package)
     }
 
+    /// Return the address of the component whose method is running, if any
+    fn component(&self) -> Result<Option<Address>, RuntimeError> {
+        self.vm
+            .as_ref()
+            .ok_or(RuntimeError::InterpreterNotStarted)
+            .map(|vm| vm.invocation.component)
+    }
+
     /// Return the function name
     fn function(&self) -> Result<String, RuntimeError> {
         self.vm
@@ -510,7 +784,18 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         bf: fn(&mut Self, Bid) -> Result<Bid, RuntimeError>,
         rf: fn(&mut Self, Rid) -> Result<Rid, RuntimeError>,
     ) -> Result<Vec<u8>, RuntimeError> {
-        let value = decode_any(data).map_err(RuntimeError::InvalidData)?;
+        self.process_data_with_limits(data, DECODE_LIMITS, bf, rf)
+    }
+
+    /// Process SBOR data by applying functions on Bid and Rid, decoding within the given limits.
+    fn process_data_with_limits(
+        &mut self,
+        data: &[u8],
+        limits: DecodeLimits,
+        bf: fn(&mut Self, Bid) -> Result<Bid, RuntimeError>,
+        rf: fn(&mut Self, Rid) -> Result<Rid, RuntimeError>,
+    ) -> Result<Vec<u8>, RuntimeError> {
+        let value = decode_any_with_limits(data, limits).map_err(RuntimeError::InvalidData)?;
         let transformed = self.visit(value, bf, rf)?;
 
         let mut encoder = Encoder::with_type(Vec::with_capacity(data.len() + 512));
@@ -518,7 +803,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         Ok(encoder.into())
     }
 
-    // TODO: stack overflow
+    // Recursion is bounded by the depth limit the data was decoded with.
     fn visit(
         &mut self,
         v: Value,
@@ -637,18 +922,40 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
                 )?;
                 Ok(Value::Custom(ty, rid.to_vec()))
             }
-            SCRYPTO_TYPE_DECIMAL
-            | SCRYPTO_TYPE_BIG_DECIMAL
-            | SCRYPTO_TYPE_ADDRESS
-            | SCRYPTO_TYPE_H256
-            | SCRYPTO_TYPE_MID
-            | SCRYPTO_TYPE_VID => Ok(Value::Custom(ty, data)),
+            SCRYPTO_TYPE_MID => {
+                let mid = Mid::try_from(data.as_slice())
+                    .map_err(|_| RuntimeError::InvalidData(DecodeError::InvalidCustomData(ty)))?;
+                if self.track.get_lazy_map(mid).is_none() {
+                    return Err(RuntimeError::LazyMapNotFound(mid));
+                }
+                Ok(Value::Custom(ty, data))
+            }
+            SCRYPTO_TYPE_VID => {
+                let vid = Vid::try_from(data.as_slice())
+                    .map_err(|_| RuntimeError::InvalidData(DecodeError::InvalidCustomData(ty)))?;
+                if self.track.get_vault(vid).is_none() {
+                    return Err(RuntimeError::VaultNotFound(vid));
+                }
+                Ok(Value::Custom(ty, data))
+            }
+            SCRYPTO_TYPE_AMOUNT | SCRYPTO_TYPE_ADDRESS | SCRYPTO_TYPE_H256 => {
+                Ok(Value::Custom(ty, data))
+            }
             _ => Err(RuntimeError::InvalidData(DecodeError::InvalidCustomData(
                 ty,
             ))),
         }
     }
 
+    /// Returns an unlocked bucket to where it was borrowed from.
+    fn unlock_bucket(&mut self, bid: Bid, bucket: Bucket) {
+        if self.locked_temp_bids.remove(&bid) {
+            self.temp_buckets.insert(bid, bucket);
+        } else {
+            self.buckets.insert(bid, bucket);
+        }
+    }
+
     /// Remove transient buckets from this process
     fn move_buckets(&mut self, bid: Bid) -> Result<Bid, RuntimeError> {
         let bucket = self
@@ -728,9 +1035,19 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
     }
 
     /// Handle a kernel call.
-    fn handle<I: Decode + fmt::Debug, O: Encode + fmt::Debug>(
+    fn handle<I: DecodeOwned + fmt::Debug, O: Encode + fmt::Debug>(
+        &mut self,
+        args: RuntimeArgs,
+        handler: fn(&mut Self, input: I) -> Result<O, RuntimeError>,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        self.handle_with_limits(args, DECODE_LIMITS, handler)
+    }
+
+    /// Handle a kernel call, decoding its input within the given limits.
+    fn handle_with_limits<I: DecodeOwned + fmt::Debug, O: Encode + fmt::Debug>(
         &mut self,
         args: RuntimeArgs,
+        limits: DecodeLimits,
         handler: fn(&mut Self, input: I) -> Result<O, RuntimeError>,
     ) -> Result<Option<RuntimeValue>, Trap> {
         let op: u32 = args.nth_checked(0)?;
@@ -740,7 +1057,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             .memory()?
             .get(input_ptr, input_len as usize)
             .map_err(|e| Trap::from(RuntimeError::MemoryAccessError(e)))?;
-        let input: I = scrypto_decode(&input_bytes)
+        let input: I = sbor::decode_with_limits(&input_bytes, limits)
             .map_err(|e| Trap::from(RuntimeError::InvalidRequestData(e)))?;
         if input_len <= 1024 {
             trace!(self, "{:?}", input);
@@ -802,6 +1119,158 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         }
     }
 
+    /// Assigns the vaults and lazy maps referenced by the data, transitively, to a component.
+    ///
+    /// Those already owned by the component are kept, but none may be referenced twice.
+    fn claim_ownership(&mut self, data: &[u8], component: Address) -> Result<(), RuntimeError> {
+        let (mut vids, mut mids) = collect_ids(data).map_err(RuntimeError::InvalidData)?;
+
+        let mut claimed_mids = HashSet::new();
+        while let Some(mid) = mids.pop() {
+            let lazy_map = self
+                .track
+                .get_lazy_map_mut(mid)
+                .ok_or(RuntimeError::LazyMapNotFound(mid))?;
+            if !claimed_mids.insert(mid) {
+                return Err(RuntimeError::LazyMapError(LazyMapError::AlreadyOwned {
+                    owner: component,
+                    claimant: component,
+                }));
+            }
+            if lazy_map.owner() == Some(component) {
+                // entries are claimed when they are inserted into an owned lazy map
+                continue;
+            }
+            lazy_map
+                .set_owner(component)
+                .map_err(RuntimeError::LazyMapError)?;
+
+            let entries: Vec<Vec<u8>> = lazy_map
+                .map()
+                .iter()
+                .flat_map(|(k, v)| vec![k.clone(), v.clone()])
+                .collect();
+            for entry in entries {
+                let (v, m) = collect_ids(&entry).map_err(RuntimeError::InvalidData)?;
+                vids.extend(v);
+                mids.extend(m);
+            }
+        }
+
+        let mut claimed_vids = HashSet::new();
+        for vid in vids {
+            let vault = self
+                .track
+                .get_vault_mut(vid)
+                .ok_or(RuntimeError::VaultNotFound(vid))?;
+            if !claimed_vids.insert(vid) {
+                return Err(RuntimeError::VaultError(VaultError::AlreadyOwned {
+                    owner: component,
+                    claimant: component,
+                }));
+            }
+            if vault.owner() != Some(component) {
+                vault
+                    .set_owner(component)
+                    .map_err(RuntimeError::VaultError)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Charges a method royalty, paid in XRD by the caller.
+    ///
+    /// The royalty is taken from the buckets the caller keeps: the context's own buckets in a
+    /// transaction, or the buckets a blueprint holds in a nested call. Buckets passed as arguments
+    /// have already been moved out by then, and are never taken.
+    fn charge_royalty(
+        &mut self,
+        component: Address,
+        method: &str,
+        amount: Amount,
+    ) -> Result<(), RuntimeError> {
+        debug!(
+            self,
+            "Charging royalty: component = {:?}, method = {:?}, amount = {:?}",
+            component,
+            method,
+            amount
+        );
+        let vid = self
+            .track
+            .get_component(component)
+            .ok_or(RuntimeError::ComponentNotFound(component))?
+            .royalty_vault()
+            .ok_or(RuntimeError::ComponentError(
+                ComponentError::RoyaltyNotEnabled,
+            ))?;
+
+        self.withdraw_resource(amount.clone(), RADIX_TOKEN)
+            .map_err(|_| RuntimeError::InsufficientRoyalty(component, method.to_owned()))?;
+
+        self.track
+            .get_vault_mut(vid)
+            .ok_or(RuntimeError::VaultNotFound(vid))?
+            .put(Bucket::new(amount.clone(), RADIX_TOKEN), Auth::NoAuth)
+            .map_err(RuntimeError::VaultError)?;
+        self.track.add_royalty(component, method.to_owned(), amount);
+
+        Ok(())
+    }
+
+    /// Transfers the vaults and lazy maps referenced by any of the states, transitively, to a new package.
+    ///
+    /// Both the old and the new state are passed, so that nothing stays bound to the old package.
+    fn transfer_ownership(
+        &mut self,
+        states: &[&[u8]],
+        from: Address,
+        to: Address,
+    ) -> Result<(), RuntimeError> {
+        let mut vids = HashSet::new();
+        let mut mids = Vec::new();
+        for state in states {
+            let (v, m) = collect_ids(state).map_err(RuntimeError::InvalidData)?;
+            vids.extend(v);
+            mids.extend(m);
+        }
+
+        let mut visited = HashSet::new();
+        while let Some(mid) = mids.pop() {
+            if !visited.insert(mid) {
+                continue;
+            }
+            let lazy_map = self
+                .track
+                .get_lazy_map_mut(mid)
+                .ok_or(RuntimeError::LazyMapNotFound(mid))?;
+            lazy_map
+                .set_auth(to, Auth::PackageAuth(from))
+                .map_err(RuntimeError::LazyMapError)?;
+            let entries: Vec<Vec<u8>> = lazy_map
+                .map()
+                .iter()
+                .flat_map(|(k, v)| vec![k.clone(), v.clone()])
+                .collect();
+            for entry in entries {
+                let (v, m) = collect_ids(&entry).map_err(RuntimeError::InvalidData)?;
+                vids.extend(v);
+                mids.extend(m);
+            }
+        }
+
+        for vid in vids {
+            self.track
+                .get_vault_mut(vid)
+                .ok_or(RuntimeError::VaultNotFound(vid))?
+                .set_auth(to, Auth::PackageAuth(from))
+                .map_err(RuntimeError::VaultError)?;
+        }
+
+        Ok(())
+    }
+
     //============================
     // SYSTEM CALL HANDLERS START
     //============================
@@ -823,6 +1292,85 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         Ok(PublishPackageOutput { package: address })
     }
 
+    fn handle_publish_upgrade(
+        &mut self,
+        input: PublishPackageUpgradeInput,
+    ) -> Result<PublishPackageUpgradeOutput, RuntimeError> {
+        Self::expect_package_address(input.previous)?;
+        let auth = match input.upgrade_auth {
+            Some(rid) => {
+                let bucket_ref = self
+                    .bucket_refs
+                    .get(&rid)
+                    .ok_or(RuntimeError::BucketRefNotFound(rid))?;
+                self.badge_auth(bucket_ref)?
+            }
+            None => self.package_auth()?,
+        };
+
+        // only the previous package itself or the holder of its upgrade badge may publish
+        let upgrade_auth = self
+            .track
+            .get_package(input.previous)
+            .ok_or(RuntimeError::PackageNotFound(input.previous))?
+            .upgrade_auth();
+        if !auth.contains(input.previous) && !upgrade_auth.is_some_and(|a| auth.contains(a)) {
+            return Err(RuntimeError::UnauthorizedPackageUpgrade(input.previous));
+        }
+
+        // drop the input upgrade auth
+        if let Some(rid) = input.upgrade_auth {
+            self.handle_drop_bucket_ref(DropBucketRefInput { bucket_ref: rid })?;
+        }
+
+        let address = self.track.new_package_address();
+
+        if self.track.get_package(address).is_some() {
+            return Err(RuntimeError::PackageAlreadyExists(address));
+        }
+        validate_module(&input.code)?;
+
+        debug!(
+            self,
+            "New package version: {:?}, previous = {:?}", address, input.previous
+        );
+        self.track.put_package(
+            address,
+            Package::new_version(input.code, input.previous, upgrade_auth),
+        );
+
+        Ok(PublishPackageUpgradeOutput { package: address })
+    }
+
+    fn handle_enable_package_upgrade(
+        &mut self,
+        input: EnablePackageUpgradeInput,
+    ) -> Result<EnablePackageUpgradeOutput, RuntimeError> {
+        Self::expect_package_address(input.package)?;
+        Self::expect_resource_def_address(input.upgrade_auth)?;
+        if !self.package_auth()?.contains(input.package) {
+            return Err(RuntimeError::UnauthorizedPackageUpgrade(input.package));
+        }
+
+        self.track
+            .get_package_mut(input.package)
+            .ok_or(RuntimeError::PackageNotFound(input.package))?
+            .enable_upgrade(input.upgrade_auth);
+
+        Ok(EnablePackageUpgradeOutput {})
+    }
+
+    fn handle_get_blueprint_abi(
+        &mut self,
+        input: GetBlueprintAbiInput,
+    ) -> Result<GetBlueprintAbiOutput, RuntimeError> {
+        Self::expect_package_address(input.package)?;
+
+        let (functions, methods) = self.export_abi(input.package, &input.name)?;
+
+        Ok(GetBlueprintAbiOutput { functions, methods })
+    }
+
     fn handle_call_function(
         &mut self,
         input: CallFunctionInput,
@@ -889,6 +1437,8 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             "New component: address = {:?}, state = {:?}", address, new_state
         );
 
+        self.claim_ownership(&new_state, address)?;
+
         let component = Component::new(self.package()?, input.name, new_state);
         self.track.put_component(address, component);
 
@@ -950,12 +1500,284 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             .ok_or(RuntimeError::ComponentNotFound(input.component))?;
 
         component
-            .set_state(new_state, auth)
+            .set_state(new_state.clone(), auth)
             .map_err(RuntimeError::ComponentError)?;
 
+        self.claim_ownership(&new_state, input.component)?;
+
         Ok(PutComponentStateOutput {})
     }
 
+    fn handle_get_component_metadata(
+        &mut self,
+        input: GetComponentMetadataInput,
+    ) -> Result<GetComponentMetadataOutput, RuntimeError> {
+        Self::expect_component_address(input.component)?;
+
+        let component = self
+            .track
+            .get_component(input.component)
+            .ok_or(RuntimeError::ComponentNotFound(input.component))?;
+
+        Ok(GetComponentMetadataOutput {
+            metadata: component.metadata().clone(),
+        })
+    }
+
+    fn handle_put_component_metadata(
+        &mut self,
+        input: PutComponentMetadataInput,
+    ) -> Result<PutComponentMetadataOutput, RuntimeError> {
+        Self::expect_component_address(input.component)?;
+        let auth = match input.auth {
+            Some(rid) => {
+                let bucket_ref = self
+                    .bucket_refs
+                    .get(&rid)
+                    .ok_or(RuntimeError::BucketRefNotFound(rid))?;
+                self.badge_auth(bucket_ref)?
+            }
+            None => self.package_auth()?,
+        };
+
+        let component = self
+            .track
+            .get_component_mut(input.component)
+            .ok_or(RuntimeError::ComponentNotFound(input.component))?;
+        component
+            .set_metadata(input.metadata, auth)
+            .map_err(RuntimeError::ComponentError)?;
+
+        // drop the input owner auth
+        if let Some(rid) = input.auth {
+            self.handle_drop_bucket_ref(DropBucketRefInput { bucket_ref: rid })?;
+        }
+
+        Ok(PutComponentMetadataOutput {})
+    }
+
+    fn handle_set_component_owner(
+        &mut self,
+        input: SetComponentOwnerInput,
+    ) -> Result<SetComponentOwnerOutput, RuntimeError> {
+        Self::expect_component_address(input.component)?;
+        Self::expect_resource_def_address(input.owner)?;
+        let auth = self.package_auth()?;
+
+        let component = self
+            .track
+            .get_component_mut(input.component)
+            .ok_or(RuntimeError::ComponentNotFound(input.component))?;
+        component
+            .set_owner(input.owner, auth)
+            .map_err(RuntimeError::ComponentError)?;
+
+        Ok(SetComponentOwnerOutput {})
+    }
+
+    fn handle_set_component_royalties(
+        &mut self,
+        input: SetComponentRoyaltiesInput,
+    ) -> Result<SetComponentRoyaltiesOutput, RuntimeError> {
+        Self::expect_component_address(input.component)?;
+        Self::expect_resource_def_address(input.royalty_auth)?;
+        let auth = self.package_auth()?;
+
+        let existing = self
+            .track
+            .get_component(input.component)
+            .ok_or(RuntimeError::ComponentNotFound(input.component))?
+            .royalty_vault();
+        let vid = match existing {
+            Some(vid) => vid,
+            None => {
+                // the royalty vault is only accessible by the engine
+                let vid = self.track.new_vid();
+                let mut vault =
+                    Vault::new(Bucket::new(Amount::zero(), RADIX_TOKEN), input.component);
+                vault
+                    .set_owner(input.component)
+                    .map_err(RuntimeError::VaultError)?;
+                self.track.put_vault(vid, vault);
+                vid
+            }
+        };
+
+        let component = self
+            .track
+            .get_component_mut(input.component)
+            .ok_or(RuntimeError::ComponentNotFound(input.component))?;
+        component
+            .set_royalties(input.royalties, input.royalty_auth, vid, auth)
+            .map_err(RuntimeError::ComponentError)?;
+
+        Ok(SetComponentRoyaltiesOutput {})
+    }
+
+    fn handle_claim_component_royalties(
+        &mut self,
+        input: ClaimComponentRoyaltiesInput,
+    ) -> Result<ClaimComponentRoyaltiesOutput, RuntimeError> {
+        Self::expect_component_address(input.component)?;
+
+        // take everything from the royalty vault
+        let bucket = {
+            let bucket_ref = self
+                .bucket_refs
+                .get(&input.royalty_auth)
+                .ok_or(RuntimeError::BucketRefNotFound(input.royalty_auth))?;
+            let auth = self.badge_auth(bucket_ref)?;
+
+            let vid = self
+                .track
+                .get_component(input.component)
+                .ok_or(RuntimeError::ComponentNotFound(input.component))?
+                .claim_royalties(auth)
+                .map_err(RuntimeError::ComponentError)?;
+            let vault = self
+                .track
+                .get_vault_mut(vid)
+                .ok_or(RuntimeError::VaultNotFound(vid))?;
+            let amount = vault
+                .amount(Auth::NoAuth)
+                .map_err(RuntimeError::VaultError)?;
+            vault
+                .take(amount, Auth::NoAuth)
+                .map_err(RuntimeError::VaultError)?
+        };
+        // drop the input royalty auth
+        self.handle_drop_bucket_ref(DropBucketRefInput {
+            bucket_ref: input.royalty_auth,
+        })?;
+
+        let bid = self.track.new_bid();
+        self.buckets.insert(bid, bucket);
+
+        Ok(ClaimComponentRoyaltiesOutput { bucket: bid })
+    }
+
+    fn handle_enable_component_upgrade(
+        &mut self,
+        input: EnableComponentUpgradeInput,
+    ) -> Result<EnableComponentUpgradeOutput, RuntimeError> {
+        Self::expect_component_address(input.component)?;
+        Self::expect_resource_def_address(input.upgrade_auth)?;
+        let auth = self.package_auth()?;
+
+        let component = self
+            .track
+            .get_component_mut(input.component)
+            .ok_or(RuntimeError::ComponentNotFound(input.component))?;
+
+        component
+            .enable_upgrade(input.upgrade_auth, auth)
+            .map_err(RuntimeError::ComponentError)?;
+
+        Ok(EnableComponentUpgradeOutput {})
+    }
+
+    fn handle_migrate_component(
+        &mut self,
+        input: MigrateComponentInput,
+    ) -> Result<MigrateComponentOutput, RuntimeError> {
+        Self::expect_component_address(input.component)?;
+        Self::expect_package_address(input.package)?;
+
+        let (old_package, name, old_state) = {
+            let bucket_ref = self
+                .bucket_refs
+                .get(&input.upgrade_auth)
+                .ok_or(RuntimeError::BucketRefNotFound(input.upgrade_auth))?;
+            let auth = self.badge_auth(bucket_ref)?;
+
+            // check the upgrade badge before running any code of the new package
+            let component = self
+                .track
+                .get_component(input.component)
+                .ok_or(RuntimeError::ComponentNotFound(input.component))?;
+            component
+                .check_upgrade(&auth)
+                .map_err(RuntimeError::ComponentError)?;
+            (
+                component.package(),
+                component.name().to_owned(),
+                component
+                    .state(Auth::NoAuth)
+                    .map_err(RuntimeError::ComponentError)?
+                    .to_vec(),
+            )
+        };
+
+        // the target package must be a descendant of the current package
+        let mut previous = self
+            .track
+            .get_package(input.package)
+            .ok_or(RuntimeError::PackageNotFound(input.package))?
+            .previous();
+        while previous != Some(old_package) {
+            previous = match previous {
+                Some(p) => self
+                    .track
+                    .get_package(p)
+                    .ok_or(RuntimeError::PackageNotFound(p))?
+                    .previous(),
+                None => {
+                    return Err(RuntimeError::InvalidPackageUpgrade(
+                        old_package,
+                        input.package,
+                    ));
+                }
+            };
+        }
+
+        // run the state migration hook of the new blueprint
+        debug!(
+            self,
+            "Migrating component: address = {:?}, from = {:?}, to = {:?}",
+            input.component,
+            old_package,
+            input.package
+        );
+        let invocation = self.prepare_call_function(
+            input.package,
+            &name,
+            "migrate",
+            vec![scrypto_encode(&old_state)],
+        )?;
+        let rtn = self.call(invocation)?;
+        let new_state: Vec<u8> = decode_untrusted(&rtn).map_err(RuntimeError::InvalidData)?;
+        let new_state =
+            self.process_data(&new_state, Self::reject_buckets, Self::reject_bucket_refs)?;
+        debug!(self, "Migrated state: {:?}", new_state);
+
+        // update component
+        {
+            let bucket_ref = self
+                .bucket_refs
+                .get(&input.upgrade_auth)
+                .ok_or(RuntimeError::BucketRefNotFound(input.upgrade_auth))?;
+            let auth = self.badge_auth(bucket_ref)?;
+
+            let component = self
+                .track
+                .get_component_mut(input.component)
+                .ok_or(RuntimeError::ComponentNotFound(input.component))?;
+            component
+                .migrate(input.package, new_state.clone(), auth)
+                .map_err(RuntimeError::ComponentError)?;
+        }
+        // drop the input upgrade auth
+        self.handle_drop_bucket_ref(DropBucketRefInput {
+            bucket_ref: input.upgrade_auth,
+        })?;
+
+        // hand over the vaults and lazy maps owned by the component
+        self.claim_ownership(&new_state, input.component)?;
+        self.transfer_ownership(&[&old_state, &new_state], old_package, input.package)?;
+
+        Ok(MigrateComponentOutput {})
+    }
+
     fn handle_create_lazy_map(
         &mut self,
         _input: CreateLazyMapInput,
@@ -976,11 +1798,15 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         input: GetLazyMapEntryInput,
     ) -> Result<GetLazyMapEntryOutput, RuntimeError> {
         let auth = self.package_auth()?;
+        let component = self.component()?;
 
         let lazy_map = self
             .track
             .get_lazy_map(input.lazy_map)
             .ok_or(RuntimeError::LazyMapNotFound(input.lazy_map))?;
+        lazy_map
+            .check_owner(component)
+            .map_err(RuntimeError::LazyMapError)?;
 
         let value = lazy_map
             .get_entry(&input.key, auth)
@@ -996,6 +1822,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         input: PutLazyMapEntryInput,
     ) -> Result<PutLazyMapEntryOutput, RuntimeError> {
         let auth = self.package_auth()?;
+        let component = self.component()?;
 
         let new_key =
             self.process_data(&input.key, Self::reject_buckets, Self::reject_bucket_refs)?;
@@ -1010,9 +1837,18 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
             .ok_or(RuntimeError::LazyMapNotFound(input.lazy_map))?;
 
         lazy_map
-            .set_entry(new_key, new_value, auth)
+            .check_owner(component)
+            .map_err(RuntimeError::LazyMapError)?;
+        let owner = lazy_map.owner();
+        lazy_map
+            .set_entry(new_key.clone(), new_value.clone(), auth)
             .map_err(RuntimeError::LazyMapError)?;
 
+        if let Some(component) = owner {
+            self.claim_ownership(&new_key, component)?;
+            self.claim_ownership(&new_value, component)?;
+        }
+
         Ok(PutLazyMapEntryOutput {})
     }
 
@@ -1042,7 +1878,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         &mut self,
         input: CreateResourceFixedInput,
     ) -> Result<CreateResourceFixedOutput, RuntimeError> {
-        let resource_def = ResourceDef::new(input.metadata, input.supply, None);
+        let resource_def = ResourceDef::new(input.metadata, input.supply.clone(), None);
 
         let address = self.track.new_resource_def_address();
 
@@ -1133,7 +1969,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
                 .get_resource_def_mut(input.resource_def)
                 .ok_or(RuntimeError::ResourceDefNotFound(input.resource_def))?;
             definition
-                .mint(input.amount, auth)
+                .mint(input.amount.clone(), auth)
                 .map_err(RuntimeError::ResourceDefError)?;
         }
         // drop the input mint auth
@@ -1187,17 +2023,21 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         input: PutIntoVaultInput,
     ) -> Result<PutIntoVaultOutput, RuntimeError> {
         let auth = self.package_auth()?;
+        let component = self.component()?;
+
+        let vault = self
+            .track
+            .get_vault_mut(input.vault)
+            .ok_or(RuntimeError::VaultNotFound(input.vault))?;
+        vault
+            .check_owner(component)
+            .map_err(RuntimeError::VaultError)?;
 
         let other = self
             .buckets
             .remove(&input.bucket)
             .ok_or(RuntimeError::BucketNotFound(input.bucket))?;
-
-        self.track
-            .get_vault_mut(input.vault)
-            .ok_or(RuntimeError::VaultNotFound(input.vault))?
-            .put(other, auth)
-            .map_err(RuntimeError::VaultError)?;
+        vault.put(other, auth).map_err(RuntimeError::VaultError)?;
 
         Ok(PutIntoVaultOutput {})
     }
@@ -1207,11 +2047,16 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         input: TakeFromVaultInput,
     ) -> Result<TakeFromVaultOutput, RuntimeError> {
         let auth = self.package_auth()?;
+        let component = self.component()?;
 
-        let new_bucket = self
+        let vault = self
             .track
             .get_vault_mut(input.vault)
-            .ok_or(RuntimeError::VaultNotFound(input.vault))?
+            .ok_or(RuntimeError::VaultNotFound(input.vault))?;
+        vault
+            .check_owner(component)
+            .map_err(RuntimeError::VaultError)?;
+        let new_bucket = vault
             .take(input.amount, auth)
             .map_err(RuntimeError::VaultError)?;
 
@@ -1226,11 +2071,15 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         input: GetVaultAmountInput,
     ) -> Result<GetVaultAmountOutput, RuntimeError> {
         let auth = self.package_auth()?;
+        let component = self.component()?;
 
         let vault = self
             .track
             .get_vault(input.vault)
             .ok_or(RuntimeError::VaultNotFound(input.vault))?;
+        vault
+            .check_owner(component)
+            .map_err(RuntimeError::VaultError)?;
 
         Ok(GetVaultAmountOutput {
             amount: vault.amount(auth).map_err(RuntimeError::VaultError)?,
@@ -1242,11 +2091,15 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
         input: GetVaultResourceAddressInput,
     ) -> Result<GetVaultResourceAddressOutput, RuntimeError> {
         let auth = self.package_auth()?;
+        let component = self.component()?;
 
         let vault = self
             .track
             .get_vault(input.vault)
             .ok_or(RuntimeError::VaultNotFound(input.vault))?;
+        vault
+            .check_owner(component)
+            .map_err(RuntimeError::VaultError)?;
 
         Ok(GetVaultResourceAddressOutput {
             resource_def: vault.resource_def(auth).map_err(RuntimeError::VaultError)?,
@@ -1378,7 +2231,7 @@ impl<'r, 'l, L: Ledger> Process<'r, 'l, L> {
 
         if count == 1 {
             if let Some(b) = self.locked_buckets.remove(&bid) {
-                self.buckets.insert(bid, Rc::try_unwrap(b).unwrap().into());
+                self.unlock_bucket(bid, Rc::try_unwrap(b).unwrap().into());
             }
         }
 
@@ -1489,7 +2342,18 @@ impl<'r, 'l, L: Ledger> Externals for Process<'r, 'l, L> {
             KERNEL_INDEX => {
                 let operation: u32 = args.nth_checked(0)?;
                 match operation {
-                    PUBLISH_PACKAGE => self.handle(args, Self::handle_publish),
+                    PUBLISH_PACKAGE => {
+                        self.handle_with_limits(args, PACKAGE_DECODE_LIMITS, Self::handle_publish)
+                    }
+                    PUBLISH_PACKAGE_UPGRADE => self.handle_with_limits(
+                        args,
+                        PACKAGE_DECODE_LIMITS,
+                        Self::handle_publish_upgrade,
+                    ),
+                    GET_BLUEPRINT_ABI => self.handle(args, Self::handle_get_blueprint_abi),
+                    ENABLE_PACKAGE_UPGRADE => {
+                        self.handle(args, Self::handle_enable_package_upgrade)
+                    }
                     CALL_FUNCTION => self.handle(args, Self::handle_call_function),
                     CALL_METHOD => self.handle(args, Self::handle_call_method),
 
@@ -1499,6 +2363,23 @@ impl<'r, 'l, L: Ledger> Externals for Process<'r, 'l, L> {
                     }
                     GET_COMPONENT_STATE => self.handle(args, Self::handle_get_component_state),
                     PUT_COMPONENT_STATE => self.handle(args, Self::handle_put_component_state),
+                    ENABLE_COMPONENT_UPGRADE => {
+                        self.handle(args, Self::handle_enable_component_upgrade)
+                    }
+                    MIGRATE_COMPONENT => self.handle(args, Self::handle_migrate_component),
+                    GET_COMPONENT_METADATA => {
+                        self.handle(args, Self::handle_get_component_metadata)
+                    }
+                    PUT_COMPONENT_METADATA => {
+                        self.handle(args, Self::handle_put_component_metadata)
+                    }
+                    SET_COMPONENT_OWNER => self.handle(args, Self::handle_set_component_owner),
+                    SET_COMPONENT_ROYALTIES => {
+                        self.handle(args, Self::handle_set_component_royalties)
+                    }
+                    CLAIM_COMPONENT_ROYALTIES => {
+                        self.handle(args, Self::handle_claim_component_royalties)
+                    }
 
                     CREATE_LAZY_MAP => self.handle(args, Self::handle_create_lazy_map),
                     GET_LAZY_MAP_ENTRY => self.handle(args, Self::handle_get_lazy_map_entry),
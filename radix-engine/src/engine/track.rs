@@ -1,4 +1,5 @@
 use lru::LruCache;
+use scrypto::abi;
 use scrypto::rust::collections::*;
 use scrypto::rust::string::String;
 use scrypto::rust::vec::Vec;
@@ -8,6 +9,7 @@ use wasmi::*;
 use crate::engine::*;
 use crate::ledger::*;
 use crate::model::*;
+use crate::utils::*;
 
 /// An abstraction of transaction execution state.
 ///
@@ -23,6 +25,7 @@ pub struct Track<'l, L: Ledger> {
     tx_signers: Vec<Address>,
     id_alloc: IdAllocator,
     logs: Vec<(Level, String)>,
+    royalties: Vec<(Address, String, Amount)>,
     packages: HashMap<Address, Package>,
     components: HashMap<Address, Component>,
     resource_defs: HashMap<Address, ResourceDef>,
@@ -34,7 +37,9 @@ pub struct Track<'l, L: Ledger> {
     updated_resource_defs: HashSet<Address>,
     updated_vaults: HashSet<Vid>,
     new_entities: Vec<Address>,
+    new_vaults: Vec<Vid>,
     code_cache: LruCache<Address, Module>, // TODO: move to ledger level
+    abi_cache: HashMap<(Address, String), (Vec<abi::Function>, Vec<abi::Method>)>,
 }
 
 impl<'l, L: Ledger> Track<'l, L> {
@@ -51,6 +56,7 @@ impl<'l, L: Ledger> Track<'l, L> {
             tx_signers,
             id_alloc: IdAllocator::new(),
             logs: Vec::new(),
+            royalties: Vec::new(),
             packages: HashMap::new(),
             components: HashMap::new(),
             resource_defs: HashMap::new(),
@@ -62,7 +68,9 @@ impl<'l, L: Ledger> Track<'l, L> {
             updated_resource_defs: HashSet::new(),
             updated_vaults: HashSet::new(),
             new_entities: Vec::new(),
+            new_vaults: Vec::new(),
             code_cache: LruCache::new(1024),
+            abi_cache: HashMap::new(),
         }
     }
 
@@ -91,6 +99,11 @@ impl<'l, L: Ledger> Track<'l, L> {
         &self.logs
     }
 
+    /// Returns the royalties charged so far.
+    pub fn royalties(&self) -> &Vec<(Address, String, Amount)> {
+        &self.royalties
+    }
+
     /// Returns new entities created so far.
     pub fn new_entities(&self) -> &[Address] {
         &self.new_entities
@@ -101,6 +114,11 @@ impl<'l, L: Ledger> Track<'l, L> {
         self.logs.push((level, message));
     }
 
+    /// Records a royalty charged for calling a component method.
+    pub fn add_royalty(&mut self, component: Address, method: String, amount: Amount) {
+        self.royalties.push((component, method, amount));
+    }
+
     /// Loads a module.
     pub fn load_module(&mut self, address: Address) -> Option<(ModuleRef, MemoryRef)> {
         match self.get_package(address).map(Clone::clone) {
@@ -118,6 +136,25 @@ impl<'l, L: Ledger> Track<'l, L> {
         }
     }
 
+    /// Returns the exported ABI of a blueprint, if cached.
+    pub fn get_abi(
+        &self,
+        package: Address,
+        name: &str,
+    ) -> Option<&(Vec<abi::Function>, Vec<abi::Method>)> {
+        self.abi_cache.get(&(package, name.to_owned()))
+    }
+
+    /// Caches the exported ABI of a blueprint, which never changes as package code is immutable.
+    pub fn put_abi(
+        &mut self,
+        package: Address,
+        name: String,
+        abi: (Vec<abi::Function>, Vec<abi::Method>),
+    ) {
+        self.abi_cache.insert((package, name), abi);
+    }
+
     /// Returns an immutable reference to a package, if exists.
     pub fn get_package(&mut self, address: Address) -> Option<&Package> {
         if self.packages.contains_key(&address) {
@@ -301,6 +338,7 @@ impl<'l, L: Ledger> Track<'l, L> {
     /// Inserts a new vault.
     pub fn put_vault(&mut self, vid: Vid, vault: Vault) {
         self.updated_vaults.insert(vid);
+        self.new_vaults.push(vid);
 
         self.vaults.insert(vid, vault);
     }
@@ -346,8 +384,143 @@ impl<'l, L: Ledger> Track<'l, L> {
         self.id_alloc.new_mid(self.tx_hash())
     }
 
+    /// Returns the vaults and lazy maps reachable from a component.
+    fn reachable_ids(
+        &mut self,
+        component: Address,
+    ) -> Result<(HashSet<Vid>, HashSet<Mid>), RuntimeError> {
+        let c = self
+            .get_component(component)
+            .ok_or(RuntimeError::ComponentNotFound(component))?;
+        let royalty_vault = c.royalty_vault();
+        let state = c
+            .state(Auth::NoAuth)
+            .map_err(RuntimeError::ComponentError)?
+            .to_vec();
+
+        let mut vids = HashSet::new();
+        let mut mids = HashSet::new();
+        vids.extend(royalty_vault);
+        let (v, mut queue) = collect_ids(&state).map_err(RuntimeError::InvalidData)?;
+        vids.extend(v);
+
+        while let Some(mid) = queue.pop() {
+            if !mids.insert(mid) {
+                continue;
+            }
+            let lazy_map = self
+                .get_lazy_map(mid)
+                .ok_or(RuntimeError::LazyMapNotFound(mid))?;
+            let entries: Vec<Vec<u8>> = lazy_map
+                .map()
+                .iter()
+                .flat_map(|(k, v)| vec![k.clone(), v.clone()])
+                .collect();
+            for entry in entries {
+                let (v, m) = collect_ids(&entry).map_err(RuntimeError::InvalidData)?;
+                vids.extend(v);
+                queue.extend(m);
+            }
+        }
+
+        Ok((vids, mids))
+    }
+
+    /// Checks that every vault created in this transaction is reachable from its owner.
+    fn check_orphaned_vaults(&mut self) -> Result<(), RuntimeError> {
+        let mut reachable: HashMap<Address, HashSet<Vid>> = HashMap::new();
+        let mut orphans = Vec::new();
+
+        for vid in self.new_vaults.clone() {
+            let owner = self
+                .get_vault(vid)
+                .ok_or(RuntimeError::VaultNotFound(vid))?
+                .owner();
+            let found = match owner {
+                Some(component) => {
+                    if let hash_map::Entry::Vacant(e) = reachable.entry(component) {
+                        e.insert(self.reachable_ids(component)?.0);
+                    }
+                    reachable[&component].contains(&vid)
+                }
+                None => false,
+            };
+
+            if !found {
+                let vault = self.vaults.get(&vid).unwrap();
+                orphans.push((
+                    vid,
+                    vault.resource_def(Auth::NoAuth).unwrap(),
+                    vault.amount(Auth::NoAuth).unwrap(),
+                ));
+            }
+        }
+
+        if orphans.is_empty() {
+            Ok(())
+        } else {
+            Err(RuntimeError::OrphanedVaults(orphans))
+        }
+    }
+
+    /// Checks that the total supply of each resource touched in this transaction equals the
+    /// total balance of all vaults holding it, on ledger and in this track.
+    ///
+    /// All buckets must have been put into vaults or burnt by the time of commit.
+    ///
+    /// This guards the engine's own accounting, not transactions, and it reads every vault on
+    /// the ledger, so it is only run in debug builds.
+    #[cfg(debug_assertions)]
+    fn check_resource_conservation(&self) -> Result<(), RuntimeError> {
+        let mut supplies: HashMap<Address, Amount> = HashMap::new();
+        for address in &self.updated_resource_defs {
+            supplies.insert(*address, self.resource_defs[address].supply());
+        }
+        for vid in &self.updated_vaults {
+            let resource_def = self.vaults[vid].resource_def(Auth::NoAuth).unwrap();
+            if let hash_map::Entry::Vacant(e) = supplies.entry(resource_def) {
+                let resource_def = self
+                    .ledger
+                    .get_resource_def(resource_def)
+                    .ok_or(RuntimeError::ResourceDefNotFound(resource_def))?;
+                e.insert(resource_def.supply());
+            }
+        }
+
+        let mut balances: HashMap<Address, Amount> = HashMap::new();
+        let ledger_vids = self.ledger.list_vaults();
+        let untouched = ledger_vids
+            .iter()
+            .filter(|vid| !self.vaults.contains_key(vid));
+        let vaults = untouched
+            .filter_map(|vid| self.ledger.get_vault(*vid))
+            .chain(self.vaults.values().cloned());
+        for vault in vaults {
+            let resource_def = vault.resource_def(Auth::NoAuth).unwrap();
+            if supplies.contains_key(&resource_def) {
+                *balances.entry(resource_def).or_insert_with(Amount::zero) +=
+                    vault.amount(Auth::NoAuth).unwrap();
+            }
+        }
+
+        for (resource_def, supply) in supplies {
+            let balance = balances.remove(&resource_def).unwrap_or_else(Amount::zero);
+            if balance != supply {
+                return Err(RuntimeError::ResourceNotConserved(resource_def));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Commits changes to the underlying ledger.
-    pub fn commit(&mut self) {
+    ///
+    /// Nothing is written if any vault created in this transaction has become unreachable.
+    pub fn commit(&mut self) -> Result<(), RuntimeError> {
+        self.check_orphaned_vaults()?;
+        #[cfg(debug_assertions)]
+        self.check_resource_conservation()?;
+
         for address in self.updated_packages.clone() {
             self.ledger
                 .put_package(address, self.packages.get(&address).unwrap().clone());
@@ -372,5 +545,7 @@ impl<'l, L: Ledger> Track<'l, L> {
             self.ledger
                 .put_vault(vault, self.vaults.get(&vault).unwrap().clone());
         }
+
+        Ok(())
     }
 }
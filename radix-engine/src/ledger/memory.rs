@@ -1,4 +1,5 @@
 use scrypto::rust::collections::HashMap;
+use scrypto::rust::vec::Vec;
 use scrypto::types::*;
 
 use crate::ledger::*;
@@ -78,4 +79,8 @@ impl Ledger for InMemoryLedger {
     fn put_vault(&mut self, vid: Vid, vault: Vault) {
         self.vaults.insert(vid, vault);
     }
+
+    fn list_vaults(&self) -> Vec<Vid> {
+        self.vaults.keys().copied().collect()
+    }
 }
@@ -2,6 +2,7 @@ use sbor::*;
 use scrypto::buffer::*;
 use scrypto::rust::borrow::ToOwned;
 use scrypto::rust::collections::*;
+use scrypto::rust::vec::Vec;
 use scrypto::types::*;
 
 use crate::model::*;
@@ -42,6 +43,8 @@ pub trait Ledger {
 
     fn put_vault(&mut self, vid: Vid, vault: Vault);
 
+    fn list_vaults(&self) -> Vec<Vid>;
+
     fn bootstrap(&mut self) {
         if self.get_package(SYSTEM_PACKAGE).is_none() {
             // System package
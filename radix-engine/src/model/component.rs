@@ -1,4 +1,5 @@
 use sbor::*;
+use scrypto::rust::collections::HashMap;
 use scrypto::rust::string::String;
 use scrypto::rust::vec::Vec;
 use scrypto::types::*;
@@ -9,15 +10,29 @@ use crate::model::Auth;
 #[derive(Debug, Clone)]
 pub enum ComponentError {
     UnauthorizedAccess,
+    UpgradeNotAllowed,
+    RoyaltyNotEnabled,
 }
 
 /// A component is an instance of blueprint.
-#[derive(Debug, TypeId, Encode, Decode)]
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
 pub struct Component {
     package: Address,
     name: String,
     state: Vec<u8>,
     auth: Address,
+    #[sbor(default)]
+    upgrade_auth: Option<Address>,
+    #[sbor(default)]
+    metadata: HashMap<String, String>,
+    #[sbor(default)]
+    owner: Option<Address>,
+    #[sbor(default)]
+    royalties: HashMap<String, Amount>,
+    #[sbor(default)]
+    royalty_auth: Option<Address>,
+    #[sbor(default)]
+    royalty_vault: Option<Vid>,
 }
 
 impl Component {
@@ -29,6 +44,12 @@ impl Component {
             name,
             state,
             auth: package,
+            upgrade_auth: None,
+            metadata: HashMap::new(),
+            owner: None,
+            royalties: HashMap::new(),
+            royalty_auth: None,
+            royalty_vault: None,
         }
     }
 
@@ -40,6 +61,125 @@ impl Component {
         &self.name
     }
 
+    pub fn upgrade_auth(&self) -> Option<Address> {
+        self.upgrade_auth
+    }
+
+    /// Opts in to upgrades, which are authorized by the given badge.
+    pub fn enable_upgrade(
+        &mut self,
+        upgrade_auth: Address,
+        auth: Auth,
+    ) -> Result<(), ComponentError> {
+        if auth.contains(self.auth) {
+            self.upgrade_auth = Some(upgrade_auth);
+            Ok(())
+        } else {
+            Err(ComponentError::UnauthorizedAccess)
+        }
+    }
+
+    /// Checks that an upgrade of this component is authorized by the upgrade badge.
+    pub fn check_upgrade(&self, auth: &Auth) -> Result<(), ComponentError> {
+        match self.upgrade_auth {
+            Some(a) => {
+                if auth.contains(a) {
+                    Ok(())
+                } else {
+                    Err(ComponentError::UnauthorizedAccess)
+                }
+            }
+            None => Err(ComponentError::UpgradeNotAllowed),
+        }
+    }
+
+    /// Moves this component to a newer blueprint version, replacing its state.
+    pub fn migrate(
+        &mut self,
+        package: Address,
+        new_state: Vec<u8>,
+        auth: Auth,
+    ) -> Result<(), ComponentError> {
+        assert!(package.is_package());
+
+        self.check_upgrade(&auth)?;
+        self.package = package;
+        self.state = new_state;
+        self.auth = package;
+        Ok(())
+    }
+
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    /// Updates metadata, authorized by either the blueprint or the owner badge.
+    pub fn set_metadata(
+        &mut self,
+        metadata: HashMap<String, String>,
+        auth: Auth,
+    ) -> Result<(), ComponentError> {
+        if auth.contains(self.auth) || self.owner.is_some_and(|a| auth.contains(a)) {
+            self.metadata = metadata;
+            Ok(())
+        } else {
+            Err(ComponentError::UnauthorizedAccess)
+        }
+    }
+
+    pub fn owner(&self) -> Option<Address> {
+        self.owner
+    }
+
+    pub fn set_owner(&mut self, owner: Address, auth: Auth) -> Result<(), ComponentError> {
+        if auth.contains(self.auth) {
+            self.owner = Some(owner);
+            Ok(())
+        } else {
+            Err(ComponentError::UnauthorizedAccess)
+        }
+    }
+
+    /// Returns the royalty charged for calling the given method, if any.
+    pub fn royalty(&self, method: &str) -> Option<Amount> {
+        self.royalties.get(method).cloned()
+    }
+
+    pub fn royalty_vault(&self) -> Option<Vid> {
+        self.royalty_vault
+    }
+
+    pub fn set_royalties(
+        &mut self,
+        royalties: HashMap<String, Amount>,
+        royalty_auth: Address,
+        royalty_vault: Vid,
+        auth: Auth,
+    ) -> Result<(), ComponentError> {
+        if auth.contains(self.auth) {
+            self.royalties = royalties;
+            self.royalty_auth = Some(royalty_auth);
+            self.royalty_vault = Some(royalty_vault);
+            Ok(())
+        } else {
+            Err(ComponentError::UnauthorizedAccess)
+        }
+    }
+
+    /// Returns the royalty vault, if authorized by the royalty badge.
+    pub fn claim_royalties(&self, auth: Auth) -> Result<Vid, ComponentError> {
+        match (self.royalty_auth, self.royalty_vault) {
+            (Some(a), Some(vid)) => {
+                if auth.contains(a) {
+                    Ok(vid)
+                } else {
+                    Err(ComponentError::UnauthorizedAccess)
+                }
+            }
+            _ => Err(ComponentError::RoyaltyNotEnabled),
+        }
+    }
+
     pub fn state(&self, auth: Auth) -> Result<&[u8], ComponentError> {
         if auth.contains(self.auth) {
             Ok(&self.state)
@@ -1,3 +1,4 @@
+use sbor::*;
 use scrypto::rust::collections::*;
 use scrypto::rust::vec::Vec;
 use scrypto::types::Address;
@@ -8,13 +9,17 @@ use crate::model::Auth;
 #[derive(Debug, Clone)]
 pub enum LazyMapError {
     UnauthorizedAccess,
+    AlreadyOwned { owner: Address, claimant: Address },
+    NotOwner(Address),
 }
 
 /// A key-value map where keys and values are lazily loaded on-demand.
-#[derive(Debug)]
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
 pub struct LazyMap {
     map: HashMap<Vec<u8>, Vec<u8>>,
     auth: Address,
+    #[sbor(default)]
+    owner: Option<Address>,
 }
 
 impl LazyMap {
@@ -22,6 +27,7 @@ impl LazyMap {
         Self {
             map: HashMap::new(),
             auth,
+            owner: None,
         }
     }
 
@@ -51,4 +57,43 @@ impl LazyMap {
             Err(LazyMapError::UnauthorizedAccess)
         }
     }
+
+    /// Transfers access to a new owner, e.g. a newer package version.
+    ///
+    /// Transferring to the current owner is a no-op.
+    pub fn set_auth(&mut self, new_auth: Address, auth: Auth) -> Result<(), LazyMapError> {
+        if self.auth == new_auth || auth.contains(self.auth) {
+            self.auth = new_auth;
+            Ok(())
+        } else {
+            Err(LazyMapError::UnauthorizedAccess)
+        }
+    }
+
+    /// Returns the component which owns this lazy map, if any.
+    pub fn owner(&self) -> Option<Address> {
+        self.owner
+    }
+
+    /// Assigns this lazy map to a component. It can only be claimed once.
+    pub fn set_owner(&mut self, owner: Address) -> Result<(), LazyMapError> {
+        match self.owner {
+            Some(a) => Err(LazyMapError::AlreadyOwned {
+                owner: a,
+                claimant: owner,
+            }),
+            None => {
+                self.owner = Some(owner);
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks that this lazy map is accessed by its owner, if it has one.
+    pub fn check_owner(&self, component: Option<Address>) -> Result<(), LazyMapError> {
+        match self.owner {
+            Some(a) if component != Some(a) => Err(LazyMapError::NotOwner(a)),
+            _ => Ok(()),
+        }
+    }
 }
@@ -1,18 +1,53 @@
 use sbor::*;
 use scrypto::rust::vec::Vec;
+use scrypto::types::*;
 
 /// A collection of blueprints, compiled and published as a single unit.
 #[derive(Debug, Clone, TypeId, Encode, Decode)]
 pub struct Package {
     code: Vec<u8>,
+    #[sbor(default)]
+    previous: Option<Address>,
+    #[sbor(default)]
+    upgrade_auth: Option<Address>,
 }
 
 impl Package {
     pub fn new(code: Vec<u8>) -> Self {
-        Self { code }
+        Self {
+            code,
+            previous: None,
+            upgrade_auth: None,
+        }
+    }
+
+    /// Creates a package which is a newer version of the `previous` package.
+    ///
+    /// The upgrade badge is inherited, so that later versions can be published the same way.
+    pub fn new_version(code: Vec<u8>, previous: Address, upgrade_auth: Option<Address>) -> Self {
+        assert!(previous.is_package());
+
+        Self {
+            code,
+            previous: Some(previous),
+            upgrade_auth,
+        }
     }
 
     pub fn code(&self) -> &[u8] {
         &self.code
     }
+
+    pub fn previous(&self) -> Option<Address> {
+        self.previous
+    }
+
+    /// Returns the badge which authorizes publishing newer versions, if any.
+    pub fn upgrade_auth(&self) -> Option<Address> {
+        self.upgrade_auth
+    }
+
+    pub fn enable_upgrade(&mut self, upgrade_auth: Address) {
+        self.upgrade_auth = Some(upgrade_auth);
+    }
 }
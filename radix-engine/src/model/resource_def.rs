@@ -38,7 +38,7 @@ impl ResourceDef {
     }
 
     pub fn supply(&self) -> Amount {
-        self.supply
+        self.supply.clone()
     }
 
     pub fn mint_auth(&self) -> Option<Address> {
@@ -9,6 +9,8 @@ use crate::model::{Auth, Bucket, BucketError};
 pub enum VaultError {
     AccountingError(BucketError),
     UnauthorizedAccess,
+    AlreadyOwned { owner: Address, claimant: Address },
+    NotOwner(Address),
 }
 
 /// A persistent resource container on ledger state.
@@ -16,11 +18,17 @@ pub enum VaultError {
 pub struct Vault {
     bucket: Bucket,
     auth: Address,
+    #[sbor(default)]
+    owner: Option<Address>,
 }
 
 impl Vault {
     pub fn new(bucket: Bucket, auth: Address) -> Self {
-        Self { bucket, auth }
+        Self {
+            bucket,
+            auth,
+            owner: None,
+        }
     }
 
     pub fn put(&mut self, other: Bucket, auth: Auth) -> Result<(), VaultError> {
@@ -56,4 +64,43 @@ impl Vault {
             Err(VaultError::UnauthorizedAccess)
         }
     }
+
+    /// Transfers access to a new owner, e.g. a newer package version.
+    ///
+    /// Transferring to the current owner is a no-op.
+    pub fn set_auth(&mut self, new_auth: Address, auth: Auth) -> Result<(), VaultError> {
+        if self.auth == new_auth || auth.contains(self.auth) {
+            self.auth = new_auth;
+            Ok(())
+        } else {
+            Err(VaultError::UnauthorizedAccess)
+        }
+    }
+
+    /// Returns the component which owns this vault, if any.
+    pub fn owner(&self) -> Option<Address> {
+        self.owner
+    }
+
+    /// Assigns this vault to a component. It can only be claimed once.
+    pub fn set_owner(&mut self, owner: Address) -> Result<(), VaultError> {
+        match self.owner {
+            Some(a) => Err(VaultError::AlreadyOwned {
+                owner: a,
+                claimant: owner,
+            }),
+            None => {
+                self.owner = Some(owner);
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks that this vault is accessed by its owner, if it has one.
+    pub fn check_owner(&self, component: Option<Address>) -> Result<(), VaultError> {
+        match self.owner {
+            Some(a) if component != Some(a) => Err(VaultError::NotOwner(a)),
+            _ => Ok(()),
+        }
+    }
 }
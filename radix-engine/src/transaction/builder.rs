@@ -98,6 +98,63 @@ impl<'a, A: AbiProvider> TransactionBuilder<'a, A> {
         })
     }
 
+    /// Publishes a new version of a package, authorized by its upgrade badge.
+    pub fn publish_package_upgrade(
+        &mut self,
+        code: &[u8],
+        previous: Address,
+        upgrade_auth: Address,
+    ) -> &mut Self {
+        self.declare_bucket_ref(|builder, rid| {
+            builder.create_bucket_ref(1.into(), upgrade_auth, rid);
+            builder.add_instruction(Instruction::CallFunction {
+                package: SYSTEM_PACKAGE,
+                name: "System".to_owned(),
+                function: "publish_package_upgrade".to_owned(),
+                args: vec![
+                    SmartValue::from(code.to_vec()),
+                    SmartValue::from(previous),
+                    SmartValue::from(rid),
+                ],
+            })
+        })
+    }
+
+    /// Migrates a component to a newer version of its package.
+    pub fn migrate_component(
+        &mut self,
+        component: Address,
+        package: Address,
+        upgrade_auth: Address,
+    ) -> &mut Self {
+        self.declare_bucket_ref(|builder, rid| {
+            builder.create_bucket_ref(1.into(), upgrade_auth, rid);
+            builder.add_instruction(Instruction::CallFunction {
+                package: SYSTEM_PACKAGE,
+                name: "System".to_owned(),
+                function: "migrate_component".to_owned(),
+                args: vec![
+                    SmartValue::from(component),
+                    SmartValue::from(package),
+                    SmartValue::from(rid),
+                ],
+            })
+        })
+    }
+
+    /// Claims the royalties collected by a component.
+    pub fn claim_royalties(&mut self, component: Address, royalty_auth: Address) -> &mut Self {
+        self.declare_bucket_ref(|builder, rid| {
+            builder.create_bucket_ref(1.into(), royalty_auth, rid);
+            builder.add_instruction(Instruction::CallFunction {
+                package: SYSTEM_PACKAGE,
+                name: "System".to_owned(),
+                function: "claim_royalties".to_owned(),
+                args: vec![SmartValue::from(component), SmartValue::from(rid)],
+            })
+        })
+    }
+
     /// Creates a resource with mutable supply.
     pub fn new_resource_mutable(
         &mut self,
@@ -192,9 +249,56 @@ impl<'a, A: AbiProvider> TransactionBuilder<'a, A> {
         })
     }
 
+    /// Creates a bucket by withdrawing all of a resource from context.
+    pub fn take_all_from_context(&mut self, resource_def: Address, bid: Bid) -> &mut Self {
+        self.add_instruction(Instruction::TakeAllFromContext {
+            resource_def,
+            to: bid,
+        })
+    }
+
+    /// Returns a bucket to context.
+    pub fn return_to_context(&mut self, bid: Bid) -> &mut Self {
+        self.add_instruction(Instruction::ReturnToContext { bucket: bid })
+    }
+
+    /// Creates a bucket ref by borrowing a bucket.
+    pub fn create_bucket_ref_from_bucket(&mut self, bid: Bid, rid: Rid) -> &mut Self {
+        self.add_instruction(Instruction::CreateBucketRefFromBucket {
+            bucket: bid,
+            to: rid,
+        })
+    }
+
+    /// Clones a bucket ref.
+    pub fn clone_bucket_ref(&mut self, rid: Rid, to: Rid) -> &mut Self {
+        self.add_instruction(Instruction::CloneBucketRef {
+            bucket_ref: rid,
+            to,
+        })
+    }
+
+    /// Asserts that the transaction context holds at least the given amount of a resource.
+    pub fn assert_context_contains(&mut self, amount: Amount, resource_def: Address) -> &mut Self {
+        self.add_instruction(Instruction::AssertContextContains {
+            amount,
+            resource_def,
+        })
+    }
+
+    /// Asserts that a temporary bucket holds at least the given amount.
+    pub fn assert_bucket_amount_at_least(&mut self, bucket: Bid, amount: Amount) -> &mut Self {
+        self.add_instruction(Instruction::AssertBucketAmountAtLeast { bucket, amount })
+    }
+
+    /// Drops all bucket refs.
+    pub fn drop_all_bucket_refs(&mut self) -> &mut Self {
+        self.add_instruction(Instruction::DropAllBucketRefs)
+    }
+
     /// Deposits everything into an account.
     pub fn deposit_all(&mut self, account: Address) -> &mut Self {
-        self.add_instruction(Instruction::PutEverythingIntoAccount { account })
+        self.add_instruction(Instruction::DepositAllBuckets { account })
     }
 
     /// Calls a function.
@@ -388,7 +492,11 @@ impl<'a, A: AbiProvider> TransactionBuilder<'a, A> {
                     .map_err(|_| BuildArgsError::FailedToParse(i, ty.clone(), arg.to_owned()))?;
                 Ok(SmartValue::from(value))
             }
-            SCRYPTO_NAME_ADDRESS => {
+            SCRYPTO_NAME_ADDRESS
+            | SCRYPTO_NAME_PACKAGE
+            | SCRYPTO_NAME_COMPONENT
+            | SCRYPTO_NAME_RESOURCE_DEF
+            | SCRYPTO_NAME_ACCOUNT => {
                 let value = arg
                     .parse::<Address>()
                     .map_err(|_| BuildArgsError::FailedToParse(i, ty.clone(), arg.to_owned()))?;
@@ -407,7 +515,7 @@ impl<'a, A: AbiProvider> TransactionBuilder<'a, A> {
                 match (amount, resource_def) {
                     (Some(a), Some(r)) => {
                         if let Some(account) = account {
-                            self.withdraw(a, r, account);
+                            self.withdraw(a.clone(), r, account);
                         }
                         let mut created_bid = None;
                         self.declare_bucket(|builder, bid| {
@@ -426,7 +534,7 @@ impl<'a, A: AbiProvider> TransactionBuilder<'a, A> {
                 match (amount, resource_def) {
                     (Some(a), Some(r)) => {
                         if let Some(account) = account {
-                            self.withdraw(a, r, account);
+                            self.withdraw(a.clone(), r, account);
                         }
                         let mut created_rid = None;
                         self.declare_bucket_ref(|builder, rid| {
@@ -158,10 +158,13 @@ impl<'l, L: Ledger> TransactionExecutor<'l, L> {
             return Err(TransactionExecutionError::MissingEndInstruction);
         };
 
+        // Transactions carry no nonce of their own; the executor's nonce tells identical ones apart
+        let mut tx_hash_data = transaction.hash().as_ref().to_vec();
+        tx_hash_data.extend(self.nonce.to_le_bytes());
         let mut track = Track::new(
             self.ledger,
             self.current_epoch,
-            sha256(self.nonce.to_string()),
+            sha256(tx_hash_data),
             signers,
         );
         let mut proc = track.start_process(trace);
@@ -183,14 +186,35 @@ impl<'l, L: Ledger> TransactionExecutor<'l, L> {
                     resource_def,
                     to,
                 } => proc
-                    .take_from_context(*amount, *resource_def, *to)
+                    .take_from_context(amount.clone(), *resource_def, *to)
                     .map(|_| None),
+                Instruction::TakeAllFromContext { resource_def, to } => {
+                    proc.take_all_from_context(*resource_def, *to).map(|_| None)
+                }
+                Instruction::ReturnToContext { bucket } => {
+                    proc.return_to_context(*bucket).map(|_| None)
+                }
                 Instruction::BorrowFromContext {
                     amount,
                     resource_def,
                     to,
                 } => proc
-                    .borrow_from_context(*amount, *resource_def, *to)
+                    .borrow_from_context(amount.clone(), *resource_def, *to)
+                    .map(|_| None),
+                Instruction::CreateBucketRefFromBucket { bucket, to } => proc
+                    .create_bucket_ref_from_bucket(*bucket, *to)
+                    .map(|_| None),
+                Instruction::CloneBucketRef { bucket_ref, to } => {
+                    proc.clone_bucket_ref(*bucket_ref, *to).map(|_| None)
+                }
+                Instruction::AssertContextContains {
+                    amount,
+                    resource_def,
+                } => proc
+                    .assert_context_contains(amount.clone(), *resource_def)
+                    .map(|_| None),
+                Instruction::AssertBucketAmountAtLeast { bucket, amount } => proc
+                    .assert_bucket_amount_at_least(*bucket, amount.clone())
                     .map(|_| None),
                 Instruction::CallFunction {
                     package,
@@ -216,7 +240,8 @@ impl<'l, L: Ledger> TransactionExecutor<'l, L> {
                         args.iter().map(|v| v.encoded.clone()).collect(),
                     )
                     .map(|rtn| Some(SmartValue { encoded: rtn })),
-                Instruction::PutEverythingIntoAccount { account } => {
+                Instruction::DropAllBucketRefs => proc.drop_all_bucket_refs().map(|_| None),
+                Instruction::DepositAllBuckets { account } => {
                     let buckets = proc.list_resources();
                     if !buckets.is_empty() {
                         proc.call_method(*account, "deposit_batch", args!(buckets))
@@ -236,8 +261,13 @@ impl<'l, L: Ledger> TransactionExecutor<'l, L> {
 
         // commit state updates
         if success {
-            track.commit();
-            self.nonce += 1;
+            match track.commit() {
+                Ok(_) => self.nonce += 1,
+                Err(e) => {
+                    success = false;
+                    results.push(Err(e));
+                }
+            }
         }
         #[cfg(feature = "alloc")]
         let execution_time = None;
@@ -249,6 +279,11 @@ impl<'l, L: Ledger> TransactionExecutor<'l, L> {
             success,
             results,
             logs: track.logs().clone(),
+            royalties: if success {
+                track.royalties().clone()
+            } else {
+                Vec::new()
+            },
             new_entities: if success {
                 track.new_entities().to_vec()
             } else {
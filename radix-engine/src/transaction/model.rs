@@ -7,13 +7,14 @@ use scrypto::rust::string::ToString;
 use scrypto::rust::vec;
 use scrypto::rust::vec::Vec;
 use scrypto::types::*;
+use scrypto::utils::*;
 
 use crate::engine::*;
 use crate::model::*;
 use crate::utils::*;
 
 /// Represents a universally recognizable value.
-#[derive(Clone, TypeId, Encode, Decode)]
+#[derive(Clone, TypeId, Encode, Decode, PartialEq, Eq)]
 pub struct SmartValue {
     pub encoded: Vec<u8>,
 }
@@ -42,6 +43,13 @@ pub struct Transaction {
     pub instructions: Vec<Instruction>,
 }
 
+impl Transaction {
+    /// Returns the hash of the canonical encoding of this transaction.
+    pub fn hash(&self) -> H256 {
+        sha256(scrypto_encode(self))
+    }
+}
+
 /// Represents an instruction in transaction
 #[derive(Debug, Clone, TypeId, Encode, Decode, PartialEq, Eq)]
 pub enum Instruction {
@@ -58,6 +66,12 @@ pub enum Instruction {
         to: Bid,
     },
 
+    /// Takes all of a resource from transaction context to a temporary bucket.
+    TakeAllFromContext { resource_def: Address, to: Bid },
+
+    /// Returns a temporary bucket to transaction context.
+    ReturnToContext { bucket: Bid },
+
     /// Borrows resource from transaction context to a temporary bucket ref.
     ///
     /// A bucket will be created to support the reference and it will stay within the context.
@@ -67,6 +81,14 @@ pub enum Instruction {
         to: Rid,
     },
 
+    /// Creates a temporary bucket ref from a temporary bucket.
+    ///
+    /// The bucket stays locked until all references to it are dropped.
+    CreateBucketRefFromBucket { bucket: Bid, to: Rid },
+
+    /// Clones a temporary bucket ref.
+    CloneBucketRef { bucket_ref: Rid, to: Rid },
+
     /// Calls a blueprint function.
     ///
     /// Buckets and bucket refs in arguments moves from transaction context to the callee.
@@ -86,10 +108,22 @@ pub enum Instruction {
         args: Vec<SmartValue>,
     },
 
-    /// Puts all resources from transaction context to the designated account.
-    PutEverythingIntoAccount { account: Address },
+    /// Asserts that the transaction context holds at least the given amount of a resource.
+    AssertContextContains {
+        amount: Amount,
+        resource_def: Address,
+    },
+
+    /// Asserts that a temporary bucket holds at least the given amount.
+    AssertBucketAmountAtLeast { bucket: Bid, amount: Amount },
 
-    /// Marks the end of transaction with signatures.
+    /// Drops all bucket refs held by transaction context.
+    DropAllBucketRefs,
+
+    /// Deposits all buckets in transaction context into the designated account.
+    DepositAllBuckets { account: Address },
+
+    /// Marks the end of transaction with the signers.
     End { signers: Vec<Address> },
 }
 
@@ -99,6 +133,7 @@ pub struct Receipt {
     pub success: bool,
     pub results: Vec<Result<Option<SmartValue>, RuntimeError>>,
     pub logs: Vec<(Level, String)>,
+    pub royalties: Vec<(Address, String, Amount)>,
     pub new_entities: Vec<Address>,
     pub execution_time: Option<u128>,
 }
@@ -189,6 +224,23 @@ impl fmt::Debug for Receipt {
             write!(f, "\n{} [{:5}] {}", prefix!(i, self.logs), l, m)?;
         }
 
+        write!(
+            f,
+            "\n{} {}",
+            "Royalties:".bold().green(),
+            self.royalties.len()
+        )?;
+        for (i, (component, method, amount)) in self.royalties.iter().enumerate() {
+            write!(
+                f,
+                "\n{} {} XRD to {}::{}",
+                prefix!(i, self.royalties),
+                amount,
+                component,
+                method
+            )?;
+        }
+
         write!(
             f,
             "\n{} {}",
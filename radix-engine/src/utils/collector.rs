@@ -0,0 +1,77 @@
+use sbor::any::*;
+use sbor::*;
+use scrypto::buffer::*;
+use scrypto::rust::borrow::Borrow;
+use scrypto::rust::convert::TryFrom;
+use scrypto::rust::vec::Vec;
+use scrypto::types::*;
+
+use crate::utils::*;
+
+/// Collects the vaults and lazy maps referenced by any data.
+pub fn collect_ids(data: &[u8]) -> Result<(Vec<Vid>, Vec<Mid>), DecodeError> {
+    let value = decode_any_untrusted(data)?;
+    let mut vids = Vec::new();
+    let mut mids = Vec::new();
+    collect_value(&value, &mut vids, &mut mids)?;
+    Ok((vids, mids))
+}
+
+fn collect_value(
+    value: &Value,
+    vids: &mut Vec<Vid>,
+    mids: &mut Vec<Mid>,
+) -> Result<(), DecodeError> {
+    match value {
+        Value::Struct(fields) | Value::Enum(_, fields) => match fields {
+            Fields::Named(values) | Fields::Unnamed(values) => collect_vec(values, vids, mids),
+            Fields::Unit => Ok(()),
+        },
+        Value::Option(v) => match v.borrow() {
+            Some(x) => collect_value(x, vids, mids),
+            None => Ok(()),
+        },
+        Value::Box(v) => collect_value(v.borrow(), vids, mids),
+        Value::Result(v) => match v.borrow() {
+            Ok(x) | Err(x) => collect_value(x, vids, mids),
+        },
+        Value::Array(_, values)
+        | Value::Tuple(values)
+        | Value::Vec(_, values)
+        | Value::TreeSet(_, values)
+        | Value::HashSet(_, values) => collect_vec(values, vids, mids),
+        Value::TreeMap(_, _, entries) | Value::HashMap(_, _, entries) => {
+            for (k, v) in entries {
+                collect_value(k, vids, mids)?;
+                collect_value(v, vids, mids)?;
+            }
+            Ok(())
+        }
+        Value::Custom(ty, data) => {
+            match *ty {
+                SCRYPTO_TYPE_VID => vids.push(
+                    Vid::try_from(data.as_slice())
+                        .map_err(|_| DecodeError::InvalidCustomData(*ty))?,
+                ),
+                SCRYPTO_TYPE_MID => mids.push(
+                    Mid::try_from(data.as_slice())
+                        .map_err(|_| DecodeError::InvalidCustomData(*ty))?,
+                ),
+                _ => {}
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn collect_vec(
+    values: &[Value],
+    vids: &mut Vec<Vid>,
+    mids: &mut Vec<Mid>,
+) -> Result<(), DecodeError> {
+    for v in values {
+        collect_value(v, vids, mids)?;
+    }
+    Ok(())
+}
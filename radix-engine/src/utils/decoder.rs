@@ -1,9 +1,66 @@
-use scrypto::buffer::*;
+use sbor::any::*;
+use sbor::describe::Type;
+use sbor::*;
+use scrypto::buffer::scrypto_type_id;
 use scrypto::rust::vec::Vec;
+use scrypto::types::*;
 
 use crate::engine::*;
 
+/// The limits applied when decoding data from blueprints and transactions.
+pub const DECODE_LIMITS: DecodeLimits = DecodeLimits {
+    max_depth: 64,
+    max_len: 1 << 22,
+    max_size: 1 << 24,
+};
+
+/// The limits applied when decoding package code, which is a single byte vector.
+pub const PACKAGE_DECODE_LIMITS: DecodeLimits = DecodeLimits {
+    max_depth: 64,
+    max_len: 1 << 24,
+    max_size: 1 << 24,
+};
+
+/// Returns the limits for decoding the arguments of a function.
+///
+/// Only the system functions which publish packages take package code.
+pub fn args_decode_limits(package: Address, function: &str) -> DecodeLimits {
+    if package == SYSTEM_PACKAGE
+        && (function == "publish_package" || function == "publish_package_upgrade")
+    {
+        PACKAGE_DECODE_LIMITS
+    } else {
+        DECODE_LIMITS
+    }
+}
+
 /// Decodes data into an instance of `T`.
-pub fn decode_data<T: sbor::Decode>(data: Vec<u8>) -> Result<T, RuntimeError> {
-    scrypto_decode(&data).map_err(RuntimeError::InvalidData)
+pub fn decode_data<T: sbor::DecodeOwned>(data: Vec<u8>) -> Result<T, RuntimeError> {
+    decode_untrusted(&data).map_err(RuntimeError::InvalidData)
+}
+
+/// Decodes untrusted data into an instance of `T`, within `DECODE_LIMITS`.
+pub fn decode_untrusted<'de, T: sbor::Decode<'de>>(data: &'de [u8]) -> Result<T, DecodeError> {
+    sbor::decode_with_limits(data, DECODE_LIMITS)
+}
+
+/// Decodes untrusted data of any type, within `DECODE_LIMITS`.
+pub fn decode_any_untrusted(data: &[u8]) -> Result<Value, DecodeError> {
+    decode_any_with_limits(data, DECODE_LIMITS)
+}
+
+/// Validates untrusted data against a type, decoding it within `DECODE_LIMITS`.
+pub fn validate_untrusted(data: &[u8], ty: &Type) -> Result<(), ValidateError> {
+    validate_untrusted_with_limits(data, ty, DECODE_LIMITS)
+}
+
+/// Validates untrusted data against a type, decoding it within the given limits.
+pub fn validate_untrusted_with_limits(
+    data: &[u8],
+    ty: &Type,
+    limits: DecodeLimits,
+) -> Result<(), ValidateError> {
+    decode_any_with_limits(data, limits)
+        .map_err(ValidateError::InvalidData)
+        .and_then(|value| validate_value(&value, ty, &scrypto_type_id))
 }
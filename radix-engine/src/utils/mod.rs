@@ -1,5 +1,10 @@
+mod collector;
 mod decoder;
 mod formatter;
 
-pub use decoder::decode_data;
-pub use formatter::{format_data, format_data_with_ledger};
+pub use collector::collect_ids;
+pub use decoder::{
+    args_decode_limits, decode_any_untrusted, decode_data, decode_untrusted, validate_untrusted,
+    validate_untrusted_with_limits, DECODE_LIMITS, PACKAGE_DECODE_LIMITS,
+};
+pub use formatter::{format_custom, format_data, format_data_with_ledger};
@@ -0,0 +1,21 @@
+use scrypto::types::Address;
+use scrypto::{blueprint, external_component};
+
+external_component! {
+    trait ComponentTestInterface {
+        fn get_component_state(&self) -> String;
+    }
+}
+
+blueprint! {
+    struct ExternalTest;
+
+    impl ExternalTest {
+        pub fn call_components(first: Address, second: Address) -> (String, String) {
+            (
+                ComponentTestInterface::from(first).get_component_state(),
+                ComponentTestInterface::from(second).get_component_state(),
+            )
+        }
+    }
+}
@@ -1,7 +1,11 @@
 pub mod bucket;
 pub mod component;
 pub mod context;
+pub mod external;
 pub mod lazy_map;
 pub mod move_resource;
+pub mod ownership;
 pub mod package;
 pub mod resource_def;
+pub mod royalty;
+pub mod upgrade;
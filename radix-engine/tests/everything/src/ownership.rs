@@ -0,0 +1,49 @@
+use scrypto::blueprint;
+use scrypto::core::{Component, State};
+use scrypto::resource::{Bucket, ResourceBuilder, Vault};
+use scrypto::types::{Vid, H256};
+
+blueprint! {
+    struct OwnershipTest {
+        test_vault: Vault,
+    }
+
+    impl OwnershipTest {
+        pub fn create_component() -> Component {
+            let bucket = ResourceBuilder::new()
+                .metadata("name", "TestToken")
+                .create_fixed(1000);
+
+            Self {
+                test_vault: Vault::with_bucket(bucket),
+            }.instantiate()
+        }
+
+        pub fn create_component_sharing_vault(other: Component) -> Component {
+            let state: OwnershipTest = other.get_state();
+
+            Self {
+                test_vault: state.test_vault,
+            }.instantiate()
+        }
+
+        pub fn take_from_component(&self, other: Component) -> Bucket {
+            let state: OwnershipTest = other.get_state();
+            state.test_vault.take(1)
+        }
+
+        pub fn create_orphaned_vault() {
+            let bucket = ResourceBuilder::new()
+                .metadata("name", "TestToken")
+                .create_fixed(1000);
+
+            Vault::with_bucket(bucket);
+        }
+
+        pub fn create_component_with_dangling_vault() -> Component {
+            Self {
+                test_vault: Vault::from(Vid(H256([0xff; 32]), 0)),
+            }.instantiate()
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use scrypto::blueprint;
+use scrypto::core::{Component, State};
+use scrypto::resource::{Bucket, ResourceBuilder};
+use scrypto::rust::collections::HashMap;
+use scrypto::rust::string::{String, ToString};
+use scrypto::rust::vec;
+use scrypto::types::Amount;
+
+blueprint! {
+    struct RoyaltyTest {
+        counter: u32,
+    }
+
+    impl RoyaltyTest {
+        pub fn create_component() -> (Component, Bucket) {
+            let badge = ResourceBuilder::new()
+                .metadata("name", "OwnerBadge")
+                .create_fixed(1);
+
+            let component = Self { counter: 0 }.instantiate();
+
+            let mut metadata = HashMap::new();
+            metadata.insert("name".to_string(), "Royalty Test".to_string());
+            component.set_metadata(metadata);
+            component.set_owner(badge.resource_def());
+
+            let mut royalties = HashMap::new();
+            royalties.insert("paid_method".to_string(), Amount::from(10));
+            component.set_royalties(royalties, badge.resource_def());
+
+            (component, badge)
+        }
+
+        pub fn get_component_metadata(component: Component) -> HashMap<String, String> {
+            component.metadata()
+        }
+
+        pub fn call_paid_method(component: Component, payment: Bucket) -> Bucket {
+            let _: u32 = component.call("paid_method", vec![]);
+            payment
+        }
+
+        pub fn paid_method(&mut self) -> u32 {
+            self.counter += 1;
+            self.counter
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use scrypto::blueprint;
+use scrypto::buffer::{scrypto_decode, scrypto_encode};
+use scrypto::core::{Component, Context, Package, State};
+use scrypto::resource::{Bucket, ResourceBuilder, Vault};
+use scrypto::rust::vec::Vec;
+
+blueprint! {
+    struct UpgradeTest {
+        test_vault: Vault,
+        version: u32,
+    }
+
+    impl UpgradeTest {
+        pub fn enable_package_upgrade() -> Bucket {
+            let badge = ResourceBuilder::new()
+                .metadata("name", "PackageUpgradeBadge")
+                .create_fixed(1);
+
+            Package::from(Context::package_address()).enable_upgrade(badge.resource_def());
+
+            badge
+        }
+
+        pub fn create_component() -> (Component, Bucket) {
+            let badge = ResourceBuilder::new()
+                .metadata("name", "UpgradeBadge")
+                .create_fixed(1);
+            let bucket = ResourceBuilder::new()
+                .metadata("name", "TestToken")
+                .create_fixed(1000);
+
+            let component = Self {
+                test_vault: Vault::with_bucket(bucket),
+                version: 1,
+            }.instantiate();
+            component.enable_upgrade(badge.resource_def());
+
+            (component, badge)
+        }
+
+        pub fn migrate(state: Vec<u8>) -> Vec<u8> {
+            let mut state: UpgradeTest = scrypto_decode(&state).unwrap();
+            state.version += 1;
+            scrypto_encode(&state)
+        }
+
+        pub fn get_version(&self) -> u32 {
+            self.version
+        }
+
+        pub fn take_from_vault(&mut self) -> Bucket {
+            self.test_vault.take(1)
+        }
+    }
+}
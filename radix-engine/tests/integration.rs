@@ -1,3 +1,4 @@
+use radix_engine::engine::*;
 use radix_engine::ledger::*;
 use radix_engine::transaction::*;
 use scrypto::prelude::*;
@@ -95,6 +96,70 @@ fn test_component() {
     assert!(receipt2.success);
 }
 
+#[test]
+fn test_external_component() {
+    compile();
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+    let package = executor.publish_package(include_code!("./everything"));
+
+    let transaction1 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "ComponentTest",
+            "create_component",
+            vec![],
+            Some(account),
+        )
+        .call_function(
+            package,
+            "OwnershipTest",
+            "create_component",
+            vec![],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+    let component = receipt1.component(0).unwrap();
+    let other = receipt1.component(1).unwrap();
+
+    // The interface matches the component's blueprint
+    let transaction2 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "ExternalTest",
+            "call_components",
+            vec![component.to_string(), component.to_string()],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt2 = executor.run(transaction2, true).unwrap();
+    assert!(receipt2.success);
+
+    // The interface does not match the blueprint of the second component
+    let transaction3 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "ExternalTest",
+            "call_components",
+            vec![component.to_string(), other.to_string()],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt3 = executor.run(transaction3, true).unwrap();
+    assert!(!receipt3.success);
+    assert!(receipt3
+        .logs
+        .iter()
+        .any(|(_, message)| message.contains("ABI mismatch")));
+}
+
 #[test]
 fn test_lazy_map() {
     compile();
@@ -197,6 +262,68 @@ fn test_move_resource() {
     assert!(receipt.success);
 }
 
+#[test]
+fn test_context_buckets() {
+    compile();
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+    let package = executor.publish_package(include_code!("./everything"));
+
+    // Create component
+    let transaction1 = TransactionBuilder::new(&executor)
+        .call_function(package, "MoveTest", "move_bucket", vec![], Some(account))
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+    let component = receipt1.component(0).unwrap();
+
+    // Take everything, return it and deposit it
+    let transaction2 = TransactionBuilder::new(&executor)
+        .withdraw(10.into(), RADIX_TOKEN, account)
+        .declare_bucket(|builder, bid| {
+            builder
+                .take_all_from_context(RADIX_TOKEN, bid)
+                .assert_bucket_amount_at_least(bid, 10.into())
+                .return_to_context(bid)
+        })
+        .assert_context_contains(10.into(), RADIX_TOKEN)
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt2 = executor.run(transaction2, true).unwrap();
+    assert!(receipt2.success);
+
+    // Lend a bucket through two refs, which unlocks it under its name once both are dropped
+    let transaction3 = TransactionBuilder::new(&executor)
+        .withdraw(10.into(), RADIX_TOKEN, account)
+        .declare_bucket(|builder, bid| {
+            builder.take_all_from_context(RADIX_TOKEN, bid);
+            builder.declare_bucket_ref(|builder, rid1| {
+                builder.create_bucket_ref_from_bucket(bid, rid1);
+                builder.declare_bucket_ref(|builder, rid2| {
+                    builder.clone_bucket_ref(rid1, rid2);
+                    for rid in [rid1, rid2] {
+                        builder.add_instruction(Instruction::CallMethod {
+                            component,
+                            method: "receive_bucket_ref".to_owned(),
+                            args: vec![SmartValue::from(rid)],
+                        });
+                    }
+                    builder
+                })
+            });
+            builder.return_to_context(bid)
+        })
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt3 = executor.run(transaction3, true).unwrap();
+    assert!(receipt3.success);
+}
+
 #[test]
 fn test_nft() {
     let mut ledger = InMemoryLedger::with_bootstrap();
@@ -220,3 +347,423 @@ fn test_nft() {
     let receipt = executor.run(transaction, true).unwrap();
     assert!(receipt.success);
 }
+
+#[test]
+fn test_upgrade() {
+    compile();
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+    let package = executor.publish_package(include_code!("./everything"));
+    let unrelated = executor.publish_package(include_code!("./everything"));
+
+    // Enable package upgrade
+    let transaction0 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "UpgradeTest",
+            "enable_package_upgrade",
+            vec![],
+            Some(account),
+        )
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt0 = executor.run(transaction0, true).unwrap();
+    assert!(receipt0.success);
+    let package_badge = receipt0.resource_def(0).unwrap();
+
+    // Publishing a new version without the upgrade badge should fail
+    let transaction1 = TransactionBuilder::new(&executor)
+        .withdraw(1.into(), RADIX_TOKEN, account)
+        .publish_package_upgrade(include_code!("./everything"), package, RADIX_TOKEN)
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(!receipt1.success);
+
+    // Publish a new version
+    let transaction1 = TransactionBuilder::new(&executor)
+        .withdraw(1.into(), package_badge, account)
+        .publish_package_upgrade(include_code!("./everything"), package, package_badge)
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+    let upgrade = receipt1.package(0).unwrap();
+
+    // Create component
+    let transaction1 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "UpgradeTest",
+            "create_component",
+            vec![],
+            Some(account),
+        )
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+    let component = receipt1.component(0).unwrap();
+    let badge = receipt1.resource_def(0).unwrap();
+
+    // Migration to a package which is not a newer version should fail
+    let transaction2 = TransactionBuilder::new(&executor)
+        .withdraw(1.into(), badge, account)
+        .migrate_component(component, unrelated, badge)
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt2 = executor.run(transaction2, true).unwrap();
+    assert!(!receipt2.success);
+
+    // Migrate and access the state and vault through the new version
+    let transaction3 = TransactionBuilder::new(&executor)
+        .withdraw(1.into(), badge, account)
+        .migrate_component(component, upgrade, badge)
+        .call_method(component, "get_version", vec![], Some(account))
+        .call_method(component, "take_from_vault", vec![], Some(account))
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt3 = executor.run(transaction3, true).unwrap();
+    assert!(receipt3.success);
+
+    // The migrated state is visible through the new version
+    let transaction4 = TransactionBuilder::new(&executor)
+        .call_method(component, "get_version", vec![], Some(account))
+        .build(vec![key])
+        .unwrap();
+    let receipt4 = executor.run(transaction4, true).unwrap();
+    assert!(receipt4.success);
+    let version: u32 = match &receipt4.results[0] {
+        Ok(Some(rtn)) => scrypto_decode(&rtn.encoded).unwrap(),
+        _ => panic!("get_version returned nothing"),
+    };
+    assert_eq!(version, 2);
+}
+
+#[test]
+fn test_royalty() {
+    compile();
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+    let package = executor.publish_package(include_code!("./everything"));
+
+    // Create component
+    let transaction1 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "RoyaltyTest",
+            "create_component",
+            vec![],
+            Some(account),
+        )
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+    let component = receipt1.component(0).unwrap();
+    let badge = receipt1.resource_def(0).unwrap();
+
+    // Calling without paying royalty should fail
+    let transaction2 = TransactionBuilder::new(&executor)
+        .call_method(component, "paid_method", vec![], Some(account))
+        .build(vec![key])
+        .unwrap();
+    let receipt2 = executor.run(transaction2, true).unwrap();
+    assert!(!receipt2.success);
+
+    // Pay royalty and read metadata
+    let transaction3 = TransactionBuilder::new(&executor)
+        .withdraw(10.into(), RADIX_TOKEN, account)
+        .call_method(component, "paid_method", vec![], Some(account))
+        .call_function(
+            package,
+            "RoyaltyTest",
+            "get_component_metadata",
+            vec![component.to_string()],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt3 = executor.run(transaction3, true).unwrap();
+    assert!(receipt3.success);
+    assert_eq!(
+        receipt3.royalties,
+        vec![(component, "paid_method".to_owned(), Amount::from(10))]
+    );
+
+    // A blueprint pays the royalty of a nested call from its own buckets
+    let transaction4 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "RoyaltyTest",
+            "call_paid_method",
+            vec![component.to_string(), format!("15,{}", RADIX_TOKEN)],
+            Some(account),
+        )
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt4 = executor.run(transaction4, true).unwrap();
+    assert!(receipt4.success);
+    assert_eq!(
+        receipt4.royalties,
+        vec![(component, "paid_method".to_owned(), Amount::from(10))]
+    );
+
+    // but not from the XRD left in the transaction context
+    let transaction5 = TransactionBuilder::new(&executor)
+        .withdraw(10.into(), RADIX_TOKEN, account)
+        .call_function(
+            package,
+            "RoyaltyTest",
+            "call_paid_method",
+            vec![component.to_string(), format!("5,{}", RADIX_TOKEN)],
+            Some(account),
+        )
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt5 = executor.run(transaction5, true).unwrap();
+    assert!(!receipt5.success);
+
+    // Claim royalties
+    let transaction6 = TransactionBuilder::new(&executor)
+        .withdraw(1.into(), badge, account)
+        .claim_royalties(component, badge)
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt6 = executor.run(transaction6, true).unwrap();
+    assert!(receipt6.success);
+}
+
+#[test]
+fn test_ownership() {
+    compile();
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+    let package = executor.publish_package(include_code!("./everything"));
+
+    let transaction1 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "OwnershipTest",
+            "create_component",
+            vec![],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+    let component = receipt1.component(0).unwrap();
+
+    // A vault can't be shared by two components
+    let transaction2 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "OwnershipTest",
+            "create_component_sharing_vault",
+            vec![component.to_string()],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt2 = executor.run(transaction2, true).unwrap();
+    assert!(!receipt2.success);
+    // the vault is reported with its current owner, not the component claiming it
+    let error = format!("{:?}", receipt2.results.last());
+    assert!(error.contains(&format!("AlreadyOwned {{ owner: {:?}", component)));
+
+    // A component can't reference a vault which does not exist
+    let transaction3 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "OwnershipTest",
+            "create_component_with_dangling_vault",
+            vec![],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt3 = executor.run(transaction3, true).unwrap();
+    assert!(!receipt3.success);
+
+    // A vault which is not stored in any component is rejected at commit
+    let transaction4 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "OwnershipTest",
+            "create_orphaned_vault",
+            vec![],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt4 = executor.run(transaction4, true).unwrap();
+    assert!(!receipt4.success);
+    assert!(matches!(
+        receipt4.results.last(),
+        Some(Err(RuntimeError::OrphanedVaults(_)))
+    ));
+
+    // A component can only access its own vaults, even within the same package
+    let transaction5 = TransactionBuilder::new(&executor)
+        .call_function(
+            package,
+            "OwnershipTest",
+            "create_component",
+            vec![],
+            Some(account),
+        )
+        .build(vec![key])
+        .unwrap();
+    let receipt5 = executor.run(transaction5, true).unwrap();
+    assert!(receipt5.success);
+    let other = receipt5.component(0).unwrap();
+
+    let transaction6 = TransactionBuilder::new(&executor)
+        .call_method(
+            other,
+            "take_from_component",
+            vec![component.to_string()],
+            Some(account),
+        )
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt6 = executor.run(transaction6, true).unwrap();
+    assert!(!receipt6.success);
+
+    let transaction7 = TransactionBuilder::new(&executor)
+        .call_method(
+            component,
+            "take_from_component",
+            vec![component.to_string()],
+            Some(account),
+        )
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt7 = executor.run(transaction7, true).unwrap();
+    assert!(receipt7.success);
+}
+
+#[test]
+fn test_assertions() {
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+
+    // Asserting what the context holds succeeds
+    let transaction1 = TransactionBuilder::new(&executor)
+        .withdraw(10.into(), RADIX_TOKEN, account)
+        .assert_context_contains(10.into(), RADIX_TOKEN)
+        .declare_bucket(|builder, bid| {
+            builder
+                .create_bucket(5.into(), RADIX_TOKEN, bid)
+                .assert_bucket_amount_at_least(bid, 5.into())
+                .add_instruction(Instruction::CallMethod {
+                    component: account,
+                    method: "deposit".to_owned(),
+                    args: vec![SmartValue::from(bid)],
+                })
+        })
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+
+    // Asserting more than the context holds fails
+    let transaction2 = TransactionBuilder::new(&executor)
+        .withdraw(10.into(), RADIX_TOKEN, account)
+        .assert_context_contains(11.into(), RADIX_TOKEN)
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt2 = executor.run(transaction2, true).unwrap();
+    assert!(!receipt2.success);
+    assert!(matches!(
+        receipt2.results.last(),
+        Some(Err(RuntimeError::ContextAssertionFailed(..)))
+    ));
+
+    // Asserting more than a bucket holds fails
+    let transaction3 = TransactionBuilder::new(&executor)
+        .withdraw(10.into(), RADIX_TOKEN, account)
+        .declare_bucket(|builder, bid| {
+            builder
+                .create_bucket(5.into(), RADIX_TOKEN, bid)
+                .assert_bucket_amount_at_least(bid, 6.into())
+        })
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let receipt3 = executor.run(transaction3, true).unwrap();
+    assert!(!receipt3.success);
+    assert!(matches!(
+        receipt3.results.last(),
+        Some(Err(RuntimeError::BucketAssertionFailed(..)))
+    ));
+}
+
+#[test]
+fn test_decode_limits() {
+    compile();
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+
+    // Package code may be longer than any other byte vector, so pad a module with a custom section
+    let payload = vec![0u8; 5 << 20];
+    let mut code = include_code!("./everything").to_vec();
+    code.push(0);
+    let mut size = payload.len() as u32 + 2;
+    while size >= 0x80 {
+        code.push((size & 0x7f) as u8 | 0x80);
+        size >>= 7;
+    }
+    code.push(size as u8);
+    code.extend([1, b'x']);
+    code.extend(&payload);
+    let transaction1 = TransactionBuilder::new(&executor)
+        .publish_package(&code)
+        .build(vec![key])
+        .unwrap();
+    let receipt1 = executor.run(transaction1, true).unwrap();
+    assert!(receipt1.success);
+
+    // Other arguments are held to the tighter length limit
+    let transaction2 = Transaction {
+        instructions: vec![
+            Instruction::CallFunction {
+                package: ACCOUNT_PACKAGE,
+                name: "Account".to_owned(),
+                function: "new".to_owned(),
+                args: vec![SmartValue::from(payload)],
+            },
+            Instruction::End { signers: vec![key] },
+        ],
+    };
+    let receipt2 = executor.run(transaction2, true).unwrap();
+    assert!(!receipt2.success);
+    assert!(matches!(
+        receipt2.results.last(),
+        Some(Err(RuntimeError::InvalidCallArgs(..)))
+    ));
+}
@@ -14,7 +14,7 @@ fn missing_memory_should_cause_error() {
     let mut test_runner = TestRunner::new(&mut substate_store);
 
     // Act
-    let code: Vec<u8> = wabt::wat2wasm(
+    let code: Vec<u8> = wat::parse_str(
         r#"
             (module
                 (func (export "test") (result i32)
@@ -14,137 +14,150 @@ macro_rules! trace {
 pub fn handle_decode(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_decode() starts");
 
-    let DeriveInput { ident, data, .. } = parse2(input)?;
+    let DeriveInput {
+        attrs,
+        ident,
+        data,
+        generics,
+        ..
+    } = parse2(input)?;
+    let bounds = match parse_bound(&attrs, "decode_bound")? {
+        Some(bounds) => bounds,
+        None => {
+            let mut bounds = bound_params(
+                &field_params(&generics, &data, |f| !is_skipped(f)),
+                quote! { ::sbor::Decode<'de> },
+            );
+            // Skipped and missing fields take their default values
+            bounds.extend(bound_params(
+                &field_params(&generics, &data, |f| is_skipped(f) || is_default(f)),
+                quote! { Default },
+            ));
+            bounds
+        }
+    };
+    let mut de_generics = decode_generics(&generics);
+    de_generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, _, where_clause) = de_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
     trace!("Decoding: {}", ident);
 
     let output = match data {
-        Data::Struct(s) => match s.fields {
-            syn::Fields::Named(FieldsNamed { named, .. }) => {
-                // ns: not skipped
-                let ns: Vec<&Field> = named.iter().filter(|f| !is_skipped(f)).collect();
-                let ns_n = Index::from(ns.len());
-                let ns_ids = ns.iter().map(|f| &f.ident);
-                let ns_types = ns.iter().map(|f| &f.ty);
-                let s: Vec<&Field> = named.iter().filter(|f| is_skipped(f)).collect();
-                let s_ids = s.iter().map(|f| &f.ident);
-                let s_types = s.iter().map(|f| &f.ty);
-                quote! {
-                    impl ::sbor::Decode for #ident {
-                        fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
-                            use ::sbor::{self, Decode};
-                            decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
-                            decoder.check_len(#ns_n)?;
-                            Ok(Self {
-                                #(#ns_ids: <#ns_types>::decode(decoder)?,)*
-                                #(#s_ids: <#s_types>::default()),*
-                            })
+        Data::Struct(s) => {
+            let body = decode_fields(quote! { Self }, &s.fields)?;
+            let uses = match s.fields {
+                syn::Fields::Unit => quote! {},
+                _ => quote! { use ::sbor::{self, Decode}; },
+            };
+            match parse_version(&attrs)? {
+                Some(version) => {
+                    let n = version.version;
+                    let index = version.index();
+                    // Data of an earlier version is decoded as that version, then upgraded
+                    let (version_const, previous_arm) = match version.previous {
+                        Some(prev) => (
+                            quote! {
+                                {
+                                    assert!(
+                                        <#prev as ::sbor::Versioned<'de>>::VERSION + 1 == #n,
+                                        "The previous version must be one less than this version"
+                                    );
+                                    #n
+                                }
+                            },
+                            quote! {
+                                else if index < #index {
+                                    <#prev as ::sbor::Versioned<'de>>::decode_version(decoder, index)
+                                        .map(Into::into)
+                                }
+                            },
+                        ),
+                        None => (quote! { #n }, quote! {}),
+                    };
+                    quote! {
+                        impl #impl_generics ::sbor::Decode<'de> for #ident #ty_generics #where_clause {
+                            fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                                let index = decoder.read_u8()?;
+                                <Self as ::sbor::Versioned<'de>>::decode_version(decoder, index)
+                            }
                         }
-                    }
-                }
-            }
-            syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                let mut all_exprs = Vec::<Expr>::new();
-                for f in &unnamed {
-                    let ty = &f.ty;
-                    if is_skipped(f) {
-                        all_exprs.push(parse_quote! {<#ty>::default()})
-                    } else {
-                        all_exprs.push(parse_quote! {<#ty>::decode(decoder)?})
-                    }
-                }
-                let ns_n = Index::from(unnamed.iter().filter(|f| !is_skipped(f)).count());
-                quote! {
-                    impl ::sbor::Decode for #ident {
-                        fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
-                            use ::sbor::{self, Decode};
-                            decoder.check_type(::sbor::type_id::TYPE_FIELDS_UNNAMED)?;
-                            decoder.check_len(#ns_n)?;
-                            Ok(Self (
-                                #(#all_exprs,)*
-                            ))
+
+                        impl #impl_generics ::sbor::Versioned<'de> for #ident #ty_generics #where_clause {
+                            const VERSION: u8 = #version_const;
+
+                            fn decode_version(decoder: &mut ::sbor::Decoder<'de>, index: u8) -> Result<Self, ::sbor::DecodeError> {
+                                #uses
+                                if index == Self::VERSION - 1 {
+                                    #body
+                                }
+                                #previous_arm
+                                else {
+                                    Err(::sbor::DecodeError::InvalidIndex(index))
+                                }
+                            }
                         }
                     }
                 }
-            }
-            syn::Fields::Unit => {
-                quote! {
-                    impl ::sbor::Decode for #ident {
-                        fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
-                            decoder.check_type(::sbor::type_id::TYPE_FIELDS_UNIT)?;
-                            Ok(Self {})
+                None => {
+                    quote! {
+                        impl #impl_generics ::sbor::Decode<'de> for #ident #ty_generics #where_clause {
+                            fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                                #uses
+                                #body
+                            }
                         }
                     }
                 }
             }
-        },
+        }
         Data::Enum(DataEnum { variants, .. }) => {
-            let match_arms = variants.iter().enumerate().map(|(i, v)| {
+            if parse_version(&attrs)?.is_some() {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "Only structs can be versioned",
+                ));
+            }
+            let discriminators = parse_discriminators(&variants)?;
+            let mut match_arms = Vec::new();
+            for (v, d) in variants.iter().zip(&discriminators) {
                 let v_id = &v.ident;
-                let v_ith = i as u8;
-                match &v.fields {
-                    syn::Fields::Named(FieldsNamed { named, .. }) => {
-                        let ns: Vec<&Field> = named.iter().filter(|f| !is_skipped(f)).collect();
-                        let ns_n = Index::from(ns.len());
-                        let ns_ids = ns.iter().map(|f| &f.ident);
-                        let ns_types = ns.iter().map(|f| &f.ty);
-                        let s: Vec<&Field> = named.iter().filter(|f| is_skipped(f)).collect();
-                        let s_ids = s.iter().map(|f| &f.ident);
-                        let s_types = s.iter().map(|f| &f.ty);
-                        quote! {
-                            #v_ith => {
-                                decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
-                                decoder.check_len(#ns_n)?;
-
-                                Ok(Self::#v_id {
-                                    #(#ns_ids: <#ns_types>::decode(decoder)?,)*
-                                    #(#s_ids: <#s_types>::default(),)*
-                                })
-                            }
-                        }
+                let discriminator = d.literal();
+                let body = decode_fields(quote! { Self::#v_id }, &v.fields)?;
+                match_arms.push(quote! {
+                    #discriminator => {
+                        #body
                     }
-                    syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                        let mut all_exprs = Vec::<Expr>::new();
-                        for f in unnamed {
-                            let ty = &f.ty;
-                            if is_skipped(f) {
-                                all_exprs.push(parse_quote! {<#ty>::default()})
-                            } else {
-                                all_exprs.push(parse_quote! {<#ty>::decode(decoder)?})
-                            }
-                        }
-                        let ns_n = Index::from(unnamed.iter().filter(|f| !is_skipped(f)).count());
-                        quote! {
-                            #v_ith => {
-                                decoder.check_type(::sbor::type_id::TYPE_FIELDS_UNNAMED)?;
-                                decoder.check_len(#ns_n)?;
+                });
+            }
 
-                                Ok(Self::#v_id (
-                                    #(#all_exprs),*
-                                ))
-                            }
-                        }
-                    }
-                    syn::Fields::Unit => {
-                        quote! {
-                            #v_ith => {
-                                decoder.check_type(::sbor::type_id::TYPE_FIELDS_UNIT)?;
-                                Ok(Self::#v_id)
-                            }
-                        }
-                    }
-                }
-            });
+            let (read, scrutinee, invalid) = match discriminators.first() {
+                Some(Discriminator::U16(_)) => (
+                    quote! { <u16>::decode_value(decoder)? },
+                    quote! { index },
+                    quote! { ::sbor::DecodeError::InvalidDiscriminator(::sbor::describe::Discriminator::U16(index)) },
+                ),
+                Some(Discriminator::String(_)) => (
+                    quote! { <::sbor::rust::string::String>::decode_value(decoder)? },
+                    quote! { index.as_str() },
+                    quote! { ::sbor::DecodeError::InvalidDiscriminator(::sbor::describe::Discriminator::String(index)) },
+                ),
+                _ => (
+                    quote! { decoder.read_u8()? },
+                    quote! { index },
+                    quote! { ::sbor::DecodeError::InvalidIndex(index) },
+                ),
+            };
 
             quote! {
-                impl ::sbor::Decode for #ident {
+                impl #impl_generics ::sbor::Decode<'de> for #ident #ty_generics #where_clause {
                     #[inline]
-                    fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
                         use ::sbor::{self, Decode};
 
-                        let index = decoder.read_u8()?;
-                        match index {
+                        let index = #read;
+                        match #scrutinee {
                             #(#match_arms,)*
-                            _ => Err(::sbor::DecodeError::InvalidIndex(index))
+                            _ => Err(#invalid)
                         }
                     }
                 }
@@ -162,6 +175,97 @@ pub fn handle_decode(input: TokenStream) -> Result<TokenStream> {
     Ok(output)
 }
 
+/// Returns the statements that decode the given fields and construct `ctor` from them.
+///
+/// Fields marked `#[sbor(default)]` may be missing from the input, which lets fields be appended
+/// to a type without breaking data encoded before; they must come after all other encoded fields.
+fn decode_fields(ctor: TokenStream, fields: &syn::Fields) -> Result<TokenStream> {
+    let (fields_type, named) = match fields {
+        syn::Fields::Named(FieldsNamed { named, .. }) => {
+            (quote! { ::sbor::type_id::TYPE_FIELDS_NAMED }, named)
+        }
+        syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+            (quote! { ::sbor::type_id::TYPE_FIELDS_UNNAMED }, unnamed)
+        }
+        syn::Fields::Unit => {
+            return Ok(quote! {
+                decoder.check_type(::sbor::type_id::TYPE_FIELDS_UNIT)?;
+                Ok(#ctor)
+            })
+        }
+    };
+
+    // ns: not skipped
+    let ns: Vec<&Field> = named.iter().filter(|f| !is_skipped(f)).collect();
+    let ns_n = ns.len();
+    let required = ns.iter().take_while(|f| !is_default(f)).count();
+    if let Some(f) = ns[required..].iter().find(|f| !is_default(f)) {
+        return Err(Error::new_spanned(
+            f,
+            "Fields with `#[sbor(default)]` must come after all other encoded fields",
+        ));
+    }
+
+    let mut exprs = Vec::<Expr>::new();
+    let mut position = 0;
+    for f in named {
+        let ty = &f.ty;
+        if is_skipped(f) {
+            exprs.push(parse_quote! { <#ty>::default() });
+        } else {
+            if position < required {
+                exprs.push(parse_quote! { <#ty>::decode(decoder)? });
+            } else {
+                let i = Index::from(position);
+                exprs.push(parse_quote! {
+                    if len > #i { <#ty>::decode(decoder)? } else { <#ty>::default() }
+                });
+            }
+            position += 1;
+        }
+    }
+
+    let check_len = if required == ns_n {
+        let ns_n = Index::from(ns_n);
+        quote! { decoder.check_len(#ns_n)?; }
+    } else {
+        let required = Index::from(required);
+        quote! {
+            let len = decoder.read_len()?;
+            if !(#required..=#ns_n).contains(&len) {
+                return Err(::sbor::DecodeError::InvalidLength { expected: #ns_n, actual: len });
+            }
+        }
+    };
+
+    let construct = match fields {
+        syn::Fields::Named(_) => {
+            let ids = named.iter().map(|f| &f.ident);
+            quote! { #ctor { #(#ids: #exprs,)* } }
+        }
+        _ => quote! { #ctor ( #(#exprs),* ) },
+    };
+
+    Ok(quote! {
+        decoder.check_type(#fields_type)?;
+        #check_len
+        Ok(#construct)
+    })
+}
+
+/// Returns the generics of a `Decode<'de>` impl, where the input outlives every lifetime of the type.
+fn decode_generics(generics: &Generics) -> Generics {
+    let lifetimes = generics.lifetimes().map(|l| &l.lifetime);
+    let de: LifetimeDef = if generics.lifetimes().next().is_some() {
+        parse_quote!('de: #(#lifetimes)+*)
+    } else {
+        parse_quote!('de)
+    };
+    let mut generics = generics.clone();
+    generics.params.insert(0, GenericParam::Lifetime(de));
+    generics
+}
+
 #[cfg(test)]
 mod tests {
     use proc_macro2::TokenStream;
@@ -181,8 +285,8 @@ mod tests {
         assert_code_eq(
             output,
             quote! {
-                impl ::sbor::Decode for Test {
-                    fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
+                impl<'de> ::sbor::Decode<'de> for Test {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
                         use ::sbor::{self, Decode};
                         decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
                         decoder.check_len(1)?;
@@ -195,6 +299,165 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_borrowed() {
+        let input = TokenStream::from_str("struct Test<'a> {a: &'a str}").unwrap();
+        let output = handle_decode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<'de: 'a, 'a> ::sbor::Decode<'de> for Test<'a> {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                        use ::sbor::{self, Decode};
+                        decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
+                        decoder.check_len(1)?;
+                        Ok(Self {
+                            a: <&'a str>::decode(decoder)?,
+                        })
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_decode_generic_struct() {
+        let input = TokenStream::from_str(
+            "#[sbor(encode_bound = \"\")] struct Test<'a, T, U> {a: &'a T, #[sbor(skip)] b: U}",
+        )
+        .unwrap();
+        let output = handle_decode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<'de: 'a, 'a, T, U> ::sbor::Decode<'de> for Test<'a, T, U>
+                where
+                    T: ::sbor::Decode<'de>,
+                    U: Default
+                {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                        use ::sbor::{self, Decode};
+                        decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
+                        decoder.check_len(1)?;
+                        Ok(Self {
+                            a: <&'a T>::decode(decoder)?,
+                            b: <U>::default(),
+                        })
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_decode_bound() {
+        let input = TokenStream::from_str(
+            "#[sbor(bound = \"T: Clone\", decode_bound = \"T: Decode<'de>\")] struct Test<T> {a: Rc<T>}",
+        )
+        .unwrap();
+        let output = handle_decode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<'de, T> ::sbor::Decode<'de> for Test<T>
+                where
+                    T: Decode<'de>
+                {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                        use ::sbor::{self, Decode};
+                        decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
+                        decoder.check_len(1)?;
+                        Ok(Self {
+                            a: <Rc<T> >::decode(decoder)?,
+                        })
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_decode_default_field() {
+        let input = TokenStream::from_str("struct Test {a: u32, #[sbor(default)] b: u8}").unwrap();
+        let output = handle_decode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<'de> ::sbor::Decode<'de> for Test {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                        use ::sbor::{self, Decode};
+                        decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
+                        let len = decoder.read_len()?;
+                        if !(1..=2usize).contains(&len) {
+                            return Err(::sbor::DecodeError::InvalidLength { expected: 2usize, actual: len });
+                        }
+                        Ok(Self {
+                            a: <u32>::decode(decoder)?,
+                            b: if len > 1 { <u8>::decode(decoder)? } else { <u8>::default() },
+                        })
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_decode_versioned() {
+        let input = TokenStream::from_str(
+            "#[sbor(version = 2, previous = \"TestV1\")] struct Test {a: u32}",
+        )
+        .unwrap();
+        let output = handle_decode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<'de> ::sbor::Decode<'de> for Test {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                        let index = decoder.read_u8()?;
+                        <Self as ::sbor::Versioned<'de>>::decode_version(decoder, index)
+                    }
+                }
+
+                impl<'de> ::sbor::Versioned<'de> for Test {
+                    const VERSION: u8 = {
+                        assert!(
+                            <TestV1 as ::sbor::Versioned<'de>>::VERSION + 1 == 2u8,
+                            "The previous version must be one less than this version"
+                        );
+                        2u8
+                    };
+
+                    fn decode_version(decoder: &mut ::sbor::Decoder<'de>, index: u8) -> Result<Self, ::sbor::DecodeError> {
+                        use ::sbor::{self, Decode};
+                        if index == Self::VERSION - 1 {
+                            decoder.check_type(::sbor::type_id::TYPE_FIELDS_NAMED)?;
+                            decoder.check_len(1)?;
+                            Ok(Self {
+                                a: <u32>::decode(decoder)?,
+                            })
+                        } else if index < 1u8 {
+                            <TestV1 as ::sbor::Versioned<'de>>::decode_version(decoder, index)
+                                .map(Into::into)
+                        } else {
+                            Err(::sbor::DecodeError::InvalidIndex(index))
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_default_field_order() {
+        let input = TokenStream::from_str("struct Test {#[sbor(default)] a: u32, b: u8}").unwrap();
+        assert!(handle_decode(input).is_err());
+    }
+
     #[test]
     fn test_decode_enum() {
         let input = TokenStream::from_str("enum Test {A, B (u32), C {x: u8}}").unwrap();
@@ -203,9 +466,9 @@ mod tests {
         assert_code_eq(
             output,
             quote! {
-                impl ::sbor::Decode for Test {
+                impl<'de> ::sbor::Decode<'de> for Test {
                     #[inline]
-                    fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
                         use ::sbor::{self, Decode};
                         let index = decoder.read_u8()?;
                         match index {
@@ -232,4 +495,53 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_decode_string_discriminators() {
+        let input = TokenStream::from_str(
+            "enum Test {#[sbor(discriminator = \"a\")] A, #[sbor(discriminator = \"b\")] B}",
+        )
+        .unwrap();
+        let output = handle_decode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<'de> ::sbor::Decode<'de> for Test {
+                    #[inline]
+                    fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                        use ::sbor::{self, Decode};
+                        let index = <::sbor::rust::string::String>::decode_value(decoder)?;
+                        match index.as_str() {
+                            "a" => {
+                                decoder.check_type(::sbor::type_id::TYPE_FIELDS_UNIT)?;
+                                Ok(Self::A)
+                            },
+                            "b" => {
+                                decoder.check_type(::sbor::type_id::TYPE_FIELDS_UNIT)?;
+                                Ok(Self::B)
+                            },
+                            _ => Err(::sbor::DecodeError::InvalidDiscriminator(
+                                ::sbor::describe::Discriminator::String(index)
+                            ))
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_invalid_discriminators() {
+        for input in [
+            "enum Test {#[sbor(discriminator = 1)] A, #[sbor(discriminator = 1)] B}",
+            "enum Test {#[sbor(discriminator = 1)] A, B}",
+            "enum Test {#[sbor(discriminator = 1)] A, #[sbor(discriminator = \"b\")] B}",
+            "enum Test {#[sbor(discriminator = 1u8)] A, #[sbor(discriminator = 300)] B}",
+            "enum Test {#[sbor(discriminator = 1i32)] A}",
+        ] {
+            let input = TokenStream::from_str(input).unwrap();
+            assert!(handle_decode(input).is_err());
+        }
+    }
 }
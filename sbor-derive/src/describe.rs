@@ -14,11 +14,71 @@ macro_rules! trace {
 pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_describe() starts");
 
-    let DeriveInput { ident, data, .. } = parse2(input)?;
+    let DeriveInput {
+        attrs,
+        ident,
+        data,
+        generics,
+        ..
+    } = parse2(input)?;
+    // Every type parameter is described, as the type arguments of the type
+    let params: Vec<Ident> = generics.type_params().map(|p| p.ident.clone()).collect();
+    let type_args = quote! { vec![#(<#params as ::sbor::Describe>::describe()),*] };
+    let mut generics = generics;
+    let bounds = match parse_bound(&attrs, "describe_bound")? {
+        Some(bounds) => bounds,
+        None => bound_params(&params, quote! { ::sbor::Describe }),
+    };
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let ident_str = ident.to_string();
     trace!("Describing: {}", ident);
 
+    let version = parse_version(&attrs)?;
     let output = match data {
+        Data::Struct(s) if version.is_some() => {
+            let version = version.expect("Checked by the guard");
+            let name = format!("V{}", version.version);
+            let fields = describe_fields(&s.fields);
+            let discriminator = Discriminator::U8(version.index()).describe();
+            let variant = quote! {
+                ::sbor::describe::Variant {
+                    name: #name.to_owned(),
+                    fields: #fields,
+                    discriminator: Some(#discriminator),
+                }
+            };
+            // The variants of earlier versions come first, in the order they are encoded
+            let variants = match version.previous {
+                Some(prev) => quote! {
+                    let mut variants = match <#prev>::describe() {
+                        ::sbor::describe::Type::Enum { variants, .. } => variants,
+                        _ => vec![],
+                    };
+                    variants.push(#variant);
+                },
+                None => quote! {
+                    let variants = vec![#variant];
+                },
+            };
+
+            quote! {
+                impl #impl_generics ::sbor::Describe for #ident #ty_generics #where_clause {
+                    fn describe() -> ::sbor::describe::Type {
+                        use ::sbor::rust::borrow::ToOwned;
+                        use ::sbor::rust::vec;
+                        use ::sbor::Describe;
+
+                        #variants
+                        ::sbor::describe::Type::Enum {
+                            name: #ident_str.to_owned(),
+                            variants,
+                            generics: #type_args,
+                        }
+                    }
+                }
+            }
+        }
         Data::Struct(s) => match s.fields {
             syn::Fields::Named(FieldsNamed { named, .. }) => {
                 // ns: not skipped
@@ -33,7 +93,7 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                 let types = ns.iter().map(|f| &f.ty);
 
                 quote! {
-                    impl ::sbor::Describe for #ident {
+                    impl #impl_generics ::sbor::Describe for #ident #ty_generics #where_clause {
                         fn describe() -> ::sbor::describe::Type {
                             use ::sbor::rust::borrow::ToOwned;
                             use ::sbor::rust::vec;
@@ -44,6 +104,7 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                                 fields: ::sbor::describe::Fields::Named {
                                     named: vec![#((#names.to_owned(), <#types>::describe())),*]
                                 },
+                                generics: #type_args,
                             }
                         }
                     }
@@ -55,7 +116,7 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                 let types = ns.iter().map(|f| &f.ty);
 
                 quote! {
-                    impl ::sbor::Describe for #ident {
+                    impl #impl_generics ::sbor::Describe for #ident #ty_generics #where_clause {
                         fn describe() -> ::sbor::describe::Type {
                             use ::sbor::rust::borrow::ToOwned;
                             use ::sbor::rust::vec;
@@ -66,6 +127,7 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                                 fields: ::sbor::describe::Fields::Unnamed {
                                     unnamed: vec![#(<#types>::describe()),*]
                                 },
+                                generics: #type_args,
                             }
                         }
                     }
@@ -73,13 +135,15 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
             }
             syn::Fields::Unit => {
                 quote! {
-                    impl ::sbor::Describe for #ident {
+                    impl #impl_generics ::sbor::Describe for #ident #ty_generics #where_clause {
                         fn describe() -> ::sbor::describe::Type {
                             use ::sbor::rust::borrow::ToOwned;
+                            use ::sbor::rust::vec;
 
                             ::sbor::describe::Type::Struct {
                                 name: #ident_str.to_owned(),
                                 fields: ::sbor::describe::Fields::Unit,
+                                generics: #type_args,
                             }
                         }
                     }
@@ -88,54 +152,12 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
         },
         Data::Enum(DataEnum { variants, .. }) => {
             let names = variants.iter().map(|v| v.ident.to_string());
-            let fields = variants.iter().map(|v| {
-                let f = &v.fields;
-
-                match f {
-                    syn::Fields::Named(FieldsNamed { named, .. }) => {
-                        let ns: Vec<&Field> = named.iter().filter(|f| !is_skipped(f)).collect();
-
-                        let names = ns.iter().map(|f| {
-                            f.ident
-                                .clone()
-                                .expect("All fields must be named")
-                                .to_string()
-                        });
-                        let types = ns.iter().map(|f| &f.ty);
-
-                        quote! {
-                            {
-                                ::sbor::describe::Fields::Named {
-                                    named: vec![#((#names.to_owned(), <#types>::describe())),*]
-                                }
-                            }
-                        }
-                    }
-                    syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                        let ns: Vec<&Field> = unnamed.iter().filter(|f| !is_skipped(f)).collect();
-
-                        let types = ns.iter().map(|f| &f.ty);
-
-                        quote! {
-                            {
-                                ::sbor::describe::Fields::Unnamed {
-                                    unnamed: vec![#(<#types>::describe()),*]
-                                }
-                            }
-                        }
-                    }
-                    syn::Fields::Unit => {
-                        quote! {
-                            {
-                                ::sbor::describe::Fields::Unit
-                            }
-                        }
-                    }
-                }
-            });
+            let fields = variants.iter().map(|v| describe_fields(&v.fields));
+            let discriminators = parse_discriminators(&variants)?;
+            let discriminators = discriminators.iter().map(Discriminator::describe);
 
             quote! {
-                impl ::sbor::Describe for #ident {
+                impl #impl_generics ::sbor::Describe for #ident #ty_generics #where_clause {
                     fn describe() -> ::sbor::describe::Type {
                         use ::sbor::rust::borrow::ToOwned;
                         use ::sbor::rust::vec;
@@ -146,9 +168,11 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                             variants: vec![
                                 #(::sbor::describe::Variant {
                                     name: #names.to_owned(),
-                                    fields: #fields
+                                    fields: #fields,
+                                    discriminator: Some(#discriminators),
                                 }),*
-                            ]
+                            ],
+                            generics: #type_args,
                         }
                     }
                 }
@@ -166,6 +190,51 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
     Ok(output)
 }
 
+/// Returns the description of the fields of an enum variant.
+fn describe_fields(fields: &syn::Fields) -> TokenStream {
+    match fields {
+        syn::Fields::Named(FieldsNamed { named, .. }) => {
+            let ns: Vec<&Field> = named.iter().filter(|f| !is_skipped(f)).collect();
+
+            let names = ns.iter().map(|f| {
+                f.ident
+                    .clone()
+                    .expect("All fields must be named")
+                    .to_string()
+            });
+            let types = ns.iter().map(|f| &f.ty);
+
+            quote! {
+                {
+                    ::sbor::describe::Fields::Named {
+                        named: vec![#((#names.to_owned(), <#types>::describe())),*]
+                    }
+                }
+            }
+        }
+        syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+            let ns: Vec<&Field> = unnamed.iter().filter(|f| !is_skipped(f)).collect();
+
+            let types = ns.iter().map(|f| &f.ty);
+
+            quote! {
+                {
+                    ::sbor::describe::Fields::Unnamed {
+                        unnamed: vec![#(<#types>::describe()),*]
+                    }
+                }
+            }
+        }
+        syn::Fields::Unit => {
+            quote! {
+                {
+                    ::sbor::describe::Fields::Unit
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use proc_macro2::TokenStream;
@@ -196,6 +265,7 @@ mod tests {
                             fields: ::sbor::describe::Fields::Named {
                                 named: vec![("a".to_owned(), <u32>::describe())]
                             },
+                            generics: vec![],
                         }
                     }
                 }
@@ -222,21 +292,25 @@ mod tests {
                             variants: vec![
                                 ::sbor::describe::Variant {
                                     name: "A".to_owned(),
-                                    fields: { ::sbor::describe::Fields::Unit }
+                                    fields: { ::sbor::describe::Fields::Unit },
+                                    discriminator: Some(::sbor::describe::Discriminator::U8(0u8)),
                                 },
                                 ::sbor::describe::Variant {
                                     name: "B".to_owned(),
                                     fields: {
                                         ::sbor::describe::Fields::Unnamed { unnamed: vec![<u32>::describe()] }
-                                    }
+                                    },
+                                    discriminator: Some(::sbor::describe::Discriminator::U8(1u8)),
                                 },
                                 ::sbor::describe::Variant {
                                     name: "C".to_owned(),
                                     fields: {
                                         ::sbor::describe::Fields::Named { named: vec![("x".to_owned(), <u8>::describe())] }
-                                    }
+                                    },
+                                    discriminator: Some(::sbor::describe::Discriminator::U8(2u8)),
                                 }
-                            ]
+                            ],
+                            generics: vec![],
                         }
                     }
                 }
@@ -261,6 +335,7 @@ mod tests {
                         ::sbor::describe::Type::Struct {
                             name: "Test".to_owned(),
                             fields: ::sbor::describe::Fields::Named { named: vec![] },
+                            generics: vec![],
                         }
                     }
                 }
@@ -289,21 +364,61 @@ mod tests {
                             variants: vec![
                                 ::sbor::describe::Variant {
                                     name: "A".to_owned(),
-                                    fields: { ::sbor::describe::Fields::Unit }
+                                    fields: { ::sbor::describe::Fields::Unit },
+                                    discriminator: Some(::sbor::describe::Discriminator::U8(0u8)),
                                 },
                                 ::sbor::describe::Variant {
                                     name: "B".to_owned(),
                                     fields: {
                                         ::sbor::describe::Fields::Unnamed { unnamed: vec![] }
-                                    }
+                                    },
+                                    discriminator: Some(::sbor::describe::Discriminator::U8(1u8)),
                                 },
                                 ::sbor::describe::Variant {
                                     name: "C".to_owned(),
                                     fields: {
                                         ::sbor::describe::Fields::Named { named: vec![] }
-                                    }
+                                    },
+                                    discriminator: Some(::sbor::describe::Discriminator::U8(2u8)),
                                 }
-                            ]
+                            ],
+                            generics: vec![],
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_describe_generic_struct() {
+        let input =
+            TokenStream::from_str("struct Test<T, U> {a: Vec<T>, #[sbor(skip)] b: PhantomData<U>}")
+                .unwrap();
+        let output = handle_describe(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<T, U> ::sbor::Describe for Test<T, U>
+                where
+                    T: ::sbor::Describe,
+                    U: ::sbor::Describe
+                {
+                    fn describe() -> ::sbor::describe::Type {
+                        use ::sbor::rust::borrow::ToOwned;
+                        use ::sbor::rust::vec;
+                        use ::sbor::Describe;
+
+                        ::sbor::describe::Type::Struct {
+                            name: "Test".to_owned(),
+                            fields: ::sbor::describe::Fields::Named {
+                                named: vec![("a".to_owned(), <Vec<T> >::describe())]
+                            },
+                            generics: vec![
+                                <T as ::sbor::Describe>::describe(),
+                                <U as ::sbor::Describe>::describe()
+                            ],
                         }
                     }
                 }
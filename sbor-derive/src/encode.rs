@@ -1,4 +1,4 @@
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Literal, Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::*;
 
@@ -14,61 +14,103 @@ macro_rules! trace {
 pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_encode() starts");
 
-    let DeriveInput { ident, data, .. } = parse2(input)?;
+    let DeriveInput {
+        attrs,
+        ident,
+        data,
+        generics,
+        ..
+    } = parse2(input)?;
+    let mut generics = generics;
+    let bounds = match parse_bound(&attrs, "encode_bound")? {
+        Some(bounds) => bounds,
+        None => bound_params(
+            &field_params(&generics, &data, |f| !is_skipped(f)),
+            quote! { ::sbor::Encode },
+        ),
+    };
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     trace!("Encoding: {}", ident);
 
     let output = match data {
-        Data::Struct(s) => match s.fields {
-            syn::Fields::Named(FieldsNamed { named, .. }) => {
-                // ns: not skipped
-                let ns: Vec<&Field> = named.iter().filter(|f| !is_skipped(f)).collect();
-                let ns_n = Index::from(ns.len());
-                let ns_ids = ns.iter().map(|f| &f.ident);
-                quote! {
-                    impl ::sbor::Encode for #ident {
-                        fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
-                            use ::sbor::{self, Encode};
-                            encoder.write_type(::sbor::type_id::TYPE_FIELDS_NAMED);
-                            encoder.write_len(#ns_n);
-                            #(
-                                self.#ns_ids.encode(encoder);
-                            )*
+        Data::Struct(s) => {
+            // A versioned struct is encoded as the variant of its version
+            let version = parse_version(&attrs)?.map(|v| {
+                let index = v.index();
+                quote! { encoder.write_u8(#index); }
+            });
+            match s.fields {
+                syn::Fields::Named(FieldsNamed { named, .. }) => {
+                    // ns: not skipped
+                    let ns: Vec<&Field> = named.iter().filter(|f| !is_skipped(f)).collect();
+                    let ns_n = Index::from(ns.len());
+                    let ns_ids = ns.iter().map(|f| &f.ident);
+                    quote! {
+                        impl #impl_generics ::sbor::Encode for #ident #ty_generics #where_clause {
+                            fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                                use ::sbor::{self, Encode};
+                                #version
+                                encoder.write_type(::sbor::type_id::TYPE_FIELDS_NAMED);
+                                encoder.write_len(#ns_n);
+                                #(
+                                    self.#ns_ids.encode(encoder);
+                                )*
+                            }
                         }
                     }
                 }
-            }
-            syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                let mut ns_idx = Vec::new();
-                for (i, f) in unnamed.iter().enumerate() {
-                    if !is_skipped(f) {
-                        ns_idx.push(Index::from(i));
+                syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
+                    let mut ns_idx = Vec::new();
+                    for (i, f) in unnamed.iter().enumerate() {
+                        if !is_skipped(f) {
+                            ns_idx.push(Index::from(i));
+                        }
                     }
-                }
-                let ns_n = Index::from(ns_idx.len());
-                quote! {
-                    impl ::sbor::Encode for #ident {
-                        fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
-                            use ::sbor::{self, Encode};
-                            encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNNAMED);
-                            encoder.write_len(#ns_n);
-                            #(self.#ns_idx.encode(encoder);)*
+                    let ns_n = Index::from(ns_idx.len());
+                    quote! {
+                        impl #impl_generics ::sbor::Encode for #ident #ty_generics #where_clause {
+                            fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                                use ::sbor::{self, Encode};
+                                #version
+                                encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNNAMED);
+                                encoder.write_len(#ns_n);
+                                #(self.#ns_idx.encode(encoder);)*
+                            }
                         }
                     }
                 }
-            }
-            syn::Fields::Unit => {
-                quote! {
-                    impl ::sbor::Encode for #ident {
-                        fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
-                            encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNIT);
+                syn::Fields::Unit => {
+                    quote! {
+                        impl #impl_generics ::sbor::Encode for #ident #ty_generics #where_clause {
+                            fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                                #version
+                                encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNIT);
+                            }
                         }
                     }
                 }
             }
-        },
+        }
         Data::Enum(DataEnum { variants, .. }) => {
-            let match_arms = variants.iter().enumerate().map(|(i, v)| {
-                let v_ith = Index::from(i);
+            if parse_version(&attrs)?.is_some() {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "Only structs can be versioned",
+                ));
+            }
+            let discriminators = parse_discriminators(&variants)?;
+            let match_arms = variants.iter().zip(&discriminators).map(|(v, d)| {
+                let discriminator = match d {
+                    Discriminator::U8(d) => {
+                        let d = Literal::u8_unsuffixed(*d);
+                        quote! { encoder.write_u8(#d); }
+                    }
+                    d => {
+                        let d = d.literal();
+                        quote! { #d.encode_value(encoder); }
+                    }
+                };
                 let v_id = &v.ident;
                 match &v.fields {
                     syn::Fields::Named(FieldsNamed { named, .. }) => {
@@ -78,7 +120,7 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
                         let ns_n = Index::from(ns.len());
                         quote! {
                             Self::#v_id {#(#ns_ids,)* ..} => {
-                                encoder.write_u8(#v_ith);
+                                #discriminator
                                 encoder.write_type(::sbor::type_id::TYPE_FIELDS_NAMED);
                                 encoder.write_len(#ns_n);
                                 #(
@@ -98,7 +140,7 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
                         let ns_n = Index::from(ns_args.len());
                         quote! {
                             Self::#v_id (#(#all_args),*) => {
-                                encoder.write_u8(#v_ith);
+                                #discriminator
                                 encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNNAMED);
                                 encoder.write_len(#ns_n);
                                 #(#ns_args.encode(encoder);)*
@@ -108,7 +150,7 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
                     syn::Fields::Unit => {
                         quote! {
                             Self::#v_id => {
-                                encoder.write_u8(#v_ith);
+                                #discriminator
                                 encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNIT);
                             }
                         }
@@ -117,7 +159,7 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
             });
 
             quote! {
-                impl ::sbor::Encode for #ident {
+                impl #impl_generics ::sbor::Encode for #ident #ty_generics #where_clause {
                     fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
                         use ::sbor::{self, Encode};
 
@@ -171,6 +213,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encode_generic_struct() {
+        let input = TokenStream::from_str(
+            "struct Test<K: Ord, V, M> {a: BTreeMap<K, V>, b: PhantomData<M>}",
+        )
+        .unwrap();
+        let output = handle_encode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<K: Ord, V, M> ::sbor::Encode for Test<K, V, M>
+                where
+                    K: ::sbor::Encode,
+                    V: ::sbor::Encode
+                {
+                    fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                        use ::sbor::{self, Encode};
+                        encoder.write_type(::sbor::type_id::TYPE_FIELDS_NAMED);
+                        encoder.write_len(2);
+                        self.a.encode(encoder);
+                        self.b.encode(encoder);
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_encode_bound() {
+        let input = TokenStream::from_str(
+            "#[sbor(bound = \"T: Clone + Encode\")] struct Test<T> {a: Rc<T>}",
+        )
+        .unwrap();
+        let output = handle_encode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl<T> ::sbor::Encode for Test<T>
+                where
+                    T: Clone + Encode
+                {
+                    fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                        use ::sbor::{self, Encode};
+                        encoder.write_type(::sbor::type_id::TYPE_FIELDS_NAMED);
+                        encoder.write_len(1);
+                        self.a.encode(encoder);
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_encode_versioned() {
+        let input = TokenStream::from_str(
+            "#[sbor(version = 2, previous = \"TestV1\")] struct Test {a: u32}",
+        )
+        .unwrap();
+        let output = handle_encode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl ::sbor::Encode for Test {
+                    fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                        use ::sbor::{self, Encode};
+                        encoder.write_u8(1u8);
+                        encoder.write_type(::sbor::type_id::TYPE_FIELDS_NAMED);
+                        encoder.write_len(1);
+                        self.a.encode(encoder);
+                    }
+                }
+            },
+        );
+    }
+
     #[test]
     fn test_encode_enum() {
         let input = TokenStream::from_str("enum Test {A, B (u32), C {x: u8}}").unwrap();
@@ -205,4 +325,36 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_encode_u16_discriminators() {
+        let input = TokenStream::from_str(
+            "enum Test {#[sbor(discriminator = 1)] A, #[sbor(discriminator = 300)] B(u8)}",
+        )
+        .unwrap();
+        let output = handle_encode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl ::sbor::Encode for Test {
+                    fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                        use ::sbor::{self, Encode};
+                        match self {
+                            Self::A => {
+                                1u16.encode_value(encoder);
+                                encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNIT);
+                            }
+                            Self::B(a0) => {
+                                300u16.encode_value(encoder);
+                                encoder.write_type(::sbor::type_id::TYPE_FIELDS_UNNAMED);
+                                encoder.write_len(1);
+                                a0.encode(encoder);
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
 }
@@ -7,7 +7,10 @@ mod utils;
 use proc_macro::TokenStream;
 
 /// Derive code that describes this data structure.
-/// 
+///
+/// Every type parameter is bound by `Describe` and described as a type argument of the
+/// struct or enum; `#[sbor(describe_bound = "...")]` replaces the inferred bounds.
+///
 /// Note that this derive doesn't work with recursive type, such as
 /// ```ignore
 /// struct A {
@@ -30,6 +33,10 @@ pub fn type_id(input: TokenStream) -> TokenStream {
 }
 
 /// Derive code that encodes this data structure
+///
+/// Type parameters used by encoded fields are bound by `Encode`, except when only used in
+/// `PhantomData`; `#[sbor(bound = "...")]` or `#[sbor(encode_bound = "...")]` replaces the
+/// inferred bounds.
 #[proc_macro_derive(Encode, attributes(sbor))]
 pub fn encode(input: TokenStream) -> TokenStream {
     encode::handle_encode(proc_macro2::TokenStream::from(input))
@@ -38,6 +45,12 @@ pub fn encode(input: TokenStream) -> TokenStream {
 }
 
 /// Derive code that decodes this data structure from a byte array.
+///
+/// Fields marked `#[sbor(default)]` may be missing from the input, and a struct marked
+/// `#[sbor(version = N, previous = "T")]` also decodes data of its earlier versions.
+///
+/// Type parameters are bound like for `Encode`, by `Decode<'de>`, or by `Default` when used by
+/// skipped or default fields; `#[sbor(decode_bound = "...")]` replaces the inferred bounds.
 #[proc_macro_derive(Decode, attributes(sbor))]
 pub fn decode(input: TokenStream) -> TokenStream {
     decode::handle_decode(proc_macro2::TokenStream::from(input))
@@ -2,6 +2,8 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::*;
 
+use crate::utils::*;
+
 macro_rules! trace {
     ($($arg:expr),*) => {{
         #[cfg(feature = "trace")]
@@ -12,26 +14,45 @@ macro_rules! trace {
 pub fn handle_type_id(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_type_id() starts");
 
-    let DeriveInput { ident, data, .. } = parse2(input).expect("Unable to parse input");
+    let DeriveInput {
+        attrs,
+        ident,
+        data,
+        generics,
+        ..
+    } = parse2(input).expect("Unable to parse input");
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     trace!("Encoding: {}", ident);
 
     let output = match data {
-        Data::Struct(_) => quote! {
-            impl ::sbor::TypeId for #ident {
+        // Versioned structs are encoded as an enum of their versions
+        Data::Struct(_) if parse_version(&attrs)?.is_some() => quote! {
+            impl #impl_generics ::sbor::TypeId for #ident #ty_generics #where_clause {
                 #[inline]
                 fn type_id() -> u8 {
-                    ::sbor::type_id::TYPE_STRUCT
+                    ::sbor::type_id::TYPE_ENUM
                 }
             }
         },
-        Data::Enum(_) => quote! {
-            impl ::sbor::TypeId for #ident {
+        Data::Struct(_) => quote! {
+            impl #impl_generics ::sbor::TypeId for #ident #ty_generics #where_clause {
                 #[inline]
                 fn type_id() -> u8 {
-                    ::sbor::type_id::TYPE_ENUM
+                    ::sbor::type_id::TYPE_STRUCT
                 }
             }
         },
+        Data::Enum(DataEnum { variants, .. }) => {
+            let type_id = enum_type_id(&parse_discriminators(&variants)?);
+            quote! {
+                impl #impl_generics ::sbor::TypeId for #ident #ty_generics #where_clause {
+                    #[inline]
+                    fn type_id() -> u8 {
+                        #type_id
+                    }
+                }
+            }
+        }
         Data::Union(_) => {
             return Err(Error::new(Span::call_site(), "Union is not supported!"));
         }
@@ -3,6 +3,9 @@ use std::io;
 use std::path::Path;
 use std::process::Command;
 
+use quote::{quote, ToTokens};
+use syn::visit::{self, Visit};
+
 #[allow(dead_code)]
 pub fn write_and_fmt<P: AsRef<Path>, S: ToString>(path: P, code: S) -> io::Result<()> {
     fs::write(&path, code.to_string())?;
@@ -32,16 +35,325 @@ pub fn print_compiled_code<S: ToString>(kind: &str, code: S) {
 }
 
 pub fn is_skipped(f: &syn::Field) -> bool {
-    let mut skipped = false;
+    has_flag(f, "skip")
+}
+
+pub fn is_default(f: &syn::Field) -> bool {
+    has_flag(f, "default")
+}
+
+fn has_flag(f: &syn::Field, flag: &str) -> bool {
+    let mut found = false;
     for att in &f.attrs {
         if att.path.is_ident("sbor")
             && att
                 .parse_args::<syn::Path>()
-                .map(|p| p.is_ident("skip"))
+                .map(|p| p.is_ident(flag))
                 .unwrap_or(false)
         {
-            skipped = true;
+            found = true;
+        }
+    }
+    found
+}
+
+/// The version of a struct, given by `#[sbor(version = N, previous = "T")]`.
+pub struct Version {
+    /// The version number, starting from 1
+    pub version: u8,
+    /// The struct of the previous version, which this one can be converted from
+    pub previous: Option<syn::Type>,
+}
+
+impl Version {
+    /// The index of the enum variant this version is encoded as.
+    pub fn index(&self) -> u8 {
+        self.version - 1
+    }
+}
+
+pub fn parse_version(attrs: &[syn::Attribute]) -> syn::Result<Option<Version>> {
+    let mut version = None;
+    let mut previous = None;
+    for att in attrs.iter().filter(|att| att.path.is_ident("sbor")) {
+        let metas = att.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+        )?;
+        for meta in metas {
+            match meta {
+                syn::Meta::NameValue(nv) if nv.path.is_ident("version") => match &nv.lit {
+                    syn::Lit::Int(n) if matches!(n.base10_parse::<u8>(), Ok(n) if n > 0) => {
+                        version = Some(n.base10_parse::<u8>()?);
+                    }
+                    lit => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "The version must be an integer from 1 to 255",
+                        ))
+                    }
+                },
+                syn::Meta::NameValue(nv) if nv.path.is_ident("previous") => match &nv.lit {
+                    syn::Lit::Str(s) => previous = Some(s.parse::<syn::Type>()?),
+                    lit => return Err(syn::Error::new_spanned(lit, "Expected a type name")),
+                },
+                // Parsed by `parse_bound()`
+                syn::Meta::NameValue(nv) if BOUND_KEYS.iter().any(|k| nv.path.is_ident(k)) => {}
+                meta => return Err(syn::Error::new_spanned(meta, "Unknown sbor attribute")),
+            }
+        }
+    }
+    match (version, previous) {
+        (Some(version), previous) if version == 1 || previous.is_some() => {
+            Ok(Some(Version { version, previous }))
+        }
+        (Some(_), _) => Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Versions after the first must name the previous one, e.g. `previous = \"TypeV1\"`",
+        )),
+        (None, Some(previous)) => Err(syn::Error::new_spanned(
+            previous,
+            "`previous` requires a version",
+        )),
+        (None, None) => Ok(None),
+    }
+}
+
+const BOUND_KEYS: [&str; 4] = ["bound", "encode_bound", "decode_bound", "describe_bound"];
+
+/// Returns the where predicates given by `#[sbor(bound = "...")]`, which replace the inferred
+/// bounds of every derived impl, or by a key for one derive only, e.g. `decode_bound`.
+pub fn parse_bound(
+    attrs: &[syn::Attribute],
+    key: &str,
+) -> syn::Result<Option<Vec<syn::WherePredicate>>> {
+    let mut bound = None;
+    let mut specific = None;
+    for att in attrs.iter().filter(|att| att.path.is_ident("sbor")) {
+        let metas = att.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+        )?;
+        for meta in metas {
+            if let syn::Meta::NameValue(nv) = meta {
+                let slot = if nv.path.is_ident("bound") {
+                    &mut bound
+                } else if nv.path.is_ident(key) {
+                    &mut specific
+                } else {
+                    continue;
+                };
+                match &nv.lit {
+                    syn::Lit::Str(s) => {
+                        let predicates = s.parse_with(
+                            syn::punctuated::Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated,
+                        )?;
+                        *slot = Some(predicates.into_iter().collect());
+                    }
+                    lit => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "Expected where predicates, e.g. `bound = \"T: Encode\"`",
+                        ))
+                    }
+                }
+            }
+        }
+    }
+    Ok(specific.or(bound))
+}
+
+/// Returns the type parameters that the types of the matching fields depend on.
+///
+/// Parameters only used in `PhantomData` are left out, as it is encoded as unit for any type.
+pub fn field_params(
+    generics: &syn::Generics,
+    data: &syn::Data,
+    filter: impl Fn(&syn::Field) -> bool,
+) -> Vec<syn::Ident> {
+    let fields: Vec<&syn::Field> = match data {
+        syn::Data::Struct(s) => s.fields.iter().collect(),
+        syn::Data::Enum(e) => e.variants.iter().flat_map(|v| &v.fields).collect(),
+        syn::Data::Union(u) => u.fields.named.iter().collect(),
+    };
+
+    let mut visitor = ParamVisitor {
+        params: generics.type_params().map(|p| &p.ident).collect(),
+        used: Vec::new(),
+    };
+    for f in fields.into_iter().filter(|f| filter(f)) {
+        visitor.visit_type(&f.ty);
+    }
+    let used = visitor.used;
+    generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .filter(|p| used.contains(p))
+        .collect()
+}
+
+struct ParamVisitor<'a> {
+    params: Vec<&'a syn::Ident>,
+    used: Vec<syn::Ident>,
+}
+
+impl<'a, 'ast> Visit<'ast> for ParamVisitor<'a> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if matches!(path.segments.last(), Some(s) if s.ident == "PhantomData") {
+            return;
+        }
+        if let Some(first) = path.segments.first() {
+            if path.leading_colon.is_none() && self.params.contains(&&first.ident) {
+                self.used.push(first.ident.clone());
+            }
+        }
+        visit::visit_path(self, path);
+    }
+}
+
+/// Returns the where predicates that bound each of the given type parameters by `bound`.
+pub fn bound_params(
+    params: &[syn::Ident],
+    bound: proc_macro2::TokenStream,
+) -> Vec<syn::WherePredicate> {
+    params
+        .iter()
+        .map(|p| syn::parse_quote! { #p: #bound })
+        .collect()
+}
+
+/// The discriminator an enum variant is encoded with.
+#[derive(PartialEq, Eq, Hash)]
+pub enum Discriminator {
+    U8(u8),
+    U16(u16),
+    String(String),
+}
+
+impl Discriminator {
+    /// Returns the discriminator as a literal, e.g. `1u8` or `"Buy"`.
+    pub fn literal(&self) -> proc_macro2::TokenStream {
+        match self {
+            Self::U8(d) => proc_macro2::Literal::u8_suffixed(*d).into_token_stream(),
+            Self::U16(d) => proc_macro2::Literal::u16_suffixed(*d).into_token_stream(),
+            Self::String(d) => d.into_token_stream(),
+        }
+    }
+
+    /// Returns the discriminator as a `::sbor::describe::Discriminator`.
+    pub fn describe(&self) -> proc_macro2::TokenStream {
+        let literal = self.literal();
+        match self {
+            Self::U8(_) => quote! { ::sbor::describe::Discriminator::U8(#literal) },
+            Self::U16(_) => quote! { ::sbor::describe::Discriminator::U16(#literal) },
+            Self::String(_) => {
+                quote! { ::sbor::describe::Discriminator::String(#literal.to_owned()) }
+            }
+        }
+    }
+}
+
+/// Returns the type id of an enum with the given discriminators.
+pub fn enum_type_id(discriminators: &[Discriminator]) -> proc_macro2::TokenStream {
+    match discriminators.first() {
+        Some(Discriminator::U16(_)) => quote! { ::sbor::type_id::TYPE_ENUM_U16 },
+        Some(Discriminator::String(_)) => quote! { ::sbor::type_id::TYPE_ENUM_STRING },
+        _ => quote! { ::sbor::type_id::TYPE_ENUM },
+    }
+}
+
+/// Returns the discriminators of the variants of an enum, which are given by
+/// `#[sbor(discriminator = N)]` on every variant or else are the positions of the variants.
+///
+/// Integer discriminators are `u16` if any of them is above 255 or has a `u16` suffix, and `u8`
+/// otherwise; string discriminators are written as `#[sbor(discriminator = "Name")]`.
+pub fn parse_discriminators(
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::Token![,]>,
+) -> syn::Result<Vec<Discriminator>> {
+    let mut given = Vec::new();
+    for v in variants {
+        given.push(parse_discriminator(&v.attrs)?);
+    }
+
+    if given.iter().all(Option::is_none) {
+        return match variants.len() {
+            n if n <= 0x100 => Ok((0..n).map(|i| Discriminator::U8(i as u8)).collect()),
+            n if n <= 0x10000 => Ok((0..n).map(|i| Discriminator::U16(i as u16)).collect()),
+            _ => Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "Enums can have at most 65536 variants",
+            )),
+        };
+    }
+
+    let mut lits = Vec::new();
+    for (v, lit) in variants.iter().zip(given) {
+        lits.push(lit.ok_or_else(|| {
+            syn::Error::new_spanned(
+                &v.ident,
+                "Every variant needs a discriminator once any variant has one",
+            )
+        })?);
+    }
+    let strings = matches!(lits[0], syn::Lit::Str(_));
+    let wide = lits.iter().any(|lit| match lit {
+        syn::Lit::Int(n) => {
+            n.suffix() == "u16" || matches!(n.base10_parse::<u16>(), Ok(d) if d > 0xff)
+        }
+        _ => false,
+    });
+
+    let mut discriminators = Vec::new();
+    for lit in &lits {
+        let discriminator = match lit {
+            syn::Lit::Int(n) if !matches!(n.suffix(), "" | "u8" | "u16") => {
+                return Err(syn::Error::new_spanned(
+                    n,
+                    "Integer discriminators must be `u8` or `u16`",
+                ))
+            }
+            syn::Lit::Int(n) if !strings => {
+                let d = n.base10_parse::<u16>()?;
+                match n.suffix() {
+                    "u8" if wide => {
+                        return Err(syn::Error::new_spanned(
+                            n,
+                            "The discriminators of this enum are `u16`",
+                        ))
+                    }
+                    _ if wide => Discriminator::U16(d),
+                    _ => Discriminator::U8(d as u8),
+                }
+            }
+            syn::Lit::Str(s) if strings => Discriminator::String(s.value()),
+            syn::Lit::Int(_) | syn::Lit::Str(_) => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "Discriminators must be either all integers or all strings",
+                ))
+            }
+            lit => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    "Discriminators must be integers or strings",
+                ))
+            }
+        };
+        if discriminators.contains(&discriminator) {
+            return Err(syn::Error::new_spanned(lit, "Duplicate discriminator"));
+        }
+        discriminators.push(discriminator);
+    }
+    Ok(discriminators)
+}
+
+fn parse_discriminator(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::Lit>> {
+    let mut discriminator = None;
+    for att in attrs.iter().filter(|att| att.path.is_ident("sbor")) {
+        match att.parse_args::<syn::Meta>()? {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("discriminator") => {
+                discriminator = Some(nv.lit)
+            }
+            meta => return Err(syn::Error::new_spanned(meta, "Unknown sbor attribute")),
         }
     }
-    skipped
+    Ok(discriminator)
 }
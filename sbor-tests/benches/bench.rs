@@ -6,17 +6,13 @@ mod adapter;
 mod data;
 
 const SIMPLE_REAPT: usize = 32;
+const BLOB_SIZE: usize = 64 * 1024;
 
 fn encode_simple_json(b: &mut Bencher) {
     let t = data::get_simple_dataset(SIMPLE_REAPT);
     b.iter(|| adapter::json_encode(&t));
 }
 
-fn encode_simple_bincode(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    b.iter(|| adapter::bincode_encode(&t));
-}
-
 fn encode_simple_sbor(b: &mut Bencher) {
     let t = data::get_simple_dataset(SIMPLE_REAPT);
     b.iter(|| sbor::encode_with_type(Vec::with_capacity(512), &t));
@@ -33,12 +29,6 @@ fn decode_simple_json(b: &mut Bencher) {
     b.iter(|| adapter::json_decode::<data::simple::SimpleStruct>(&bytes));
 }
 
-fn decode_simple_bincode(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    let bytes = adapter::bincode_encode(&t);
-    b.iter(|| adapter::bincode_decode::<data::simple::SimpleStruct>(&bytes));
-}
-
 fn decode_simple_sbor(b: &mut Bencher) {
     let t = data::get_simple_dataset(SIMPLE_REAPT);
     let bytes = sbor::encode_with_type(Vec::with_capacity(512), &t);
@@ -51,18 +41,47 @@ fn decode_simple_sbor_no_type(b: &mut Bencher) {
     b.iter(|| sbor::decode_no_type::<data::simple::SimpleStruct>(&bytes));
 }
 
+fn decode_blob_sbor_owned(b: &mut Bencher) {
+    let t = data::get_blob_dataset(BLOB_SIZE);
+    let bytes = sbor::encode_with_type(Vec::with_capacity(BLOB_SIZE + 512), &t);
+    b.iter(|| sbor::decode_with_type::<data::blob::OwnedBlob>(&bytes));
+}
+
+fn decode_blob_sbor_borrowed(b: &mut Bencher) {
+    let t = data::get_blob_dataset(BLOB_SIZE);
+    let bytes = sbor::encode_with_type(Vec::with_capacity(BLOB_SIZE + 512), &t);
+    b.iter(|| sbor::decode_with_type::<data::blob::BorrowedBlob>(&bytes));
+}
+
+fn decode_blob_sbor_owned_no_type(b: &mut Bencher) {
+    let t = data::get_blob_dataset(BLOB_SIZE);
+    let bytes = sbor::encode_no_type(Vec::with_capacity(BLOB_SIZE + 512), &t);
+    b.iter(|| sbor::decode_no_type::<data::blob::OwnedBlob>(&bytes));
+}
+
+fn decode_blob_sbor_borrowed_no_type(b: &mut Bencher) {
+    let t = data::get_blob_dataset(BLOB_SIZE);
+    let bytes = sbor::encode_no_type(Vec::with_capacity(BLOB_SIZE + 512), &t);
+    b.iter(|| sbor::decode_no_type::<data::blob::BorrowedBlob>(&bytes));
+}
+
 benchmark_group!(
     encode_simple,
     encode_simple_json,
-    encode_simple_bincode,
     encode_simple_sbor,
     encode_simple_sbor_no_type
 );
 benchmark_group!(
     decode_simple,
     decode_simple_json,
-    decode_simple_bincode,
     decode_simple_sbor,
     decode_simple_sbor_no_type
 );
-benchmark_main!(encode_simple, decode_simple);
+benchmark_group!(
+    decode_blob,
+    decode_blob_sbor_owned,
+    decode_blob_sbor_borrowed,
+    decode_blob_sbor_owned_no_type,
+    decode_blob_sbor_borrowed_no_type
+);
+benchmark_main!(encode_simple, decode_simple, decode_blob);
@@ -0,0 +1,18 @@
+use std::borrow::Cow;
+
+use sbor::{Decode, Encode, TypeId};
+
+#[derive(TypeId, Encode, Decode)]
+pub struct OwnedBlob {
+    pub name: String,
+    pub code: Vec<u8>,
+    pub metadata: String,
+}
+
+/// The same data as `OwnedBlob`, borrowed from the input when decoded.
+#[derive(TypeId, Encode, Decode)]
+pub struct BorrowedBlob<'a> {
+    pub name: &'a str,
+    pub code: &'a [u8],
+    pub metadata: Cow<'a, str>,
+}
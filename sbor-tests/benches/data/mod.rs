@@ -1,3 +1,4 @@
+pub mod blob;
 pub mod simple;
 
 pub fn get_simple_dataset(repeat: usize) -> simple::SimpleStruct {
@@ -12,3 +13,11 @@ pub fn get_simple_dataset(repeat: usize) -> simple::SimpleStruct {
         },
     }
 }
+
+pub fn get_blob_dataset(size: usize) -> blob::OwnedBlob {
+    blob::OwnedBlob {
+        name: "package".to_owned(),
+        code: vec![0x61u8; size],
+        metadata: "dummy".repeat(size / 64),
+    }
+}
@@ -27,41 +27,43 @@ fn test_encode_as_json() {
         c: (2, vec![3, 4]),
         d: "5".to_string(),
     };
-    let bytes = sbor::encode_with_type(&sample);
+    let bytes = sbor::encode_with_type(Vec::new(), &sample);
     let any = sbor::decode_any(&bytes).unwrap();
 
     assert_json_eq(
         any,
         json!({
-            "Struct": [
-                "Unit",
-                {
-                    "U32": 1
-                },
-                {
-                    "Tuple": [
-                        {
-                            "U8": 2
-                        },
-                        {
-                            "Vec": [
-                                7,
-                                [
-                                    {
-                                        "U8": 3
-                                    },
-                                    {
-                                        "U8": 4
-                                    }
+            "Struct": {
+                "Named": [
+                    "Unit",
+                    {
+                        "U32": 1
+                    },
+                    {
+                        "Tuple": [
+                            {
+                                "U8": 2
+                            },
+                            {
+                                "Vec": [
+                                    7,
+                                    [
+                                        {
+                                            "U8": 3
+                                        },
+                                        {
+                                            "U8": 4
+                                        }
+                                    ]
                                 ]
-                            ]
-                        }
-                    ]
-                },
-                {
-                    "String": "5"
-                }
-            ]
+                            }
+                        ]
+                    },
+                    {
+                        "String": "5"
+                    }
+                ]
+            }
         }),
     );
 }
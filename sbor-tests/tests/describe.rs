@@ -85,12 +85,20 @@ fn test_describe_enum() {
             "variants": [
                 {
                     "name": "A",
+                    "discriminator": {
+                        "type": "U8",
+                        "value": 0
+                    },
                     "fields": {
                         "type": "Unit"
                     }
                 },
                 {
                     "name": "B",
+                    "discriminator": {
+                        "type": "U8",
+                        "value": 1
+                    },
                     "fields": {
                         "type": "Unnamed",
                         "unnamed": [
@@ -102,6 +110,10 @@ fn test_describe_enum() {
                 },
                 {
                     "name": "C",
+                    "discriminator": {
+                        "type": "U8",
+                        "value": 2
+                    },
                     "fields": {
                         "type": "Named",
                         "named": [
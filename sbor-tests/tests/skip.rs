@@ -170,6 +170,10 @@ fn test_enum_with_skip() {
           "variants": [
             {
               "name": "A",
+              "discriminator": {
+                "type": "U8",
+                "value": 0
+              },
               "fields": {
                 "type": "Named",
                 "named": [
@@ -184,6 +188,10 @@ fn test_enum_with_skip() {
             },
             {
               "name": "B",
+              "discriminator": {
+                "type": "U8",
+                "value": 1
+              },
               "fields": {
                 "type": "Unnamed",
                 "unnamed": [
@@ -195,6 +203,10 @@ fn test_enum_with_skip() {
             },
             {
               "name": "C",
+              "discriminator": {
+                "type": "U8",
+                "value": 2
+              },
               "fields": {
                 "type": "Unit"
               }
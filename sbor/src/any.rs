@@ -1,4 +1,5 @@
 use crate::decode::*;
+use crate::describe::Discriminator;
 use crate::encode::*;
 use crate::rust::borrow::Borrow;
 use crate::rust::boxed::Box;
@@ -28,7 +29,7 @@ pub enum Value {
     String(String),
 
     Struct(Fields),
-    Enum(u8, Fields),
+    Enum(Discriminator, Fields),
 
     Option(Box<Option<Value>>),
     Box(Box<Value>),
@@ -46,6 +47,10 @@ pub enum Value {
 }
 
 /// Represents the fields of a struct or enum variant.
+#[cfg_attr(
+    any(feature = "serde_std", feature = "serde_alloc"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Fields {
     Named(Vec<Value>),
@@ -79,11 +84,15 @@ pub fn encode_any(ty_ctx: Option<u8>, value: &Value, enc: &mut Encoder) {
             }
             encode_fields(fields, enc);
         }
-        Value::Enum(index, fields) => {
+        Value::Enum(discriminator, fields) => {
             if ty_ctx.is_none() {
-                enc.write_type(TYPE_ENUM);
+                enc.write_type(discriminator.enum_type_id());
+            }
+            match discriminator {
+                Discriminator::U8(d) => enc.write_u8(*d),
+                Discriminator::U16(d) => d.encode_value(enc),
+                Discriminator::String(d) => d.encode_value(enc),
             }
-            enc.write_u8(*index);
             encode_fields(fields, enc);
         }
         // composite types
@@ -158,9 +167,7 @@ pub fn encode_any(ty_ctx: Option<u8>, value: &Value, enc: &mut Encoder) {
             }
             enc.write_type(*ty);
             enc.write_len(elements.len());
-            for e in elements {
-                encode_any(Some(*ty), e, enc);
-            }
+            enc.write_sorted(elements, |e, key, _| encode_any(Some(*ty), e, key));
         }
         Value::HashSet(ty, elements) => {
             if ty_ctx.is_none() {
@@ -168,9 +175,7 @@ pub fn encode_any(ty_ctx: Option<u8>, value: &Value, enc: &mut Encoder) {
             }
             enc.write_type(*ty);
             enc.write_len(elements.len());
-            for e in elements {
-                encode_any(Some(*ty), e, enc);
-            }
+            enc.write_sorted(elements, |e, key, _| encode_any(Some(*ty), e, key));
         }
         Value::TreeMap(ty_k, ty_v, elements) => {
             if ty_ctx.is_none() {
@@ -179,10 +184,10 @@ pub fn encode_any(ty_ctx: Option<u8>, value: &Value, enc: &mut Encoder) {
             enc.write_type(*ty_k);
             enc.write_type(*ty_v);
             enc.write_len(elements.len());
-            for (k, v) in elements {
-                encode_any(Some(*ty_k), k, enc);
-                encode_any(Some(*ty_v), v, enc);
-            }
+            enc.write_sorted(elements, |(k, v), key, value| {
+                encode_any(Some(*ty_k), k, key);
+                encode_any(Some(*ty_v), v, value);
+            });
         }
         Value::HashMap(ty_k, ty_v, elements) => {
             if ty_ctx.is_none() {
@@ -191,10 +196,10 @@ pub fn encode_any(ty_ctx: Option<u8>, value: &Value, enc: &mut Encoder) {
             enc.write_type(*ty_k);
             enc.write_type(*ty_v);
             enc.write_len(elements.len());
-            for (k, v) in elements {
-                encode_any(Some(*ty_k), k, enc);
-                encode_any(Some(*ty_v), v, enc);
-            }
+            enc.write_sorted(elements, |(k, v), key, value| {
+                encode_any(Some(*ty_k), k, key);
+                encode_any(Some(*ty_v), v, value);
+            });
         }
         // custom types
         Value::Custom(ty, data) => {
@@ -238,13 +243,33 @@ fn encode_basic<T: Encode>(ty_ctx: Option<u8>, t: u8, v: &T, enc: &mut Encoder)
 
 /// Decode any SBOR data.
 pub fn decode_any(data: &[u8]) -> Result<Value, DecodeError> {
-    let mut decoder = Decoder::with_type(data);
-    let result = decode_next(None, &mut decoder);
+    decode_any_with_limits(data, DecodeLimits::default())
+}
+
+/// Decode any SBOR data, within the given limits.
+pub fn decode_any_with_limits(data: &[u8], limits: DecodeLimits) -> Result<Value, DecodeError> {
+    decode_any_from(Decoder::with_limits(data, true, limits))
+}
+
+/// Decode any canonically encoded SBOR data.
+pub fn decode_any_strict(data: &[u8]) -> Result<Value, DecodeError> {
+    decode_any_from(Decoder::with_type(data).strict())
+}
+
+fn decode_any_from(mut decoder: Decoder) -> Result<Value, DecodeError> {
+    let result = decode_next(None, &mut decoder)?;
     decoder.check_end()?;
-    result
+    Ok(result)
 }
 
 fn decode_next(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeError> {
+    dec.enter()?;
+    let value = decode_value(ty_ctx, dec)?;
+    dec.exit();
+    Ok(value)
+}
+
+fn decode_value(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeError> {
     let ty = match ty_ctx {
         Some(t) => t,
         None => dec.read_type()?,
@@ -271,12 +296,16 @@ fn decode_next(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeErr
             let fields = decode_fields(dec)?;
             Ok(Value::Struct(fields))
         }
-        TYPE_ENUM => {
-            // index
-            let index = dec.read_u8()?;
+        TYPE_ENUM | TYPE_ENUM_U16 | TYPE_ENUM_STRING => {
+            // discriminator
+            let discriminator = match ty {
+                TYPE_ENUM => Discriminator::U8(dec.read_u8()?),
+                TYPE_ENUM_U16 => Discriminator::U16(<u16>::decode_value(dec)?),
+                _ => Discriminator::String(<String>::decode_value(dec)?),
+            };
             // fields
             let fields = decode_fields(dec)?;
-            Ok(Value::Enum(index, fields))
+            Ok(Value::Enum(discriminator, fields))
         }
         // composite types
         TYPE_OPTION => {
@@ -342,8 +371,11 @@ fn decode_next(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeErr
             let len = dec.read_len()?;
             // values
             let mut elements = Vec::new();
+            let mut last = None;
             for _ in 0..len {
+                let start = dec.offset();
                 elements.push(decode_next(Some(ele_ty), dec)?);
+                dec.check_order(start, &mut last)?;
             }
             if ty == TYPE_TREE_SET {
                 Ok(Value::TreeSet(ele_ty, elements))
@@ -360,11 +392,12 @@ fn decode_next(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeErr
             let len = dec.read_len()?;
             // elements
             let mut elements = Vec::new();
+            let mut last = None;
             for _ in 0..len {
-                elements.push((
-                    decode_next(Some(key_ty), dec)?,
-                    decode_next(Some(value_ty), dec)?,
-                ));
+                let start = dec.offset();
+                let key = decode_next(Some(key_ty), dec)?;
+                dec.check_order(start, &mut last)?;
+                elements.push((key, decode_next(Some(value_ty), dec)?));
             }
             if ty == TYPE_TREE_MAP {
                 Ok(Value::TreeMap(key_ty, value_ty, elements))
@@ -534,9 +567,9 @@ mod tests {
                 Value::Array(TYPE_U32, vec![Value::U32(1), Value::U32(2), Value::U32(3),]),
                 Value::Tuple(vec![Value::U32(1), Value::U32(2),]),
                 Value::Struct(Fields::Named(vec![Value::U32(1)])),
-                Value::Enum(0, Fields::Named(vec![Value::U32(1)])),
-                Value::Enum(1, Fields::Unnamed(vec![Value::U32(2)])),
-                Value::Enum(2, Fields::Unit),
+                Value::Enum(Discriminator::U8(0), Fields::Named(vec![Value::U32(1)])),
+                Value::Enum(Discriminator::U8(1), Fields::Unnamed(vec![Value::U32(2)])),
+                Value::Enum(Discriminator::U8(2), Fields::Unit),
                 Value::Vec(TYPE_U32, vec![Value::U32(1), Value::U32(2),]),
                 Value::TreeSet(TYPE_U32, vec![Value::U32(1)]),
                 Value::HashSet(TYPE_U32, vec![Value::U32(2)]),
@@ -552,6 +585,48 @@ mod tests {
         assert_eq!(bytes2, bytes);
     }
 
+    #[test]
+    pub fn test_parse_with_limits() {
+        let bytes = encode_with_type(Vec::new(), &Box::new(Box::new(1u8)));
+        let limits = DecodeLimits {
+            max_depth: 3,
+            max_len: 0,
+            max_size: bytes.len(),
+        };
+        assert!(decode_any_with_limits(&bytes, limits).is_ok());
+        assert!(matches!(
+            decode_any_with_limits(
+                &bytes,
+                DecodeLimits {
+                    max_depth: 2,
+                    ..limits
+                }
+            ),
+            Err(DecodeError::MaxDepthExceeded(2))
+        ));
+    }
+
+    #[test]
+    pub fn test_parse_strict() {
+        let value = Value::HashMap(
+            TYPE_U16,
+            TYPE_UNIT,
+            vec![(Value::U16(256), Value::Unit), (Value::U16(1), Value::Unit)],
+        );
+        let mut enc = Encoder::with_type(Vec::new());
+        encode_any(None, &value, &mut enc);
+        let bytes: Vec<u8> = enc.into();
+        assert_eq!(vec![52, 8, 0, 2, 0, 0, 0, 0, 1, 1, 0], bytes);
+        assert!(decode_any_strict(&bytes).is_ok());
+
+        let unsorted = vec![52, 8, 0, 2, 0, 0, 0, 1, 0, 0, 1];
+        assert!(decode_any(&unsorted).is_ok());
+        assert!(matches!(
+            decode_any_strict(&unsorted),
+            Err(DecodeError::UnsortedEntry)
+        ));
+    }
+
     #[test]
     pub fn test_parse_custom() {
         let bytes: Vec<u8> = vec![0x80, 0x02, 0x00, 0x00, 0x00, 0x01, 0x02];
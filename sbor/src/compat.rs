@@ -0,0 +1,456 @@
+use crate::describe::{Discriminator, Fields, Type, Variant};
+use crate::rust::fmt;
+use crate::rust::format;
+use crate::rust::string::String;
+use crate::rust::string::ToString;
+use crate::rust::vec::Vec;
+use crate::validate::{describe_type, describe_type_id, enum_type_id, index, join, type_id};
+
+/// Represents a change between two schemas that breaks decoding in one direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incompatibility {
+    /// Where the schemas differ, e.g. `fields.price`
+    pub path: String,
+    /// How the schemas differ
+    pub reason: String,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.reason)
+        } else {
+            write!(f, "{}: {}", self.path, self.reason)
+        }
+    }
+}
+
+/// The result of comparing an old schema with a new one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Compatibility {
+    /// Why the new schema cannot decode data of the old one
+    pub backward: Vec<Incompatibility>,
+    /// Why the old schema cannot decode data of the new one
+    pub forward: Vec<Incompatibility>,
+}
+
+impl Compatibility {
+    /// Whether data encoded with the old schema can be decoded with the new one.
+    pub fn is_backward_compatible(&self) -> bool {
+        self.backward.is_empty()
+    }
+
+    /// Whether data encoded with the new schema can be decoded with the old one.
+    pub fn is_forward_compatible(&self) -> bool {
+        self.forward.is_empty()
+    }
+}
+
+/// Compares two versions of a schema.
+///
+/// Struct fields are compared by position, as their names are not encoded, and enum variants by
+/// discriminator. Fields appended to a struct are assumed to be `#[sbor(default)]`, so they break
+/// forward compatibility only; likewise, variants added to an enum, including a new version of a
+/// versioned struct, break forward compatibility only.
+pub fn check_compatibility(old: &Type, new: &Type) -> Compatibility {
+    let mut result = Compatibility::default();
+    compare(old, new, "", &mut result);
+    result
+}
+
+fn compare(old: &Type, new: &Type, path: &str, result: &mut Compatibility) {
+    match (old, new) {
+        (Type::Option { value: old }, Type::Option { value: new })
+        | (Type::Box { value: old }, Type::Box { value: new }) => {
+            compare(old, new, &join(path, "value"), result)
+        }
+        (
+            Type::Array {
+                element: old,
+                length: old_length,
+            },
+            Type::Array {
+                element: new,
+                length: new_length,
+            },
+        ) => {
+            if old_length != new_length {
+                breaking(
+                    path,
+                    format!("length changed from {} to {}", old_length, new_length),
+                    result,
+                );
+            }
+            compare(old, new, &join(path, "element"), result);
+        }
+        (Type::Tuple { elements: old }, Type::Tuple { elements: new }) => {
+            if old.len() != new.len() {
+                breaking(
+                    path,
+                    format!("length changed from {} to {}", old.len(), new.len()),
+                    result,
+                );
+            }
+            for (i, (old, new)) in old.iter().zip(new).enumerate() {
+                compare(old, new, &index(path, i), result);
+            }
+        }
+        (Type::Struct { fields: old, .. }, Type::Struct { fields: new, .. }) => {
+            compare_fields(old, new, &join(path, "fields"), result)
+        }
+        (Type::Enum { variants: old, .. }, Type::Enum { variants: new, .. }) => {
+            compare_variants(old, new, path, result)
+        }
+        (
+            Type::Result {
+                okay: old_okay,
+                error: old_error,
+            },
+            Type::Result {
+                okay: new_okay,
+                error: new_error,
+            },
+        ) => {
+            compare(old_okay, new_okay, &join(path, "okay"), result);
+            compare(old_error, new_error, &join(path, "error"), result);
+        }
+        (Type::Vec { element: old }, Type::Vec { element: new })
+        | (Type::TreeSet { element: old }, Type::TreeSet { element: new })
+        | (Type::HashSet { element: old }, Type::HashSet { element: new }) => {
+            compare(old, new, &join(path, "element"), result)
+        }
+        (
+            Type::TreeMap {
+                key: old_key,
+                value: old_value,
+            },
+            Type::TreeMap {
+                key: new_key,
+                value: new_value,
+            },
+        )
+        | (
+            Type::HashMap {
+                key: old_key,
+                value: old_value,
+            },
+            Type::HashMap {
+                key: new_key,
+                value: new_value,
+            },
+        ) => {
+            compare(old_key, new_key, &join(path, "key"), result);
+            compare(old_value, new_value, &join(path, "value"), result);
+        }
+        (
+            Type::Custom {
+                name: old_name,
+                generics: old_generics,
+            },
+            Type::Custom {
+                name: new_name,
+                generics: new_generics,
+            },
+        ) => {
+            if old_name != new_name || old_generics.len() != new_generics.len() {
+                changed(old, new, path, result);
+            } else {
+                for (i, (old, new)) in old_generics.iter().zip(new_generics).enumerate() {
+                    compare(old, new, &index(&join(path, "generics"), i), result);
+                }
+            }
+        }
+        (old, new) => {
+            if type_id(old).is_none() || type_id(old) != type_id(new) {
+                changed(old, new, path, result);
+            }
+        }
+    }
+}
+
+fn compare_fields(old: &Fields, new: &Fields, path: &str, result: &mut Compatibility) {
+    match (old, new) {
+        (Fields::Named { named: old }, Fields::Named { named: new }) => {
+            for (old, new) in old.iter().zip(new) {
+                compare(&old.1, &new.1, &join(path, &new.0), result);
+            }
+            resized(
+                old.iter().map(|(name, _)| format!("`{}`", name)).collect(),
+                new.iter().map(|(name, _)| format!("`{}`", name)).collect(),
+                "field",
+                path,
+                result,
+            );
+        }
+        (Fields::Unnamed { unnamed: old }, Fields::Unnamed { unnamed: new }) => {
+            for (i, (old, new)) in old.iter().zip(new).enumerate() {
+                compare(old, new, &index(path, i), result);
+            }
+            resized(
+                (0..old.len()).map(|i| i.to_string()).collect(),
+                (0..new.len()).map(|i| i.to_string()).collect(),
+                "field",
+                path,
+                result,
+            );
+        }
+        (Fields::Unit, Fields::Unit) => {}
+        (old, new) => breaking(
+            path,
+            format!(
+                "changed from {} to {}",
+                describe_fields(old),
+                describe_fields(new)
+            ),
+            result,
+        ),
+    }
+}
+
+fn compare_variants(old: &[Variant], new: &[Variant], path: &str, result: &mut Compatibility) {
+    if !old.is_empty() && !new.is_empty() && enum_type_id(old) != enum_type_id(new) {
+        breaking(
+            path,
+            format!(
+                "changed from {} to {}",
+                describe_type_id(enum_type_id(old)),
+                describe_type_id(enum_type_id(new))
+            ),
+            result,
+        );
+        return;
+    }
+
+    let mut removed = Vec::new();
+    for (i, old) in old.iter().enumerate() {
+        match find_variant(new, &old.discriminator_at(i)) {
+            Some(new) => compare_fields(
+                &old.fields,
+                &new.fields,
+                &join(&join(path, &new.name), "fields"),
+                result,
+            ),
+            None => removed.push(format!("`{}`", old.name)),
+        }
+    }
+    let added = new
+        .iter()
+        .enumerate()
+        .filter(|(i, v)| find_variant(old, &v.discriminator_at(*i)).is_none())
+        .map(|(_, v)| format!("`{}`", v.name))
+        .collect();
+    record(added, removed, "variant", path, result);
+}
+
+fn find_variant<'a>(variants: &'a [Variant], discriminator: &Discriminator) -> Option<&'a Variant> {
+    variants
+        .iter()
+        .enumerate()
+        .find(|(i, v)| v.discriminator_at(*i) == *discriminator)
+        .map(|(_, v)| v)
+}
+
+/// Records the fields or variants appended to or removed from the end of a list.
+fn resized(old: Vec<String>, new: Vec<String>, kind: &str, path: &str, result: &mut Compatibility) {
+    let added = new.get(old.len()..).unwrap_or_default().to_vec();
+    let removed = old.get(new.len()..).unwrap_or_default().to_vec();
+    record(added, removed, kind, path, result);
+}
+
+/// Records added and removed fields or variants.
+///
+/// Added ones can't be decoded by the old schema, and removed ones by the new schema.
+fn record(
+    added: Vec<String>,
+    removed: Vec<String>,
+    kind: &str,
+    path: &str,
+    result: &mut Compatibility,
+) {
+    if !added.is_empty() {
+        result.forward.push(Incompatibility {
+            path: path.to_string(),
+            reason: format!("{} {} added", kind, added.join(", ")),
+        });
+    }
+    if !removed.is_empty() {
+        result.backward.push(Incompatibility {
+            path: path.to_string(),
+            reason: format!("{} {} removed", kind, removed.join(", ")),
+        });
+    }
+}
+
+fn changed(old: &Type, new: &Type, path: &str, result: &mut Compatibility) {
+    breaking(
+        path,
+        format!(
+            "changed from {} to {}",
+            describe_type(old),
+            describe_type(new)
+        ),
+        result,
+    );
+}
+
+/// Records a change that breaks decoding in both directions.
+fn breaking(path: &str, reason: String, result: &mut Compatibility) {
+    let incompatibility = Incompatibility {
+        path: path.to_string(),
+        reason,
+    };
+    result.backward.push(incompatibility.clone());
+    result.forward.push(incompatibility);
+}
+
+fn describe_fields(fields: &Fields) -> &'static str {
+    match fields {
+        Fields::Named { .. } => "named fields",
+        Fields::Unnamed { .. } => "unnamed fields",
+        Fields::Unit => "no fields",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust::string::String;
+    use crate::rust::string::ToString;
+    use crate::rust::vec;
+    use crate::rust::vec::Vec;
+    use crate::*;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, TypeId, Encode, Decode, Describe)]
+    struct OrderV1 {
+        price: u128,
+    }
+
+    #[derive(Debug, PartialEq, TypeId, Encode, Decode, Describe)]
+    struct OrderV2 {
+        price: u128,
+        #[sbor(default)]
+        size: u32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeId, Describe)]
+    struct BadOrder {
+        price: String,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeId, Describe)]
+    enum Side {
+        Buy,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeId, Describe)]
+    enum SideV2 {
+        Buy,
+        Sell(u32),
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeId, Describe)]
+    #[sbor(version = 1)]
+    struct PoolV1 {
+        reserve: u128,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeId, Describe)]
+    #[sbor(version = 2, previous = "PoolV1")]
+    struct Pool {
+        reserve: u128,
+        fee: u8,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeId, Describe)]
+    enum Reordered {
+        #[sbor(discriminator = 1)]
+        Sell(u32),
+        #[sbor(discriminator = 0)]
+        Buy,
+    }
+
+    fn reasons(incompatibilities: &[Incompatibility]) -> Vec<String> {
+        incompatibilities.iter().map(|i| i.to_string()).collect()
+    }
+
+    #[test]
+    fn test_identical() {
+        let result = check_compatibility(&OrderV1::describe(), &OrderV1::describe());
+        assert_eq!(result, Compatibility::default());
+    }
+
+    #[test]
+    fn test_appended_field() {
+        let result = check_compatibility(&OrderV1::describe(), &OrderV2::describe());
+        assert!(result.is_backward_compatible());
+        assert_eq!(reasons(&result.forward), vec!["fields: field `size` added"]);
+
+        let result = check_compatibility(&OrderV2::describe(), &OrderV1::describe());
+        assert_eq!(
+            reasons(&result.backward),
+            vec!["fields: field `size` removed"]
+        );
+        assert!(result.is_forward_compatible());
+
+        // Data of the old version decodes with the new one
+        let bytes = encode_with_type(Vec::new(), &OrderV1 { price: 5 });
+        assert_eq!(
+            decode_with_type::<OrderV2>(&bytes).unwrap(),
+            OrderV2 { price: 5, size: 0 }
+        );
+        let bytes = encode_with_type(Vec::new(), &OrderV2 { price: 5, size: 1 });
+        assert!(decode_with_type::<OrderV1>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_appended_variant() {
+        let result = check_compatibility(&Side::describe(), &SideV2::describe());
+        assert!(result.is_backward_compatible());
+        assert_eq!(reasons(&result.forward), vec!["variant `Sell` added"]);
+    }
+
+    #[test]
+    fn test_new_version() {
+        let new = Pool::describe();
+        match &new {
+            Type::Enum { name, variants, .. } => {
+                assert_eq!(name, "Pool");
+                assert_eq!(variants[0].name, "V1");
+                assert_eq!(variants[1].name, "V2");
+            }
+            ty => panic!("Expected an enum, got {:?}", ty),
+        }
+
+        let result = check_compatibility(&PoolV1::describe(), &new);
+        assert!(result.is_backward_compatible());
+        assert_eq!(reasons(&result.forward), vec!["variant `V2` added"]);
+    }
+
+    #[test]
+    fn test_reordered_variants() {
+        let result = check_compatibility(&SideV2::describe(), &Reordered::describe());
+        assert_eq!(result, Compatibility::default());
+
+        let result = check_compatibility(&Reordered::describe(), &Side::describe());
+        assert!(result.is_forward_compatible());
+        assert_eq!(reasons(&result.backward), vec!["variant `Sell` removed"]);
+    }
+
+    #[test]
+    fn test_changed_type() {
+        let result = check_compatibility(&OrderV1::describe(), &BadOrder::describe());
+        let expected = vec!["fields.price: changed from U128 to String"];
+        assert_eq!(reasons(&result.backward), expected);
+        assert_eq!(reasons(&result.forward), expected);
+
+        let result = check_compatibility(&<[u8; 2]>::describe(), &<Vec<u8>>::describe());
+        assert_eq!(reasons(&result.backward), vec!["changed from Array to Vec"]);
+    }
+}
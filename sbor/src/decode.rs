@@ -1,7 +1,10 @@
+use crate::describe::Discriminator;
+use crate::rust::borrow::{Cow, ToOwned};
 use crate::rust::boxed::Box;
 use crate::rust::cell::RefCell;
 use crate::rust::collections::*;
 use crate::rust::hash::Hash;
+use crate::rust::marker::PhantomData;
 use crate::rust::mem::MaybeUninit;
 use crate::rust::ptr::copy;
 use crate::rust::rc::Rc;
@@ -22,6 +25,8 @@ pub enum DecodeError {
 
     InvalidIndex(u8),
 
+    InvalidDiscriminator(Discriminator),
+
     InvalidBool(u8),
 
     InvalidUtf8,
@@ -31,17 +36,72 @@ pub enum DecodeError {
     InvalidCustomData(u8),
 
     DuplicateEntry,
+
+    MaxDepthExceeded(usize),
+
+    MaxLengthExceeded { max: usize, actual: usize },
+
+    MaxSizeExceeded(usize),
+
+    UnsortedEntry,
+}
+
+/// Bounds on the data a `Decoder` accepts, for decoding untrusted input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The maximum nesting depth of values
+    pub max_depth: usize,
+    /// The maximum of any length prefix, such as the number of collection elements
+    pub max_len: usize,
+    /// The maximum number of bytes read
+    pub max_size: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_len: usize::MAX,
+            max_size: usize::MAX,
+        }
+    }
 }
 
 /// A data structure that can be decoded from a byte array using SBOR.
-pub trait Decode: Sized + TypeId {
+///
+/// The lifetime `'de` is that of the input, which types like `&'de str` borrow from instead of
+/// copying.
+pub trait Decode<'de>: Sized + TypeId {
     #[inline]
-    fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+    fn decode(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         decoder.check_type(Self::type_id())?;
-        Self::decode_value(decoder)
+        decoder.enter()?;
+        let value = Self::decode_value(decoder)?;
+        decoder.exit();
+        Ok(value)
     }
 
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError>;
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError>;
+}
+
+/// A data structure that can be decoded without borrowing from the input.
+pub trait DecodeOwned: for<'de> Decode<'de> {}
+
+impl<T: for<'de> Decode<'de>> DecodeOwned for T {}
+
+/// A struct whose encoding records its version, so that data of earlier versions can still be
+/// decoded and upgraded to it.
+///
+/// A versioned struct is encoded as an enum with one variant per version, and is usually derived
+/// with `#[sbor(version = N, previous = "TypeV{N-1}")]` together with a `From` conversion from
+/// the previous version.
+pub trait Versioned<'de>: Sized {
+    /// The version of this struct, starting from 1.
+    const VERSION: u8;
+
+    /// Decodes the fields of the version encoded as variant `index`, which is `VERSION - 1` or
+    /// that of an earlier version, and upgrades them to this version.
+    fn decode_version(decoder: &mut Decoder<'de>, index: u8) -> Result<Self, DecodeError>;
 }
 
 /// A `Decoder` abstracts the logic for decoding basic types.
@@ -49,17 +109,34 @@ pub struct Decoder<'de> {
     input: &'de [u8],
     offset: usize,
     with_type: bool,
+    limits: DecodeLimits,
+    depth: usize,
+    strict: bool,
 }
 
 impl<'de> Decoder<'de> {
     pub fn new(input: &'de [u8], with_type: bool) -> Self {
+        Self::with_limits(input, with_type, DecodeLimits::default())
+    }
+
+    pub fn with_limits(input: &'de [u8], with_type: bool, limits: DecodeLimits) -> Self {
         Self {
             input,
             offset: 0,
             with_type,
+            limits,
+            depth: 0,
+            strict: false,
         }
     }
 
+    /// Makes this decoder reject input that isn't canonically encoded, i.e. sets and maps whose
+    /// entries aren't sorted by the encoding of their keys.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
     pub fn with_type(input: &'de [u8]) -> Self {
         Self::new(input, true)
     }
@@ -74,7 +151,9 @@ impl<'de> Decoder<'de> {
     }
 
     pub fn require(&self, n: usize) -> Result<(), DecodeError> {
-        if self.remaining() < n {
+        if self.offset.saturating_add(n) > self.limits.max_size {
+            Err(DecodeError::MaxSizeExceeded(self.limits.max_size))
+        } else if self.remaining() < n {
             Err(DecodeError::Underflow {
                 required: n,
                 remaining: self.remaining(),
@@ -91,7 +170,28 @@ impl<'de> Decoder<'de> {
     pub fn read_len(&mut self) -> Result<usize, DecodeError> {
         let mut bytes = [0u8; 4];
         bytes.copy_from_slice(self.read_bytes(4)?);
-        Ok(u32::from_le_bytes(bytes) as usize)
+        let len = u32::from_le_bytes(bytes) as usize;
+        if len > self.limits.max_len {
+            return Err(DecodeError::MaxLengthExceeded {
+                max: self.limits.max_len,
+                actual: len,
+            });
+        }
+        Ok(len)
+    }
+
+    /// Steps into a nested value, checking the depth limit.
+    pub fn enter(&mut self) -> Result<(), DecodeError> {
+        if self.depth >= self.limits.max_depth {
+            return Err(DecodeError::MaxDepthExceeded(self.limits.max_depth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Steps out of a nested value.
+    pub fn exit(&mut self) {
+        self.depth -= 1;
     }
 
     pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
@@ -134,6 +234,33 @@ impl<'de> Decoder<'de> {
         Ok(())
     }
 
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// In strict mode, checks that the set element or map key read since `start` sorts after the
+    /// previous one, which is kept in `last`.
+    pub fn check_order(
+        &self,
+        start: usize,
+        last: &mut Option<&'de [u8]>,
+    ) -> Result<(), DecodeError> {
+        if self.strict {
+            let key = &self.input[start..self.offset];
+            if let Some(prev) = last {
+                if key == *prev {
+                    return Err(DecodeError::DuplicateEntry);
+                }
+                if key < *prev {
+                    return Err(DecodeError::UnsortedEntry);
+                }
+            }
+            *last = Some(key);
+        }
+        Ok(())
+    }
+
     pub fn check_end(&self) -> Result<(), DecodeError> {
         let n = self.remaining();
         if n != 0 {
@@ -144,14 +271,20 @@ impl<'de> Decoder<'de> {
     }
 }
 
-impl Decode for () {
-    fn decode_value(_decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for () {
+    fn decode_value(_decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Ok(())
     }
 }
 
-impl Decode for bool {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: ?Sized> Decode<'de> for PhantomData<T> {
+    fn decode_value(_decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
+        Ok(PhantomData)
+    }
+}
+
+impl<'de> Decode<'de> for bool {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let value = decoder.read_u8()?;
         match value {
             0 => Ok(false),
@@ -161,15 +294,15 @@ impl Decode for bool {
     }
 }
 
-impl Decode for i8 {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for i8 {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let value = decoder.read_u8()?;
         Ok(value as i8)
     }
 }
 
-impl Decode for u8 {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for u8 {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let value = decoder.read_u8()?;
         Ok(value)
     }
@@ -177,8 +310,8 @@ impl Decode for u8 {
 
 macro_rules! decode_int {
     ($type:ident, $type_id:ident, $n:expr) => {
-        impl Decode for $type {
-            fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        impl<'de> Decode<'de> for $type {
+            fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
                 let slice = decoder.read_bytes($n)?;
                 let mut bytes = [0u8; $n];
                 bytes.copy_from_slice(&slice[..]);
@@ -197,28 +330,54 @@ decode_int!(u32, TYPE_U32, 4);
 decode_int!(u64, TYPE_U64, 8);
 decode_int!(u128, TYPE_U128, 16);
 
-impl Decode for isize {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for isize {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         i32::decode_value(decoder).map(|i| i as isize)
     }
 }
 
-impl Decode for usize {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for usize {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         u32::decode_value(decoder).map(|i| i as usize)
     }
 }
 
-impl Decode for String {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for String {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let len = decoder.read_len()?;
         let slice = decoder.read_bytes(len)?;
         String::from_utf8(slice.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
     }
 }
 
-impl<T: Decode> Decode for Option<T> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de: 'a, 'a> Decode<'de> for &'a str {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
+        let len = decoder.read_len()?;
+        let slice = decoder.read_bytes(len)?;
+        core::str::from_utf8(slice).map_err(|_| DecodeError::InvalidUtf8)
+    }
+}
+
+impl<'de: 'a, 'a> Decode<'de> for &'a [u8] {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
+        decoder.check_type(TYPE_U8)?;
+        let len = decoder.read_len()?;
+        decoder.read_bytes(len)
+    }
+}
+
+impl<'de: 'a, 'a, B> Decode<'de> for Cow<'a, B>
+where
+    B: ToOwned + TypeId + ?Sized,
+    &'a B: Decode<'de>,
+{
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
+        <&'a B>::decode_value(decoder).map(Cow::Borrowed)
+    }
+}
+
+impl<'de, T: Decode<'de>> Decode<'de> for Option<T> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let index = decoder.read_u8()?;
 
         match index {
@@ -229,29 +388,29 @@ impl<T: Decode> Decode for Option<T> {
     }
 }
 
-impl<T: Decode> Decode for Box<T> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de>> Decode<'de> for Box<T> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let v = T::decode(decoder)?;
         Ok(Box::new(v))
     }
 }
 
-impl<T: Decode> Decode for Rc<T> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de>> Decode<'de> for Rc<T> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let v = T::decode_value(decoder)?;
         Ok(Rc::new(v))
     }
 }
 
-impl<T: Decode> Decode for RefCell<T> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de>> Decode<'de> for RefCell<T> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let v = T::decode_value(decoder)?;
         Ok(RefCell::new(v))
     }
 }
 
-impl<T: Decode, const N: usize> Decode for [T; N] {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de>, const N: usize> Decode<'de> for [T; N] {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         decoder.check_type(T::type_id())?;
         decoder.check_len(N)?;
 
@@ -265,14 +424,15 @@ impl<T: Decode, const N: usize> Decode for [T; N] {
         let mut data: [MaybeUninit<T>; N] = unsafe { MaybeUninit::uninit().assume_init() };
 
         // Decode element by element
+        decoder.enter()?;
         for elem in &mut data[..] {
             elem.write(T::decode_value(decoder)?);
         }
+        decoder.exit();
 
         // Use &mut as an assertion of unique "ownership"
         let ptr = &mut data as *mut _ as *mut [T; N];
         let res = unsafe { ptr.read() };
-        core::mem::forget(data);
 
         Ok(res)
     }
@@ -280,8 +440,8 @@ impl<T: Decode, const N: usize> Decode for [T; N] {
 
 macro_rules! decode_tuple {
     ($n:tt $($idx:tt $name:ident)+) => {
-        impl<$($name: Decode),+> Decode for ($($name,)+) {
-                    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        impl<'de, $($name: Decode<'de>),+> Decode<'de> for ($($name,)+) {
+                    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
                 let len = decoder.read_len()?;
 
                 if len != $n {
@@ -304,8 +464,8 @@ decode_tuple! { 8 0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H }
 decode_tuple! { 9 0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H 8 I }
 decode_tuple! { 10 0 A 1 B 2 C 3 D 4 E 5 F 6 G 7 H 8 I 9 J }
 
-impl<T: Decode, E: Decode> Decode for Result<T, E> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de>, E: Decode<'de>> Decode<'de> for Result<T, E> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         let index = decoder.read_u8()?;
         match index {
             0 => Ok(Ok(T::decode(decoder)?)),
@@ -315,8 +475,8 @@ impl<T: Decode, E: Decode> Decode for Result<T, E> {
     }
 }
 
-impl<T: Decode> Decode for Vec<T> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de>> Decode<'de> for Vec<T> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         decoder.check_type(T::type_id())?;
         let len = decoder.read_len()?;
 
@@ -330,76 +490,96 @@ impl<T: Decode> Decode for Vec<T> {
             Ok(result)
         } else {
             let mut result = Vec::<T>::with_capacity(if len <= 1024 { len } else { 1024 });
+            decoder.enter()?;
             for _ in 0..len {
                 result.push(T::decode_value(decoder)?);
             }
+            decoder.exit();
             Ok(result)
         }
     }
 }
 
-impl<T: Decode + Ord> Decode for BTreeSet<T> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de> + Ord> Decode<'de> for BTreeSet<T> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         decoder.check_type(T::type_id())?;
         let len = decoder.read_len()?;
 
         let mut result = BTreeSet::new();
+        let mut last = None;
+        decoder.enter()?;
         for _ in 0..len {
-            if !result.insert(T::decode_value(decoder)?) {
+            let start = decoder.offset();
+            let element = T::decode_value(decoder)?;
+            decoder.check_order(start, &mut last)?;
+            if !result.insert(element) {
                 return Err(DecodeError::DuplicateEntry);
             }
         }
+        decoder.exit();
         Ok(result)
     }
 }
 
-impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, K: Decode<'de> + Ord, V: Decode<'de>> Decode<'de> for BTreeMap<K, V> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         decoder.check_type(K::type_id())?;
         decoder.check_type(V::type_id())?;
         let len = decoder.read_len()?;
         let mut map = BTreeMap::new();
+        let mut last = None;
+        decoder.enter()?;
         for _ in 0..len {
-            if map
-                .insert(K::decode_value(decoder)?, V::decode_value(decoder)?)
-                .is_some()
-            {
+            let start = decoder.offset();
+            let key = K::decode_value(decoder)?;
+            decoder.check_order(start, &mut last)?;
+            if map.insert(key, V::decode_value(decoder)?).is_some() {
                 return Err(DecodeError::DuplicateEntry);
             }
         }
+        decoder.exit();
         Ok(map)
     }
 }
 
-impl<T: Decode + Hash + Eq> Decode for HashSet<T> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, T: Decode<'de> + Hash + Eq> Decode<'de> for HashSet<T> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         decoder.check_type(T::type_id())?;
         let len = decoder.read_len()?;
 
         let mut result = HashSet::new();
+        let mut last = None;
+        decoder.enter()?;
         for _ in 0..len {
-            if !result.insert(T::decode_value(decoder)?) {
+            let start = decoder.offset();
+            let element = T::decode_value(decoder)?;
+            decoder.check_order(start, &mut last)?;
+            if !result.insert(element) {
                 return Err(DecodeError::DuplicateEntry);
             }
         }
+        decoder.exit();
         Ok(result)
     }
 }
 
-impl<K: Decode + Hash + Eq, V: Decode> Decode for HashMap<K, V> {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de, K: Decode<'de> + Hash + Eq, V: Decode<'de>> Decode<'de> for HashMap<K, V> {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         decoder.check_type(K::type_id())?;
         decoder.check_type(V::type_id())?;
         let len = decoder.read_len()?;
         let mut map = HashMap::new();
+        let mut last = None;
+        decoder.enter()?;
         for _ in 0..len {
-            if map
-                .insert(K::decode_value(decoder)?, V::decode_value(decoder)?)
-                .is_some()
-            {
+            let start = decoder.offset();
+            let key = K::decode_value(decoder)?;
+            decoder.check_order(start, &mut last)?;
+            if map.insert(key, V::decode_value(decoder)?).is_some() {
                 return Err(DecodeError::DuplicateEntry);
             }
         }
+        decoder.exit();
         Ok(map)
     }
 }
@@ -414,7 +594,7 @@ mod tests {
 
     fn assert_decoding(dec: &mut Decoder) {
         <()>::decode(dec).unwrap();
-        assert_eq!(true, <bool>::decode(dec).unwrap());
+        assert!(<bool>::decode(dec).unwrap());
         assert_eq!(1, <i8>::decode(dec).unwrap());
         assert_eq!(1, <i16>::decode(dec).unwrap());
         assert_eq!(1, <i32>::decode(dec).unwrap());
@@ -525,7 +705,7 @@ mod tests {
     }
 
     #[derive(sbor::TypeId, sbor::Encode, sbor::Decode, PartialEq, Eq, Debug)]
-    struct NFA {
+    struct Nfa {
         a: [u8; 32],
         b: Vec<u8>,
     }
@@ -533,11 +713,11 @@ mod tests {
     #[test]
     pub fn test_generic_array() {
         let value1 = [
-            NFA {
+            Nfa {
                 a: [1u8; 32],
                 b: vec![1],
             },
-            NFA {
+            Nfa {
                 a: [2u8; 32],
                 b: vec![2],
             },
@@ -549,7 +729,324 @@ mod tests {
         let bytes: Vec<u8> = enc.into();
 
         let mut dec = Decoder::with_type(&bytes);
-        let value2 = <[NFA; 2]>::decode(&mut dec).unwrap();
+        let value2 = <[Nfa; 2]>::decode(&mut dec).unwrap();
         assert_eq!(value1, value2);
     }
+
+    #[test]
+    pub fn test_strict_decoding() {
+        let sorted = vec![49, 7, 2, 0, 0, 0, 1, 2];
+        let unsorted = vec![49, 7, 2, 0, 0, 0, 2, 1];
+        let duplicate = vec![52, 7, 7, 2, 0, 0, 0, 1, 2, 1, 3];
+
+        let mut dec = Decoder::with_type(&sorted).strict();
+        assert!(<BTreeSet<u8>>::decode(&mut dec).is_ok());
+
+        let mut dec = Decoder::with_type(&unsorted);
+        assert!(<BTreeSet<u8>>::decode(&mut dec).is_ok());
+        let mut dec = Decoder::with_type(&unsorted).strict();
+        assert!(matches!(
+            <BTreeSet<u8>>::decode(&mut dec),
+            Err(DecodeError::UnsortedEntry)
+        ));
+
+        let mut dec = Decoder::with_type(&duplicate).strict();
+        assert!(matches!(
+            <HashMap<u8, u8>>::decode(&mut dec),
+            Err(DecodeError::DuplicateEntry)
+        ));
+    }
+
+    fn limits(max_depth: usize, max_len: usize, max_size: usize) -> DecodeLimits {
+        DecodeLimits {
+            max_depth,
+            max_len,
+            max_size,
+        }
+    }
+
+    #[test]
+    pub fn test_max_depth() {
+        let value = Some(vec![vec![1u32]]);
+        let mut enc = Encoder::with_type(Vec::new());
+        value.encode(&mut enc);
+        let bytes: Vec<u8> = enc.into();
+
+        let mut dec = Decoder::with_limits(&bytes, true, limits(4, 8, 64));
+        assert_eq!(value, <Option<Vec<Vec<u32>>>>::decode(&mut dec).unwrap());
+
+        let mut dec = Decoder::with_limits(&bytes, true, limits(3, 8, 64));
+        assert!(matches!(
+            <Option<Vec<Vec<u32>>>>::decode(&mut dec),
+            Err(DecodeError::MaxDepthExceeded(3))
+        ));
+    }
+
+    #[test]
+    pub fn test_max_len() {
+        // A vec of a billion units, which takes no bytes beyond its length
+        let bytes = vec![48, 0, 0, 0xca, 0x9a, 0x3b];
+        let mut dec = Decoder::with_limits(&bytes, true, limits(8, 1024, 64));
+        assert!(matches!(
+            <Vec<()>>::decode(&mut dec),
+            Err(DecodeError::MaxLengthExceeded {
+                max: 1024,
+                actual: 1_000_000_000,
+            })
+        ));
+    }
+
+    #[test]
+    pub fn test_max_size() {
+        let bytes = vec![12, 5, 0, 0, 0, 104, 101, 108, 108, 111];
+        let mut dec = Decoder::with_limits(&bytes, true, limits(8, 8, 10));
+        assert_eq!("hello", <String>::decode(&mut dec).unwrap());
+
+        let mut dec = Decoder::with_limits(&bytes, true, limits(8, 8, 9));
+        assert!(matches!(
+            <String>::decode(&mut dec),
+            Err(DecodeError::MaxSizeExceeded(9))
+        ));
+    }
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode)]
+    struct Blob<'a> {
+        name: &'a str,
+        code: &'a [u8],
+        note: Cow<'a, str>,
+    }
+
+    #[test]
+    pub fn test_decode_borrowed() {
+        let blob = Blob {
+            name: "hello",
+            code: &[1, 2, 3],
+            note: Cow::Owned("world".to_owned()),
+        };
+        let mut enc = Encoder::with_type(Vec::new());
+        blob.encode(&mut enc);
+        let bytes: Vec<u8> = enc.into();
+
+        let mut dec = Decoder::with_type(&bytes);
+        let decoded = Blob::decode(&mut dec).unwrap();
+        assert_eq!(decoded, blob);
+        assert!(matches!(decoded.note, Cow::Borrowed("world")));
+
+        // The fields point into the input rather than copies of it
+        let input = bytes.as_ptr_range();
+        assert!(input.contains(&decoded.name.as_ptr()));
+        assert!(input.contains(&decoded.code.as_ptr()));
+    }
+
+    #[test]
+    pub fn test_decode_borrowed_invalid() {
+        let bytes = vec![12, 1, 0, 0, 0, 0xff];
+        let mut dec = Decoder::with_type(&bytes);
+        assert!(matches!(
+            <&str>::decode(&mut dec),
+            Err(DecodeError::InvalidUtf8)
+        ));
+
+        // Only byte slices can be borrowed
+        let bytes = vec![48, 9, 1, 0, 0, 0, 1, 0, 0, 0];
+        let mut dec = Decoder::with_type(&bytes);
+        assert!(matches!(
+            <&[u8]>::decode(&mut dec),
+            Err(DecodeError::InvalidType {
+                expected: Some(TYPE_U8),
+                actual: TYPE_U32,
+            })
+        ));
+    }
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode)]
+    #[sbor(version = 1)]
+    struct AccountV1 {
+        owner: String,
+    }
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode)]
+    #[sbor(version = 2, previous = "AccountV1")]
+    struct Account {
+        owner: String,
+        balance: u32,
+        #[sbor(default)]
+        memo: Option<String>,
+    }
+
+    impl From<AccountV1> for Account {
+        fn from(v1: AccountV1) -> Self {
+            Self {
+                owner: v1.owner,
+                balance: 100,
+                memo: None,
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_decode_versioned() {
+        let v1 = AccountV1 {
+            owner: "alice".to_owned(),
+        };
+        let bytes = crate::encode_with_type(Vec::new(), &v1);
+        assert_eq!(
+            crate::decode_with_type::<Account>(&bytes).unwrap(),
+            Account {
+                owner: "alice".to_owned(),
+                balance: 100,
+                memo: None,
+            }
+        );
+
+        let v2 = Account {
+            owner: "bob".to_owned(),
+            balance: 5,
+            memo: Some("hi".to_owned()),
+        };
+        let bytes = crate::encode_with_type(Vec::new(), &v2);
+        assert_eq!(bytes[1], 1); // the variant of version 2
+        assert_eq!(crate::decode_with_type::<Account>(&bytes).unwrap(), v2);
+        assert!(matches!(
+            crate::decode_with_type::<AccountV1>(&bytes),
+            Err(DecodeError::InvalidIndex(1))
+        ));
+    }
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode)]
+    enum Status {
+        #[sbor(discriminator = 7)]
+        Open,
+        #[sbor(discriminator = 3)]
+        Closed(u32),
+    }
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode)]
+    enum Wide {
+        #[sbor(discriminator = 1000)]
+        A,
+        #[sbor(discriminator = 1)]
+        B,
+    }
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode)]
+    enum Named {
+        #[sbor(discriminator = "buy")]
+        Buy { price: u32 },
+        #[sbor(discriminator = "sell")]
+        Sell,
+    }
+
+    #[test]
+    pub fn test_decode_discriminators() {
+        let bytes = crate::encode_with_type(Vec::new(), &Status::Closed(1));
+        assert_eq!(bytes[0..2], [TYPE_ENUM, 3]);
+        assert_eq!(
+            crate::decode_with_type::<Status>(&bytes).unwrap(),
+            Status::Closed(1)
+        );
+        assert!(matches!(
+            crate::decode_with_type::<Status>(&[TYPE_ENUM, 0, TYPE_FIELDS_UNIT]),
+            Err(DecodeError::InvalidIndex(0))
+        ));
+
+        let bytes = crate::encode_with_type(Vec::new(), &Wide::A);
+        assert_eq!(bytes, [TYPE_ENUM_U16, 0xe8, 0x03, TYPE_FIELDS_UNIT]);
+        assert_eq!(crate::decode_with_type::<Wide>(&bytes).unwrap(), Wide::A);
+
+        let named = Named::Buy { price: 5 };
+        let bytes = crate::encode_with_type(Vec::new(), &named);
+        assert_eq!(bytes[0], TYPE_ENUM_STRING);
+        assert_eq!(crate::decode_with_type::<Named>(&bytes).unwrap(), named);
+        let value = crate::decode_any(&bytes).unwrap();
+        let mut enc = Encoder::with_type(Vec::new());
+        crate::encode_any(None, &value, &mut enc);
+        assert_eq!(Vec::<u8>::from(enc), bytes);
+
+        let bytes = crate::encode_with_type(Vec::new(), &Wide::B);
+        assert!(matches!(
+            crate::decode_with_type::<Named>(&bytes),
+            Err(DecodeError::InvalidType { .. })
+        ));
+    }
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode, sbor::Describe)]
+    struct OrderBook<K: Ord, V> {
+        orders: BTreeMap<K, V>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Usd;
+
+    #[derive(Debug, PartialEq, sbor::TypeId, sbor::Encode, sbor::Decode)]
+    struct Ledger<T, C> {
+        entries: Vec<T>,
+        currency: PhantomData<C>,
+    }
+
+    #[test]
+    pub fn test_decode_generic() {
+        use crate::describe::{Describe, Fields, Type};
+
+        let mut book = OrderBook {
+            orders: BTreeMap::new(),
+        };
+        book.orders.insert(5u32, "five".to_owned());
+        let bytes = crate::encode_with_type(Vec::new(), &book);
+        assert_eq!(
+            crate::decode_with_type::<OrderBook<u32, String>>(&bytes).unwrap(),
+            book
+        );
+        assert_eq!(
+            OrderBook::<u32, String>::describe(),
+            Type::Struct {
+                name: "OrderBook".to_owned(),
+                fields: Fields::Named {
+                    named: vec![(
+                        "orders".to_owned(),
+                        Type::TreeMap {
+                            key: Box::new(Type::U32),
+                            value: Box::new(Type::String),
+                        }
+                    )]
+                },
+                generics: vec![Type::U32, Type::String],
+            }
+        );
+
+        // `Usd` needs no SBOR impls, as it is only used in `PhantomData`
+        let ledger = Ledger::<u8, Usd> {
+            entries: vec![1, 2],
+            currency: PhantomData,
+        };
+        let bytes = crate::encode_with_type(Vec::new(), &ledger);
+        assert_eq!(
+            crate::decode_with_type::<Ledger<u8, Usd>>(&bytes).unwrap(),
+            ledger
+        );
+    }
+
+    #[test]
+    pub fn test_decode_default_fields() {
+        // Version 2 encoded before `memo` was added
+        let bytes = vec![17, 1, 18, 2, 0, 0, 0, 12, 1, 0, 0, 0, b'c', 9, 7, 0, 0, 0];
+        assert_eq!(
+            crate::decode_with_type::<Account>(&bytes).unwrap(),
+            Account {
+                owner: "c".to_owned(),
+                balance: 7,
+                memo: None,
+            }
+        );
+
+        // Required fields can't be missing
+        let bytes = vec![17, 1, 18, 1, 0, 0, 0, 12, 1, 0, 0, 0, b'c'];
+        assert!(matches!(
+            crate::decode_with_type::<Account>(&bytes),
+            Err(DecodeError::InvalidLength {
+                expected: 3,
+                actual: 1
+            })
+        ));
+    }
 }
@@ -3,11 +3,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::sbor::{Decode, Encode, TypeId};
 
+use crate::rust::borrow::{Cow, ToOwned};
 use crate::rust::boxed::Box;
 use crate::rust::collections::*;
+use crate::rust::fmt;
+use crate::rust::marker::PhantomData;
 use crate::rust::string::String;
 use crate::rust::vec;
 use crate::rust::vec::Vec;
+use crate::type_id::*;
 
 // For enum, we use internally tagged representation for readability.
 // See: https://serde.rs/enum-representations.html
@@ -54,11 +58,25 @@ pub enum Type {
     Struct {
         name: String,
         fields: Fields,
+        /// The type arguments of a generic struct, e.g. `T` of `Ledger<T>`
+        #[cfg_attr(
+            any(feature = "serde_std", feature = "serde_alloc"),
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        #[sbor(default)]
+        generics: Vec<Type>,
     },
 
     Enum {
         name: String,
         variants: Vec<Variant>, // Order matters as it decides of the variant index
+        /// The type arguments of a generic enum
+        #[cfg_attr(
+            any(feature = "serde_std", feature = "serde_alloc"),
+            serde(default, skip_serializing_if = "Vec::is_empty")
+        )]
+        #[sbor(default)]
+        generics: Vec<Type>,
     },
 
     Result {
@@ -103,6 +121,57 @@ pub enum Type {
 pub struct Variant {
     pub name: String,
     pub fields: Fields,
+    /// The discriminator of this variant, or `None` for its position in the enum
+    #[cfg_attr(any(feature = "serde_std", feature = "serde_alloc"), serde(default))]
+    #[sbor(default)]
+    pub discriminator: Option<Discriminator>,
+}
+
+impl Variant {
+    /// Returns the discriminator of this variant, given its position in the enum.
+    pub fn discriminator_at(&self, index: usize) -> Discriminator {
+        self.discriminator
+            .clone()
+            .unwrap_or(Discriminator::U8(index as u8))
+    }
+}
+
+/// Identifies an enum variant in the encoding.
+///
+/// Variants are identified by their position, unless given discriminators with
+/// `#[sbor(discriminator = N)]`. Each kind of discriminator has its own enum type id, so that
+/// enums of any kind can be decoded without a schema.
+#[cfg_attr(
+    any(feature = "serde_std", feature = "serde_alloc"),
+    derive(Serialize, Deserialize),
+    serde(tag = "type", content = "value")
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, TypeId, Decode, Encode)]
+pub enum Discriminator {
+    U8(u8),
+    U16(u16),
+    String(String),
+}
+
+impl Discriminator {
+    /// Returns the type id of enums with this kind of discriminator.
+    pub fn enum_type_id(&self) -> u8 {
+        match self {
+            Self::U8(_) => TYPE_ENUM,
+            Self::U16(_) => TYPE_ENUM_U16,
+            Self::String(_) => TYPE_ENUM_STRING,
+        }
+    }
+}
+
+impl fmt::Display for Discriminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::U8(d) => write!(f, "{}", d),
+            Self::U16(d) => write!(f, "{}", d),
+            Self::String(d) => write!(f, "{}", d),
+        }
+    }
 }
 
 /// Represents the type info of struct fields.
@@ -131,6 +200,12 @@ impl Describe for () {
     }
 }
 
+impl<T: ?Sized> Describe for PhantomData<T> {
+    fn describe() -> Type {
+        Type::Unit
+    }
+}
+
 macro_rules! describe_basic_type {
     ($type:ident, $type_id:expr) => {
         impl Describe for $type {
@@ -159,6 +234,18 @@ describe_basic_type!(usize, Type::U32);
 describe_basic_type!(str, Type::String);
 describe_basic_type!(String, Type::String);
 
+impl Describe for &str {
+    fn describe() -> Type {
+        Type::String
+    }
+}
+
+impl<'a, B: Describe + ToOwned + ?Sized> Describe for Cow<'a, B> {
+    fn describe() -> Type {
+        B::describe()
+    }
+}
+
 impl<T: Describe> Describe for Option<T> {
     fn describe() -> Type {
         let ty = T::describe();
@@ -227,6 +314,18 @@ impl<T: Describe> Describe for Vec<T> {
     }
 }
 
+impl<T: Describe> Describe for [T] {
+    fn describe() -> Type {
+        Vec::<T>::describe()
+    }
+}
+
+impl<T: Describe> Describe for &[T] {
+    fn describe() -> Type {
+        Vec::<T>::describe()
+    }
+}
+
 impl<T: Describe> Describe for BTreeSet<T> {
     fn describe() -> Type {
         let ty = T::describe();
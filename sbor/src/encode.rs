@@ -1,6 +1,8 @@
+use crate::rust::borrow::{Cow, ToOwned};
 use crate::rust::boxed::Box;
 use crate::rust::cell::RefCell;
 use crate::rust::collections::*;
+use crate::rust::marker::PhantomData;
 use crate::rust::string::String;
 use crate::rust::vec::Vec;
 use crate::type_id::*;
@@ -52,6 +54,25 @@ impl Encoder {
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.buf.extend(slice);
     }
+
+    /// Writes set elements or map entries in canonical order, i.e. sorted by the encoding of
+    /// their keys, given a function that encodes each entry as a key and a value.
+    pub fn write_sorted<T, F: Fn(&T, &mut Encoder, &mut Encoder)>(&mut self, entries: &[T], f: F) {
+        let mut encoded: Vec<(Vec<u8>, Vec<u8>)> = entries
+            .iter()
+            .map(|e| {
+                let mut key = Encoder::new(Vec::new(), self.with_type);
+                let mut value = Encoder::new(Vec::new(), self.with_type);
+                f(e, &mut key, &mut value);
+                (key.buf, value.buf)
+            })
+            .collect();
+        encoded.sort();
+        for (key, value) in encoded {
+            self.write_slice(&key);
+            self.write_slice(&value);
+        }
+    }
 }
 
 impl From<Encoder> for Vec<u8> {
@@ -64,6 +85,10 @@ impl Encode for () {
     fn encode_value(&self, _encoder: &mut Encoder) {}
 }
 
+impl<T: ?Sized> Encode for PhantomData<T> {
+    fn encode_value(&self, _encoder: &mut Encoder) {}
+}
+
 impl Encode for bool {
     fn encode_value(&self, encoder: &mut Encoder) {
         encoder.write_u8(if *self { 1u8 } else { 0u8 })
@@ -159,6 +184,12 @@ impl<T: Encode> Encode for RefCell<T> {
     }
 }
 
+impl<'a, B: Encode + ToOwned + ?Sized> Encode for Cow<'a, B> {
+    fn encode_value(&self, encoder: &mut Encoder) {
+        self.as_ref().encode_value(encoder);
+    }
+}
+
 impl<T: Encode, const N: usize> Encode for [T; N] {
     fn encode_value(&self, encoder: &mut Encoder) {
         encoder.write_type(T::type_id());
@@ -226,13 +257,18 @@ impl<T: Encode> Encode for [T] {
     }
 }
 
+impl<T: Encode> Encode for &[T] {
+    fn encode_value(&self, encoder: &mut Encoder) {
+        (*self).encode_value(encoder);
+    }
+}
+
 impl<T: Encode> Encode for BTreeSet<T> {
     fn encode_value(&self, encoder: &mut Encoder) {
         encoder.write_type(T::type_id());
         encoder.write_len(self.len());
-        for v in self {
-            v.encode_value(encoder);
-        }
+        let elements: Vec<&T> = self.iter().collect();
+        encoder.write_sorted(&elements, |v, key, _| v.encode_value(key));
     }
 }
 
@@ -241,10 +277,11 @@ impl<K: Encode, V: Encode> Encode for BTreeMap<K, V> {
         encoder.write_type(K::type_id());
         encoder.write_type(V::type_id());
         encoder.write_len(self.len());
-        for (k, v) in self {
-            k.encode_value(encoder);
-            v.encode_value(encoder);
-        }
+        let entries: Vec<(&K, &V)> = self.iter().collect();
+        encoder.write_sorted(&entries, |(k, v), key, value| {
+            k.encode_value(key);
+            v.encode_value(value);
+        });
     }
 }
 
@@ -252,9 +289,8 @@ impl<T: Encode> Encode for HashSet<T> {
     fn encode_value(&self, encoder: &mut Encoder) {
         encoder.write_type(T::type_id());
         encoder.write_len(self.len());
-        for v in self {
-            v.encode_value(encoder);
-        }
+        let elements: Vec<&T> = self.iter().collect();
+        encoder.write_sorted(&elements, |v, key, _| v.encode_value(key));
     }
 }
 
@@ -263,10 +299,11 @@ impl<K: Encode, V: Encode> Encode for HashMap<K, V> {
         encoder.write_type(K::type_id());
         encoder.write_type(V::type_id());
         encoder.write_len(self.len());
-        for (k, v) in self {
-            k.encode_value(encoder);
-            v.encode_value(encoder);
-        }
+        let entries: Vec<(&K, &V)> = self.iter().collect();
+        encoder.write_sorted(&entries, |(k, v), key, value| {
+            k.encode_value(key);
+            v.encode_value(value);
+        });
     }
 }
 
@@ -379,6 +416,30 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_canonical_encoding() {
+        let mut map1 = HashMap::<u32, u8>::new();
+        let mut map2 = HashMap::<u32, u8>::new();
+        for i in 0..100 {
+            map1.insert(i, i as u8);
+            map2.insert(99 - i, (99 - i) as u8);
+        }
+        let mut enc1 = Encoder::with_type(Vec::new());
+        map1.encode(&mut enc1);
+        let mut enc2 = Encoder::with_type(Vec::new());
+        map2.encode(&mut enc2);
+        assert_eq!(Vec::<u8>::from(enc1), Vec::<u8>::from(enc2));
+
+        // Entries are sorted by their encoding, not by `Ord`
+        let mut set = BTreeSet::<u16>::new();
+        set.insert(1);
+        set.insert(256);
+        let mut enc = Encoder::no_type(Vec::new());
+        set.encode(&mut enc);
+        let bytes: Vec<u8> = enc.into();
+        assert_eq!(vec![2, 0, 0, 0, 0, 1, 1, 0], bytes);
+    }
+
     #[test]
     pub fn test_encode_rc() {
         let x = crate::rust::rc::Rc::new(5u8);
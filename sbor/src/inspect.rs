@@ -0,0 +1,504 @@
+use crate::decode::*;
+use crate::describe::{Discriminator, Fields, Type};
+use crate::rust::fmt;
+use crate::rust::format;
+use crate::rust::string::String;
+use crate::rust::string::ToString;
+use crate::rust::vec::Vec;
+use crate::type_id::*;
+use crate::validate::{describe_type_id, type_id};
+
+/// The maximum nesting depth inspected, which keeps corrupted input from exhausting the stack.
+const MAX_DEPTH: usize = 64;
+
+/// A value of an SBOR payload, annotated with where and how it is encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// The offset of the type id, or of the value if its type is given by its container
+    pub offset: usize,
+    /// The field name, element index or map entry this value is found at
+    pub label: Option<String>,
+    pub type_id: u8,
+    /// The name of the type in the schema, such as of a struct
+    pub name: Option<String>,
+    /// The length prefix, for values encoded with one
+    pub len: Option<usize>,
+    /// The value of a primitive or custom type, or the variant of an enum, option or result
+    pub value: Option<String>,
+    pub children: Vec<Node>,
+}
+
+/// The annotated tree of an SBOR payload, as far as it could be decoded.
+#[derive(Debug, Clone)]
+pub struct Inspection {
+    pub root: Option<Node>,
+    /// The offset where decoding failed, and why
+    pub error: Option<(usize, DecodeError)>,
+}
+
+/// Inspects an SBOR payload, with type info included.
+///
+/// Given the schema of the payload, field names and variant names are added.
+pub fn inspect(data: &[u8], schema: Option<&Type>) -> Inspection {
+    inspect_with(data, schema, &|_, _| None)
+}
+
+/// Inspects an SBOR payload, with type info included, given a function that formats the values
+/// of custom types; those it doesn't format are shown as hex.
+pub fn inspect_with(
+    data: &[u8],
+    schema: Option<&Type>,
+    custom: &dyn Fn(u8, &[u8]) -> Option<String>,
+) -> Inspection {
+    let limits = DecodeLimits {
+        max_depth: MAX_DEPTH,
+        ..DecodeLimits::default()
+    };
+    let mut inspector = Inspector {
+        decoder: Decoder::with_limits(data, true, limits),
+        custom,
+    };
+
+    let mut nodes = Vec::new();
+    let result = inspector
+        .next(&mut nodes, None, schema, None)
+        .and_then(|_| inspector.decoder.check_end());
+    Inspection {
+        root: nodes.pop(),
+        error: result.err().map(|e| (inspector.decoder.offset(), e)),
+    }
+}
+
+struct Inspector<'a, 'de> {
+    decoder: Decoder<'de>,
+    custom: &'a dyn Fn(u8, &[u8]) -> Option<String>,
+}
+
+impl<'a, 'de> Inspector<'a, 'de> {
+    /// Inspects the next value and adds it to `nodes`, even if it is only partially decoded.
+    fn next(
+        &mut self,
+        nodes: &mut Vec<Node>,
+        ty_ctx: Option<u8>,
+        schema: Option<&Type>,
+        label: Option<String>,
+    ) -> Result<(), DecodeError> {
+        let offset = self.decoder.offset();
+        let ty = match ty_ctx {
+            Some(t) => t,
+            None => self.decoder.read_type()?,
+        };
+        // The schema is ignored where it doesn't match the data
+        let schema = schema.filter(|s| match s {
+            Type::Custom { .. } => ty >= TYPE_CUSTOM_START,
+            s => type_id(s) == Some(ty),
+        });
+
+        let mut node = Node {
+            offset,
+            label,
+            type_id: ty,
+            name: match schema {
+                Some(Type::Struct { name, .. })
+                | Some(Type::Enum { name, .. })
+                | Some(Type::Custom { name, .. }) => Some(name.clone()),
+                _ => None,
+            },
+            len: None,
+            value: None,
+            children: Vec::new(),
+        };
+        let result = self.decoder.enter().and_then(|_| {
+            let result = self.value(&mut node, schema);
+            self.decoder.exit();
+            result
+        });
+        nodes.push(node);
+        result
+    }
+
+    fn value(&mut self, node: &mut Node, schema: Option<&Type>) -> Result<(), DecodeError> {
+        let dec = &mut self.decoder;
+        match node.type_id {
+            // primitive types
+            TYPE_UNIT => {}
+            TYPE_BOOL => node.value = Some(<bool>::decode_value(dec)?.to_string()),
+            TYPE_I8 => node.value = Some(<i8>::decode_value(dec)?.to_string()),
+            TYPE_I16 => node.value = Some(<i16>::decode_value(dec)?.to_string()),
+            TYPE_I32 => node.value = Some(<i32>::decode_value(dec)?.to_string()),
+            TYPE_I64 => node.value = Some(<i64>::decode_value(dec)?.to_string()),
+            TYPE_I128 => node.value = Some(<i128>::decode_value(dec)?.to_string()),
+            TYPE_U8 => node.value = Some(<u8>::decode_value(dec)?.to_string()),
+            TYPE_U16 => node.value = Some(<u16>::decode_value(dec)?.to_string()),
+            TYPE_U32 => node.value = Some(<u32>::decode_value(dec)?.to_string()),
+            TYPE_U64 => node.value = Some(<u64>::decode_value(dec)?.to_string()),
+            TYPE_U128 => node.value = Some(<u128>::decode_value(dec)?.to_string()),
+            TYPE_STRING => {
+                let len = dec.read_len()?;
+                node.len = Some(len);
+                let s = core::str::from_utf8(dec.read_bytes(len)?)
+                    .map_err(|_| DecodeError::InvalidUtf8)?;
+                node.value = Some(format!("{:?}", s));
+            }
+            // struct & enum
+            TYPE_STRUCT => {
+                let fields = match schema {
+                    Some(Type::Struct { fields, .. }) => Some(fields),
+                    _ => None,
+                };
+                self.fields(node, fields)?;
+            }
+            TYPE_ENUM | TYPE_ENUM_U16 | TYPE_ENUM_STRING => {
+                let discriminator = match node.type_id {
+                    TYPE_ENUM => Discriminator::U8(dec.read_u8()?),
+                    TYPE_ENUM_U16 => Discriminator::U16(<u16>::decode_value(dec)?),
+                    _ => Discriminator::String(<String>::decode_value(dec)?),
+                };
+                let variant = match schema {
+                    Some(Type::Enum { variants, .. }) => variants
+                        .iter()
+                        .enumerate()
+                        .find(|(i, v)| v.discriminator_at(*i) == discriminator)
+                        .map(|(_, v)| v),
+                    _ => None,
+                };
+                node.value = Some(match variant {
+                    Some(v) => format!("{} ({})", v.name, discriminator),
+                    None => discriminator.to_string(),
+                });
+                self.fields(node, variant.map(|v| &v.fields))?;
+            }
+            // composite types
+            TYPE_OPTION | TYPE_RESULT => {
+                let index = dec.read_u8()?;
+                let (name, inner) = match (node.type_id, index, schema) {
+                    (TYPE_OPTION, 0, _) => ("None", None),
+                    (TYPE_OPTION, 1, Some(Type::Option { value })) => ("Some", Some(&**value)),
+                    (TYPE_OPTION, 1, _) => ("Some", None),
+                    (TYPE_RESULT, 0, Some(Type::Result { okay, .. })) => ("Ok", Some(&**okay)),
+                    (TYPE_RESULT, 0, _) => ("Ok", None),
+                    (TYPE_RESULT, 1, Some(Type::Result { error, .. })) => ("Err", Some(&**error)),
+                    (TYPE_RESULT, 1, _) => ("Err", None),
+                    _ => return Err(DecodeError::InvalidIndex(index)),
+                };
+                node.value = Some(name.to_string());
+                if name != "None" {
+                    self.next(&mut node.children, None, inner, None)?;
+                }
+            }
+            TYPE_BOX => {
+                let inner = match schema {
+                    Some(Type::Box { value }) => Some(&**value),
+                    _ => None,
+                };
+                self.next(&mut node.children, None, inner, None)?;
+            }
+            TYPE_TUPLE => {
+                let len = dec.read_len()?;
+                node.len = Some(len);
+                for i in 0..len {
+                    let element = match schema {
+                        Some(Type::Tuple { elements }) => elements.get(i),
+                        _ => None,
+                    };
+                    self.next(&mut node.children, None, element, Some(index(i)))?;
+                }
+            }
+            // collections
+            TYPE_ARRAY | TYPE_VEC | TYPE_TREE_SET | TYPE_HASH_SET => {
+                let ele_ty = dec.read_type()?;
+                let len = dec.read_len()?;
+                node.len = Some(len);
+                let element = match schema {
+                    Some(Type::Array { element, .. })
+                    | Some(Type::Vec { element })
+                    | Some(Type::TreeSet { element })
+                    | Some(Type::HashSet { element }) => Some(&**element),
+                    _ => None,
+                };
+                for i in 0..len {
+                    self.next(&mut node.children, Some(ele_ty), element, Some(index(i)))?;
+                }
+            }
+            TYPE_TREE_MAP | TYPE_HASH_MAP => {
+                let key_ty = dec.read_type()?;
+                let value_ty = dec.read_type()?;
+                let len = dec.read_len()?;
+                node.len = Some(len);
+                let (key, value) = match schema {
+                    Some(Type::TreeMap { key, value }) | Some(Type::HashMap { key, value }) => {
+                        (Some(&**key), Some(&**value))
+                    }
+                    _ => (None, None),
+                };
+                for i in 0..len {
+                    let label = format!("{}.key", index(i));
+                    self.next(&mut node.children, Some(key_ty), key, Some(label))?;
+                    let label = format!("{}.value", index(i));
+                    self.next(&mut node.children, Some(value_ty), value, Some(label))?;
+                }
+            }
+            ty if ty >= TYPE_CUSTOM_START => {
+                let len = dec.read_len()?;
+                node.len = Some(len);
+                let data = dec.read_bytes(len)?;
+                node.value = Some((self.custom)(ty, data).unwrap_or_else(|| hex(data)));
+            }
+            ty => {
+                return Err(DecodeError::InvalidType {
+                    expected: None,
+                    actual: ty,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Inspects the fields of a struct or enum variant, as a child of `node`.
+    fn fields(&mut self, node: &mut Node, schema: Option<&Fields>) -> Result<(), DecodeError> {
+        let offset = self.decoder.offset();
+        let ty = self.decoder.read_type()?;
+        let mut fields = Node {
+            offset,
+            label: None,
+            type_id: ty,
+            name: None,
+            len: None,
+            value: None,
+            children: Vec::new(),
+        };
+        let result = self.field_values(&mut fields, schema);
+        node.children.push(fields);
+        result
+    }
+
+    fn field_values(
+        &mut self,
+        node: &mut Node,
+        schema: Option<&Fields>,
+    ) -> Result<(), DecodeError> {
+        match node.type_id {
+            TYPE_FIELDS_NAMED | TYPE_FIELDS_UNNAMED => {
+                let len = self.decoder.read_len()?;
+                node.len = Some(len);
+                for i in 0..len {
+                    let (label, ty) = match schema {
+                        Some(Fields::Named { named }) if node.type_id == TYPE_FIELDS_NAMED => {
+                            match named.get(i) {
+                                Some((name, ty)) => (name.clone(), Some(ty)),
+                                None => (index(i), None),
+                            }
+                        }
+                        Some(Fields::Unnamed { unnamed })
+                            if node.type_id == TYPE_FIELDS_UNNAMED =>
+                        {
+                            (index(i), unnamed.get(i))
+                        }
+                        _ => (index(i), None),
+                    };
+                    self.next(&mut node.children, None, ty, Some(label))?;
+                }
+                Ok(())
+            }
+            TYPE_FIELDS_UNIT => Ok(()),
+            ty => Err(DecodeError::InvalidType {
+                expected: None,
+                actual: ty,
+            }),
+        }
+    }
+}
+
+fn index(i: usize) -> String {
+    format!("[{}]", i)
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn type_name(ty: u8) -> String {
+    match ty {
+        TYPE_FIELDS_NAMED => "NamedFields".to_string(),
+        TYPE_FIELDS_UNNAMED => "UnnamedFields".to_string(),
+        TYPE_FIELDS_UNIT => "UnitFields".to_string(),
+        ty if ty >= TYPE_CUSTOM_START => "Custom".to_string(),
+        ty => describe_type_id(ty),
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_at(f, 0)
+    }
+}
+
+impl Node {
+    fn fmt_at(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:06x}  {:width$}", self.offset, "", width = depth * 2)?;
+        if let Some(label) = &self.label {
+            write!(f, "{}: ", label)?;
+        }
+        write!(f, "{} ({:#04x})", type_name(self.type_id), self.type_id)?;
+        if let Some(name) = &self.name {
+            write!(f, " {}", name)?;
+        }
+        if let Some(len) = self.len {
+            write!(f, ", len {}", len)?;
+        }
+        if let Some(value) = &self.value {
+            write!(f, " = {}", value)?;
+        }
+        writeln!(f)?;
+        for child in &self.children {
+            child.fmt_at(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(root) = &self.root {
+            write!(f, "{}", root)?;
+        }
+        if let Some((offset, e)) = &self.error {
+            writeln!(f, "error at {:06x}: {:?}", offset, e)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust::borrow::ToOwned;
+    use crate::rust::string::String;
+    use crate::rust::string::ToString;
+    use crate::rust::vec;
+    use crate::rust::vec::Vec;
+    use crate::*;
+
+    use super::*;
+
+    #[derive(TypeId, Encode, Describe)]
+    struct Account {
+        owner: String,
+        balance: Option<u32>,
+        side: Side,
+    }
+
+    #[derive(TypeId, Encode, Describe)]
+    enum Side {
+        Buy,
+        #[allow(dead_code)]
+        Sell,
+    }
+
+    fn account() -> Vec<u8> {
+        encode_with_type(
+            Vec::new(),
+            &Account {
+                owner: "al".to_owned(),
+                balance: Some(5),
+                side: Side::Buy,
+            },
+        )
+    }
+
+    #[test]
+    pub fn test_inspect() {
+        let inspection = inspect(&account(), None);
+        assert!(inspection.error.is_none());
+        assert_eq!(
+            inspection.to_string(),
+            "\
+000000  Struct (0x10)
+000001    NamedFields (0x12), len 3
+000006      [0]: String (0x0c), len 2 = \"al\"
+00000d      [1]: Option (0x20) = Some
+00000f        U32 (0x09) = 5
+000014      [2]: Enum (0x11) = 0
+000016        UnitFields (0x14)
+"
+        );
+    }
+
+    #[test]
+    pub fn test_inspect_schema() {
+        let inspection = inspect(&account(), Some(&Account::describe()));
+        assert_eq!(
+            inspection.to_string(),
+            "\
+000000  Struct (0x10) Account
+000001    NamedFields (0x12), len 3
+000006      owner: String (0x0c), len 2 = \"al\"
+00000d      balance: Option (0x20) = Some
+00000f        U32 (0x09) = 5
+000014      side: Enum (0x11) Side = Buy (0)
+000016        UnitFields (0x14)
+"
+        );
+    }
+
+    #[test]
+    pub fn test_inspect_invalid() {
+        // The balance is cut short
+        let bytes = account();
+        let inspection = inspect(&bytes[..0x12], Some(&Account::describe()));
+        assert!(matches!(
+            inspection.error,
+            Some((0x10, DecodeError::Underflow { .. }))
+        ));
+        assert_eq!(
+            inspection.to_string(),
+            "\
+000000  Struct (0x10) Account
+000001    NamedFields (0x12), len 3
+000006      owner: String (0x0c), len 2 = \"al\"
+00000d      balance: Option (0x20) = Some
+00000f        U32 (0x09)
+error at 000010: Underflow { required: 4, remaining: 2 }
+"
+        );
+
+        let mut bytes = account();
+        bytes.push(0);
+        let inspection = inspect(&bytes, None);
+        assert!(inspection.root.is_some());
+        assert!(matches!(
+            inspection.error,
+            Some((0x17, DecodeError::NotAllBytesUsed(1)))
+        ));
+
+        let inspection = inspect(&[TYPE_BOX; 1000], None);
+        assert!(matches!(
+            inspection.error,
+            Some((65, DecodeError::MaxDepthExceeded(MAX_DEPTH)))
+        ));
+
+        let inspection = inspect(&[0x7f], None);
+        assert_eq!(inspection.root.unwrap().type_id, 0x7f);
+        assert!(matches!(
+            inspection.error,
+            Some((1, DecodeError::InvalidType { .. }))
+        ));
+    }
+
+    #[test]
+    pub fn test_inspect_custom() {
+        let bytes = vec![TYPE_VEC, 0x81, 1, 0, 0, 0, 2, 0, 0, 0, 0xab, 0xcd];
+        assert_eq!(
+            inspect(&bytes, None).to_string(),
+            "\
+000000  Vec (0x30), len 1
+000006    [0]: Custom (0x81), len 2 = abcd
+"
+        );
+        let custom = |ty: u8, data: &[u8]| Some(format!("{}:{}", ty, data.len()));
+        let inspection = inspect_with(&bytes, None, &custom);
+        assert_eq!(
+            inspection.root.unwrap().children[0].value,
+            Some("129:2".to_string())
+        );
+    }
+}
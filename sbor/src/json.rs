@@ -0,0 +1,1149 @@
+use crate::any::*;
+use crate::describe::{Discriminator, Fields as TypeFields, Type};
+use crate::rust::boxed::Box;
+use crate::rust::convert::TryFrom;
+use crate::rust::fmt;
+use crate::rust::format;
+use crate::rust::string::String;
+use crate::rust::string::ToString;
+use crate::rust::vec;
+use crate::rust::vec::Vec;
+use crate::type_id::*;
+use crate::validate::*;
+
+pub use serde_json::Map as JsonMap;
+pub use serde_json::Value as JsonValue;
+
+/// Represents an error ocurred during JSON conversion.
+#[derive(Debug, Clone)]
+pub enum JsonError {
+    /// The JSON at `path` is not of the expected form.
+    InvalidJson { path: String, expected: String },
+
+    /// The type id of the named custom type is unknown.
+    UnknownType(String),
+
+    /// The value does not match the schema.
+    Mismatch(ValidateError),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson { path, expected } if path.is_empty() => {
+                write!(f, "expected {}", expected)
+            }
+            Self::InvalidJson { path, expected } => write!(f, "{}: expected {}", path, expected),
+            Self::UnknownType(name) => write!(f, "unknown type: {}", name),
+            Self::Mismatch(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Renders custom types, which SBOR only knows as bytes, readably.
+///
+/// Custom values without a rendering are written as `{"type": "Custom", "id": 128, "data": "<hex>"}`
+/// by `to_json`, and as hex strings by `to_json_with_schema`. Use `&()` for no custom types.
+pub trait CustomJson {
+    /// Returns the name of a custom type id, e.g. `Amount`.
+    fn type_name(&self, _ty: u8) -> Option<String> {
+        None
+    }
+
+    /// Returns the type id of a custom type, by the name from `type_name` or by the name it's
+    /// described with.
+    fn type_id(&self, _name: &str) -> Option<u8> {
+        None
+    }
+
+    /// Renders the data of a custom value.
+    fn render(&self, _ty: u8, _data: &[u8]) -> Option<JsonValue> {
+        None
+    }
+
+    /// Reads the data of a custom value back from its rendering.
+    fn parse(&self, _ty: u8, _json: &JsonValue) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+impl CustomJson for () {}
+
+/// Converts a value into JSON that tags every value with its type, e.g. `{"type": "U32", "value": 1}`.
+///
+/// 128-bit integers are written as strings. `from_json` reads the result back into the same value.
+pub fn to_json(value: &Value, custom: &dyn CustomJson) -> JsonValue {
+    Converter { custom }.write_tagged(value)
+}
+
+/// Reads a value from JSON written by `to_json`.
+pub fn from_json(json: &JsonValue, custom: &dyn CustomJson) -> Result<Value, JsonError> {
+    Converter { custom }.read_tagged(json, "")
+}
+
+/// Converts a value of the given type into plain JSON, as `serde_json` would render the Rust type.
+///
+/// Structs with named fields become objects, enums become `"Variant"` or `{"Variant": fields}`,
+/// `None` becomes `null` and maps with string keys become objects. `Some` of a type that may
+/// itself be `null` is wrapped in a one-element array, so that `from_json_with_schema` can read
+/// the result back into the same value.
+pub fn to_json_with_schema(
+    value: &Value,
+    ty: &Type,
+    custom: &dyn CustomJson,
+) -> Result<JsonValue, JsonError> {
+    Converter { custom }.write_plain(value, ty, "")
+}
+
+/// Reads a value of the given type from JSON written by `to_json_with_schema`.
+pub fn from_json_with_schema(
+    json: &JsonValue,
+    ty: &Type,
+    custom: &dyn CustomJson,
+) -> Result<Value, JsonError> {
+    Converter { custom }.read_plain(json, ty, "")
+}
+
+struct Converter<'a> {
+    custom: &'a dyn CustomJson,
+}
+
+impl<'a> Converter<'a> {
+    fn write_tagged(&self, value: &Value) -> JsonValue {
+        let mut json = JsonMap::new();
+        json.insert(
+            "type".to_string(),
+            self.type_name(value_type_id(value)).into(),
+        );
+        match value {
+            Value::Struct(fields) => self.insert_fields(&mut json, fields),
+            Value::Enum(discriminator, fields) => {
+                let discriminator = match discriminator {
+                    Discriminator::U8(d) => (*d).into(),
+                    Discriminator::U16(d) => (*d).into(),
+                    Discriminator::String(d) => d.clone().into(),
+                };
+                json.insert("index".to_string(), discriminator);
+                self.insert_fields(&mut json, fields);
+            }
+            Value::Option(v) => {
+                let v = match v.as_ref() {
+                    Some(v) => self.write_tagged(v),
+                    None => JsonValue::Null,
+                };
+                json.insert("value".to_string(), v);
+            }
+            Value::Box(v) => {
+                json.insert("value".to_string(), self.write_tagged(v));
+            }
+            Value::Array(ty, elements)
+            | Value::Vec(ty, elements)
+            | Value::TreeSet(ty, elements)
+            | Value::HashSet(ty, elements) => {
+                json.insert("element_type".to_string(), self.type_name(*ty).into());
+                json.insert("elements".to_string(), self.write_tagged_elements(elements));
+            }
+            Value::Tuple(elements) => {
+                json.insert("elements".to_string(), self.write_tagged_elements(elements));
+            }
+            Value::Result(v) => match v.as_ref() {
+                Ok(v) => {
+                    json.insert("okay".to_string(), self.write_tagged(v));
+                }
+                Err(v) => {
+                    json.insert("error".to_string(), self.write_tagged(v));
+                }
+            },
+            Value::TreeMap(key_ty, value_ty, entries)
+            | Value::HashMap(key_ty, value_ty, entries) => {
+                json.insert("key_type".to_string(), self.type_name(*key_ty).into());
+                json.insert("value_type".to_string(), self.type_name(*value_ty).into());
+                let entries = entries
+                    .iter()
+                    .map(|(k, v)| {
+                        JsonValue::Array(vec![self.write_tagged(k), self.write_tagged(v)])
+                    })
+                    .collect();
+                json.insert("entries".to_string(), JsonValue::Array(entries));
+            }
+            Value::Custom(ty, data) => match self.custom.render(*ty, data) {
+                Some(v) if self.custom.type_name(*ty).is_some() => {
+                    json.insert("value".to_string(), v);
+                }
+                _ => {
+                    json.insert("type".to_string(), "Custom".into());
+                    json.insert("id".to_string(), (*ty).into());
+                    json.insert("data".to_string(), to_hex(data).into());
+                }
+            },
+            Value::Unit => {}
+            v => {
+                json.insert("value".to_string(), primitive_to_json(v));
+            }
+        }
+        JsonValue::Object(json)
+    }
+
+    fn insert_fields(&self, json: &mut JsonMap<String, JsonValue>, fields: &Fields) {
+        match fields {
+            Fields::Named(values) => {
+                json.insert("named".to_string(), self.write_tagged_elements(values));
+            }
+            Fields::Unnamed(values) => {
+                json.insert("unnamed".to_string(), self.write_tagged_elements(values));
+            }
+            Fields::Unit => {}
+        }
+    }
+
+    fn write_tagged_elements(&self, elements: &[Value]) -> JsonValue {
+        JsonValue::Array(elements.iter().map(|e| self.write_tagged(e)).collect())
+    }
+
+    fn read_tagged(&self, json: &JsonValue, path: &str) -> Result<Value, JsonError> {
+        let object = json
+            .as_object()
+            .ok_or_else(|| invalid(path, "a tagged value"))?;
+        let name = get(object, "type", path)?
+            .as_str()
+            .ok_or_else(|| invalid(&join(path, "type"), "a type name"))?;
+        if name == "Custom" {
+            let ty = get(object, "id", path)?
+                .as_u64()
+                .and_then(|id| u8::try_from(id).ok())
+                .filter(|id| *id >= TYPE_CUSTOM_START)
+                .ok_or_else(|| invalid(&join(path, "id"), "a custom type id"))?;
+            let data = get(object, "data", path)?
+                .as_str()
+                .and_then(from_hex)
+                .ok_or_else(|| invalid(&join(path, "data"), "a hex string"))?;
+            return Ok(Value::Custom(ty, data));
+        }
+
+        let ty = self
+            .parse_type_name(name)
+            .ok_or_else(|| invalid(&join(path, "type"), "a type name"))?;
+        match ty {
+            TYPE_UNIT => Ok(Value::Unit),
+            TYPE_STRUCT => Ok(Value::Struct(self.read_tagged_fields(object, path)?)),
+            TYPE_ENUM | TYPE_ENUM_U16 | TYPE_ENUM_STRING => {
+                let index = get(object, "index", path)?;
+                let discriminator = match ty {
+                    TYPE_ENUM => index
+                        .as_u64()
+                        .and_then(|i| u8::try_from(i).ok())
+                        .map(Discriminator::U8),
+                    TYPE_ENUM_U16 => index
+                        .as_u64()
+                        .and_then(|i| u16::try_from(i).ok())
+                        .map(Discriminator::U16),
+                    _ => index.as_str().map(|i| Discriminator::String(i.to_string())),
+                }
+                .ok_or_else(|| invalid(&join(path, "index"), "a variant discriminator"))?;
+                Ok(Value::Enum(
+                    discriminator,
+                    self.read_tagged_fields(object, path)?,
+                ))
+            }
+            TYPE_OPTION => {
+                let v = match get(object, "value", path)? {
+                    JsonValue::Null => None,
+                    v => Some(self.read_tagged(v, &join(path, "value"))?),
+                };
+                Ok(Value::Option(Box::new(v)))
+            }
+            TYPE_BOX => {
+                let v = self.read_tagged(get(object, "value", path)?, &join(path, "value"))?;
+                Ok(Value::Box(Box::new(v)))
+            }
+            TYPE_ARRAY | TYPE_VEC | TYPE_TREE_SET | TYPE_HASH_SET => {
+                let element_ty = self.get_type(object, "element_type", path)?;
+                let elements = self.read_tagged_elements(object, element_ty, path)?;
+                Ok(match ty {
+                    TYPE_ARRAY => Value::Array(element_ty, elements),
+                    TYPE_VEC => Value::Vec(element_ty, elements),
+                    TYPE_TREE_SET => Value::TreeSet(element_ty, elements),
+                    _ => Value::HashSet(element_ty, elements),
+                })
+            }
+            TYPE_TUPLE => {
+                let elements = get(object, "elements", path)?;
+                let path = join(path, "elements");
+                let elements = elements
+                    .as_array()
+                    .ok_or_else(|| invalid(&path, "an array"))?
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| self.read_tagged(e, &index(&path, i)))
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::Tuple(elements))
+            }
+            TYPE_RESULT => {
+                let v = match (object.get("okay"), object.get("error")) {
+                    (Some(v), None) => Ok(self.read_tagged(v, &join(path, "okay"))?),
+                    (None, Some(v)) => Err(self.read_tagged(v, &join(path, "error"))?),
+                    _ => return Err(invalid(path, "either `okay` or `error`")),
+                };
+                Ok(Value::Result(Box::new(v)))
+            }
+            TYPE_TREE_MAP | TYPE_HASH_MAP => {
+                let key_ty = self.get_type(object, "key_type", path)?;
+                let value_ty = self.get_type(object, "value_type", path)?;
+                let entries = get(object, "entries", path)?;
+                let path = join(path, "entries");
+                let entries = entries
+                    .as_array()
+                    .ok_or_else(|| invalid(&path, "an array"))?
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| {
+                        let path = index(&path, i);
+                        match e.as_array().map(Vec::as_slice) {
+                            Some([k, v]) => Ok((
+                                self.read_tagged_typed(k, key_ty, &index(&path, 0))?,
+                                self.read_tagged_typed(v, value_ty, &index(&path, 1))?,
+                            )),
+                            _ => Err(invalid(&path, "a key-value pair")),
+                        }
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(if ty == TYPE_TREE_MAP {
+                    Value::TreeMap(key_ty, value_ty, entries)
+                } else {
+                    Value::HashMap(key_ty, value_ty, entries)
+                })
+            }
+            ty if ty >= TYPE_CUSTOM_START => {
+                let data = self
+                    .custom
+                    .parse(ty, get(object, "value", path)?)
+                    .ok_or_else(|| invalid(&join(path, "value"), name))?;
+                Ok(Value::Custom(ty, data))
+            }
+            ty => parse_primitive(ty, get(object, "value", path)?, &join(path, "value")),
+        }
+    }
+
+    fn read_tagged_fields(
+        &self,
+        object: &JsonMap<String, JsonValue>,
+        path: &str,
+    ) -> Result<Fields, JsonError> {
+        let parse = |key: &str, json: &JsonValue| -> Result<Vec<Value>, JsonError> {
+            let path = join(path, key);
+            json.as_array()
+                .ok_or_else(|| invalid(&path, "an array"))?
+                .iter()
+                .enumerate()
+                .map(|(i, e)| self.read_tagged(e, &index(&path, i)))
+                .collect()
+        };
+        match (object.get("named"), object.get("unnamed")) {
+            (Some(json), None) => Ok(Fields::Named(parse("named", json)?)),
+            (None, Some(json)) => Ok(Fields::Unnamed(parse("unnamed", json)?)),
+            (None, None) => Ok(Fields::Unit),
+            _ => Err(invalid(path, "either `named` or `unnamed` fields")),
+        }
+    }
+
+    fn read_tagged_elements(
+        &self,
+        object: &JsonMap<String, JsonValue>,
+        ty: u8,
+        path: &str,
+    ) -> Result<Vec<Value>, JsonError> {
+        let elements = get(object, "elements", path)?;
+        let path = join(path, "elements");
+        elements
+            .as_array()
+            .ok_or_else(|| invalid(&path, "an array"))?
+            .iter()
+            .enumerate()
+            .map(|(i, e)| self.read_tagged_typed(e, ty, &index(&path, i)))
+            .collect()
+    }
+
+    /// Reads a tagged value, which must be of the given type as it's an element of a collection.
+    fn read_tagged_typed(&self, json: &JsonValue, ty: u8, path: &str) -> Result<Value, JsonError> {
+        let value = self.read_tagged(json, path)?;
+        if value_type_id(&value) == ty {
+            Ok(value)
+        } else {
+            Err(invalid(path, &self.type_name(ty)))
+        }
+    }
+
+    fn get_type(
+        &self,
+        object: &JsonMap<String, JsonValue>,
+        key: &str,
+        path: &str,
+    ) -> Result<u8, JsonError> {
+        get(object, key, path)?
+            .as_str()
+            .and_then(|name| self.parse_type_name(name))
+            .ok_or_else(|| invalid(&join(path, key), "a type name"))
+    }
+
+    fn write_plain(&self, value: &Value, ty: &Type, path: &str) -> Result<JsonValue, JsonError> {
+        match (value, ty) {
+            (Value::Struct(fields), Type::Struct { fields: tys, .. }) => {
+                self.write_plain_fields(fields, tys, &join(path, "fields"))
+            }
+            (Value::Enum(discriminator, fields), Type::Enum { variants, .. }) => {
+                let variant = variants
+                    .iter()
+                    .enumerate()
+                    .find(|(i, v)| v.discriminator_at(*i) == *discriminator)
+                    .map(|(_, v)| v)
+                    .ok_or_else(|| self.mismatch(value, ty, path))?;
+                let fields =
+                    self.write_plain_fields(fields, &variant.fields, &join(path, &variant.name))?;
+                Ok(match variant.fields {
+                    TypeFields::Unit => variant.name.clone().into(),
+                    _ => single(&variant.name, fields),
+                })
+            }
+            (Value::Option(v), Type::Option { value: ty }) => match v.as_ref() {
+                Some(v) => {
+                    let v = self.write_plain(v, ty, &join(path, "value"))?;
+                    Ok(if nullable(ty) {
+                        JsonValue::Array(vec![v])
+                    } else {
+                        v
+                    })
+                }
+                None => Ok(JsonValue::Null),
+            },
+            (Value::Box(v), Type::Box { value: ty }) => {
+                self.write_plain(v, ty, &join(path, "value"))
+            }
+            (Value::Array(_, elements), Type::Array { element, length })
+                if elements.len() == *length as usize =>
+            {
+                self.write_plain_elements(elements, element, path)
+            }
+            (Value::Tuple(elements), Type::Tuple { elements: tys })
+                if elements.len() == tys.len() =>
+            {
+                let elements = elements
+                    .iter()
+                    .zip(tys)
+                    .enumerate()
+                    .map(|(i, (e, ty))| self.write_plain(e, ty, &index(path, i)))
+                    .collect::<Result<_, _>>()?;
+                Ok(JsonValue::Array(elements))
+            }
+            (Value::Result(v), Type::Result { okay, error }) => match v.as_ref() {
+                Ok(v) => Ok(single(
+                    "Ok",
+                    self.write_plain(v, okay, &join(path, "okay"))?,
+                )),
+                Err(v) => Ok(single(
+                    "Err",
+                    self.write_plain(v, error, &join(path, "error"))?,
+                )),
+            },
+            (Value::Vec(_, elements), Type::Vec { element })
+            | (Value::TreeSet(_, elements), Type::TreeSet { element })
+            | (Value::HashSet(_, elements), Type::HashSet { element }) => {
+                self.write_plain_elements(elements, element, path)
+            }
+            (Value::TreeMap(_, _, entries), Type::TreeMap { key, value: ty })
+            | (Value::HashMap(_, _, entries), Type::HashMap { key, value: ty }) => {
+                if let Type::String = key.as_ref() {
+                    let mut json = JsonMap::new();
+                    for (i, (k, v)) in entries.iter().enumerate() {
+                        let path = index(path, i);
+                        match k {
+                            Value::String(k) => {
+                                let v = self.write_plain(v, ty, &join(&path, "value"))?;
+                                json.insert(k.clone(), v);
+                            }
+                            k => return Err(self.mismatch(k, key, &join(&path, "key"))),
+                        }
+                    }
+                    Ok(JsonValue::Object(json))
+                } else {
+                    let entries = entries
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (k, v))| {
+                            let path = index(path, i);
+                            Ok(JsonValue::Array(vec![
+                                self.write_plain(k, key, &join(&path, "key"))?,
+                                self.write_plain(v, ty, &join(&path, "value"))?,
+                            ]))
+                        })
+                        .collect::<Result<_, _>>()?;
+                    Ok(JsonValue::Array(entries))
+                }
+            }
+            (Value::Custom(id, data), Type::Custom { name, .. }) => {
+                match self.custom.type_id(name) {
+                    Some(expected) if expected != *id => Err(self.mismatch(value, ty, path)),
+                    _ => Ok(self
+                        .custom
+                        .render(*id, data)
+                        .unwrap_or_else(|| to_hex(data).into())),
+                }
+            }
+            // A custom type that's encoded as a standard one, which the schema says nothing about
+            (value, Type::Custom { name, .. })
+                if self.custom.type_id(name) == Some(value_type_id(value)) =>
+            {
+                Ok(self.write_tagged(value))
+            }
+            (value, ty) if type_id(ty) == Some(value_type_id(value)) && is_primitive(ty) => {
+                Ok(primitive_to_json(value))
+            }
+            (value, ty) => Err(self.mismatch(value, ty, path)),
+        }
+    }
+
+    fn write_plain_fields(
+        &self,
+        fields: &Fields,
+        tys: &TypeFields,
+        path: &str,
+    ) -> Result<JsonValue, JsonError> {
+        match (fields, tys) {
+            (Fields::Named(values), TypeFields::Named { named }) if values.len() == named.len() => {
+                let mut json = JsonMap::new();
+                for (v, (name, ty)) in values.iter().zip(named) {
+                    json.insert(name.clone(), self.write_plain(v, ty, &join(path, name))?);
+                }
+                Ok(JsonValue::Object(json))
+            }
+            (Fields::Unnamed(values), TypeFields::Unnamed { unnamed })
+                if values.len() == unnamed.len() =>
+            {
+                let values = values
+                    .iter()
+                    .zip(unnamed)
+                    .enumerate()
+                    .map(|(i, (v, ty))| self.write_plain(v, ty, &index(path, i)))
+                    .collect::<Result<_, _>>()?;
+                Ok(JsonValue::Array(values))
+            }
+            (Fields::Unit, TypeFields::Unit) => Ok(JsonValue::Null),
+            _ => Err(JsonError::Mismatch(ValidateError::Mismatch {
+                path: path.to_string(),
+                expected: describe_fields(tys).to_string(),
+                actual: match fields {
+                    Fields::Named(_) => "named fields",
+                    Fields::Unnamed(_) => "unnamed fields",
+                    Fields::Unit => "no fields",
+                }
+                .to_string(),
+            })),
+        }
+    }
+
+    fn write_plain_elements(
+        &self,
+        elements: &[Value],
+        ty: &Type,
+        path: &str,
+    ) -> Result<JsonValue, JsonError> {
+        let elements = elements
+            .iter()
+            .enumerate()
+            .map(|(i, e)| self.write_plain(e, ty, &index(path, i)))
+            .collect::<Result<_, _>>()?;
+        Ok(JsonValue::Array(elements))
+    }
+
+    fn read_plain(&self, json: &JsonValue, ty: &Type, path: &str) -> Result<Value, JsonError> {
+        match ty {
+            Type::Struct { fields, .. } => {
+                Ok(Value::Struct(self.read_plain_fields(json, fields, path)?))
+            }
+            Type::Enum { variants, .. } => {
+                let (name, fields) = match json {
+                    JsonValue::String(name) => (name, None),
+                    JsonValue::Object(object) if object.len() == 1 => {
+                        let (name, fields) = object.iter().next().unwrap();
+                        (name, Some(fields))
+                    }
+                    _ => return Err(invalid(path, "a variant")),
+                };
+                let (i, variant) = variants
+                    .iter()
+                    .enumerate()
+                    .find(|(_, v)| &v.name == name)
+                    .ok_or_else(|| invalid(path, "a known variant"))?;
+                let fields = match (fields, &variant.fields) {
+                    (None, TypeFields::Unit) => Fields::Unit,
+                    (Some(json), tys) => self.read_plain_fields(json, tys, &join(path, name))?,
+                    (None, tys) => return Err(invalid(path, describe_fields(tys))),
+                };
+                Ok(Value::Enum(variant.discriminator_at(i), fields))
+            }
+            Type::Option { value: ty } => {
+                let v = match json {
+                    JsonValue::Null => None,
+                    json if nullable(ty) => match json.as_array().map(Vec::as_slice) {
+                        Some([json]) => Some(self.read_plain(json, ty, &index(path, 0))?),
+                        _ => return Err(invalid(path, "null or a one-element array")),
+                    },
+                    json => Some(self.read_plain(json, ty, path)?),
+                };
+                Ok(Value::Option(Box::new(v)))
+            }
+            Type::Box { value: ty } => Ok(Value::Box(Box::new(self.read_plain(json, ty, path)?))),
+            Type::Array { element, length } => {
+                let elements = self.read_plain_elements(json, element, path)?;
+                if elements.len() != *length as usize {
+                    return Err(invalid(path, &format!("{} elements", length)));
+                }
+                Ok(Value::Array(self.type_id(element)?, elements))
+            }
+            Type::Tuple { elements: tys } => {
+                let elements = json
+                    .as_array()
+                    .filter(|a| a.len() == tys.len())
+                    .ok_or_else(|| invalid(path, &format!("{} elements", tys.len())))?
+                    .iter()
+                    .zip(tys)
+                    .enumerate()
+                    .map(|(i, (e, ty))| self.read_plain(e, ty, &index(path, i)))
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::Tuple(elements))
+            }
+            Type::Result { okay, error } => {
+                let object = json
+                    .as_object()
+                    .filter(|o| o.len() == 1)
+                    .ok_or_else(|| invalid(path, "either `Ok` or `Err`"))?;
+                let v = match (object.get("Ok"), object.get("Err")) {
+                    (Some(v), _) => Ok(self.read_plain(v, okay, &join(path, "Ok"))?),
+                    (_, Some(v)) => Err(self.read_plain(v, error, &join(path, "Err"))?),
+                    _ => return Err(invalid(path, "either `Ok` or `Err`")),
+                };
+                Ok(Value::Result(Box::new(v)))
+            }
+            Type::Vec { element } => Ok(Value::Vec(
+                self.type_id(element)?,
+                self.read_plain_elements(json, element, path)?,
+            )),
+            Type::TreeSet { element } => Ok(Value::TreeSet(
+                self.type_id(element)?,
+                self.read_plain_elements(json, element, path)?,
+            )),
+            Type::HashSet { element } => Ok(Value::HashSet(
+                self.type_id(element)?,
+                self.read_plain_elements(json, element, path)?,
+            )),
+            Type::TreeMap { key, value } | Type::HashMap { key, value } => {
+                let entries = if let Type::String = key.as_ref() {
+                    json.as_object()
+                        .ok_or_else(|| invalid(path, "an object"))?
+                        .iter()
+                        .map(|(k, v)| {
+                            Ok((
+                                Value::String(k.clone()),
+                                self.read_plain(v, value, &join(path, k))?,
+                            ))
+                        })
+                        .collect::<Result<_, _>>()?
+                } else {
+                    json.as_array()
+                        .ok_or_else(|| invalid(path, "an array"))?
+                        .iter()
+                        .enumerate()
+                        .map(|(i, e)| {
+                            let path = index(path, i);
+                            match e.as_array().map(Vec::as_slice) {
+                                Some([k, v]) => Ok((
+                                    self.read_plain(k, key, &index(&path, 0))?,
+                                    self.read_plain(v, value, &index(&path, 1))?,
+                                )),
+                                _ => Err(invalid(&path, "a key-value pair")),
+                            }
+                        })
+                        .collect::<Result<_, _>>()?
+                };
+                let (key, value) = (self.type_id(key)?, self.type_id(value)?);
+                Ok(match ty {
+                    Type::TreeMap { .. } => Value::TreeMap(key, value, entries),
+                    _ => Value::HashMap(key, value, entries),
+                })
+            }
+            Type::Custom { name, .. } => {
+                let id = self.type_id(ty)?;
+                if id < TYPE_CUSTOM_START {
+                    return self.read_tagged_typed(json, id, path);
+                }
+                let data = self
+                    .custom
+                    .parse(id, json)
+                    .or_else(|| json.as_str().and_then(from_hex))
+                    .ok_or_else(|| invalid(path, name))?;
+                Ok(Value::Custom(id, data))
+            }
+            Type::Unit => match json {
+                JsonValue::Null => Ok(Value::Unit),
+                _ => Err(invalid(path, "null")),
+            },
+            ty => parse_primitive(type_id(ty).unwrap(), json, path),
+        }
+    }
+
+    fn read_plain_fields(
+        &self,
+        json: &JsonValue,
+        tys: &TypeFields,
+        path: &str,
+    ) -> Result<Fields, JsonError> {
+        match tys {
+            TypeFields::Named { named } => {
+                let object = json
+                    .as_object()
+                    .filter(|o| o.len() == named.len())
+                    .ok_or_else(|| invalid(path, describe_fields(tys)))?;
+                let values = named
+                    .iter()
+                    .map(|(name, ty)| {
+                        let path = join(path, name);
+                        let v = object.get(name).ok_or_else(|| invalid(&path, "a value"))?;
+                        self.read_plain(v, ty, &path)
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Fields::Named(values))
+            }
+            TypeFields::Unnamed { unnamed } => {
+                let values = json
+                    .as_array()
+                    .filter(|a| a.len() == unnamed.len())
+                    .ok_or_else(|| invalid(path, describe_fields(tys)))?
+                    .iter()
+                    .zip(unnamed)
+                    .enumerate()
+                    .map(|(i, (v, ty))| self.read_plain(v, ty, &index(path, i)))
+                    .collect::<Result<_, _>>()?;
+                Ok(Fields::Unnamed(values))
+            }
+            TypeFields::Unit => match json {
+                JsonValue::Null => Ok(Fields::Unit),
+                _ => Err(invalid(path, "null")),
+            },
+        }
+    }
+
+    fn read_plain_elements(
+        &self,
+        json: &JsonValue,
+        ty: &Type,
+        path: &str,
+    ) -> Result<Vec<Value>, JsonError> {
+        json.as_array()
+            .ok_or_else(|| invalid(path, "an array"))?
+            .iter()
+            .enumerate()
+            .map(|(i, e)| self.read_plain(e, ty, &index(path, i)))
+            .collect()
+    }
+
+    /// Returns the type id of a type, looking up custom types by name.
+    fn type_id(&self, ty: &Type) -> Result<u8, JsonError> {
+        match ty {
+            Type::Custom { name, .. } => self
+                .custom
+                .type_id(name)
+                .ok_or_else(|| JsonError::UnknownType(name.clone())),
+            ty => Ok(type_id(ty).unwrap()),
+        }
+    }
+
+    fn type_name(&self, ty: u8) -> String {
+        if ty >= TYPE_CUSTOM_START {
+            if let Some(name) = self.custom.type_name(ty) {
+                return name;
+            }
+        }
+        describe_type_id(ty)
+    }
+
+    fn parse_type_name(&self, name: &str) -> Option<u8> {
+        type_id_by_name(name)
+            .or_else(|| self.custom.type_id(name))
+            .or_else(|| {
+                name.strip_prefix("Custom(0x")
+                    .and_then(|s| s.strip_suffix(')'))
+                    .and_then(|s| u8::from_str_radix(s, 16).ok())
+                    .filter(|ty| *ty >= TYPE_CUSTOM_START)
+            })
+    }
+
+    /// Returns the error for a value that isn't of the given type, as validation describes it.
+    fn mismatch(&self, value: &Value, ty: &Type, path: &str) -> JsonError {
+        let e = match validate_value(value, ty, &|name| self.custom.type_id(name)) {
+            Err(e) => e.at(path),
+            Ok(()) => ValidateError::Mismatch {
+                path: path.to_string(),
+                expected: describe_type(ty),
+                actual: describe_value(value),
+            },
+        };
+        JsonError::Mismatch(e)
+    }
+}
+
+/// Returns whether the plain JSON of a type may be `null`.
+fn nullable(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Unit
+            | Type::Option { .. }
+            | Type::Struct {
+                fields: TypeFields::Unit,
+                ..
+            }
+    )
+}
+
+fn is_primitive(ty: &Type) -> bool {
+    matches!(type_id(ty), Some(ty) if ty <= TYPE_STRING)
+}
+
+fn primitive_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Bool(v) => (*v).into(),
+        Value::I8(v) => (*v).into(),
+        Value::I16(v) => (*v).into(),
+        Value::I32(v) => (*v).into(),
+        Value::I64(v) => (*v).into(),
+        Value::I128(v) => v.to_string().into(),
+        Value::U8(v) => (*v).into(),
+        Value::U16(v) => (*v).into(),
+        Value::U32(v) => (*v).into(),
+        Value::U64(v) => (*v).into(),
+        Value::U128(v) => v.to_string().into(),
+        Value::String(v) => v.clone().into(),
+        _ => JsonValue::Null,
+    }
+}
+
+fn parse_primitive(ty: u8, json: &JsonValue, path: &str) -> Result<Value, JsonError> {
+    let name = describe_type_id(ty);
+    let err = || invalid(path, &name);
+    let signed = || json.as_i64().ok_or_else(err);
+    let unsigned = || json.as_u64().ok_or_else(err);
+    Ok(match ty {
+        TYPE_UNIT => match json {
+            JsonValue::Null => Value::Unit,
+            _ => return Err(err()),
+        },
+        TYPE_BOOL => Value::Bool(json.as_bool().ok_or_else(err)?),
+        TYPE_I8 => Value::I8(i8::try_from(signed()?).map_err(|_| err())?),
+        TYPE_I16 => Value::I16(i16::try_from(signed()?).map_err(|_| err())?),
+        TYPE_I32 => Value::I32(i32::try_from(signed()?).map_err(|_| err())?),
+        TYPE_I64 => Value::I64(signed()?),
+        TYPE_I128 => Value::I128(json.as_str().and_then(|s| s.parse().ok()).ok_or_else(err)?),
+        TYPE_U8 => Value::U8(u8::try_from(unsigned()?).map_err(|_| err())?),
+        TYPE_U16 => Value::U16(u16::try_from(unsigned()?).map_err(|_| err())?),
+        TYPE_U32 => Value::U32(u32::try_from(unsigned()?).map_err(|_| err())?),
+        TYPE_U64 => Value::U64(unsigned()?),
+        TYPE_U128 => Value::U128(json.as_str().and_then(|s| s.parse().ok()).ok_or_else(err)?),
+        TYPE_STRING => Value::String(json.as_str().ok_or_else(err)?.to_string()),
+        _ => return Err(err()),
+    })
+}
+
+fn type_id_by_name(name: &str) -> Option<u8> {
+    Some(match name {
+        "Unit" => TYPE_UNIT,
+        "Bool" => TYPE_BOOL,
+        "I8" => TYPE_I8,
+        "I16" => TYPE_I16,
+        "I32" => TYPE_I32,
+        "I64" => TYPE_I64,
+        "I128" => TYPE_I128,
+        "U8" => TYPE_U8,
+        "U16" => TYPE_U16,
+        "U32" => TYPE_U32,
+        "U64" => TYPE_U64,
+        "U128" => TYPE_U128,
+        "String" => TYPE_STRING,
+        "Struct" => TYPE_STRUCT,
+        "Enum" => TYPE_ENUM,
+        "EnumU16" => TYPE_ENUM_U16,
+        "EnumString" => TYPE_ENUM_STRING,
+        "Option" => TYPE_OPTION,
+        "Box" => TYPE_BOX,
+        "Array" => TYPE_ARRAY,
+        "Tuple" => TYPE_TUPLE,
+        "Result" => TYPE_RESULT,
+        "Vec" => TYPE_VEC,
+        "TreeSet" => TYPE_TREE_SET,
+        "TreeMap" => TYPE_TREE_MAP,
+        "HashSet" => TYPE_HASH_SET,
+        "HashMap" => TYPE_HASH_MAP,
+        _ => return None,
+    })
+}
+
+fn describe_fields(tys: &TypeFields) -> &'static str {
+    match tys {
+        TypeFields::Named { .. } => "named fields",
+        TypeFields::Unnamed { .. } => "unnamed fields",
+        TypeFields::Unit => "no fields",
+    }
+}
+
+fn single(key: &str, value: JsonValue) -> JsonValue {
+    let mut json = JsonMap::new();
+    json.insert(key.to_string(), value);
+    JsonValue::Object(json)
+}
+
+fn get<'j>(
+    object: &'j JsonMap<String, JsonValue>,
+    key: &str,
+    path: &str,
+) -> Result<&'j JsonValue, JsonError> {
+    object
+        .get(key)
+        .ok_or_else(|| invalid(&join(path, key), "a value"))
+}
+
+fn invalid(path: &str, expected: &str) -> JsonError {
+    JsonError::InvalidJson {
+        path: path.to_string(),
+        expected: expected.to_string(),
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            let digits = s.get(i..i + 2)?;
+            if digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                u8::from_str_radix(digits, 16).ok()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rust::collections::*;
+    use crate::rust::string::String;
+    use crate::rust::string::ToString;
+    use crate::rust::vec;
+    use crate::rust::vec::Vec;
+    use crate::*;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(TypeId, Encode, Describe)]
+    struct Order {
+        price: u128,
+        size: u32,
+        side: Side,
+        note: Option<()>,
+    }
+
+    #[derive(TypeId, Encode, Describe)]
+    enum Side {
+        Buy,
+        Sell(i8),
+    }
+
+    #[derive(TypeId, Encode, Describe)]
+    struct Book {
+        orders: Vec<Order>,
+        depth: HashMap<String, u64>,
+        levels: BTreeMap<u32, (bool, i128)>,
+        last: Result<[u8; 2], String>,
+    }
+
+    /// Renders custom type `0x80` as a decimal string of its little-endian bytes.
+    struct Amounts;
+
+    impl CustomJson for Amounts {
+        fn type_name(&self, ty: u8) -> Option<String> {
+            (ty == 0x80).then(|| "Amount".to_string())
+        }
+
+        fn type_id(&self, name: &str) -> Option<u8> {
+            (name == "Amount").then_some(0x80)
+        }
+
+        fn render(&self, _ty: u8, data: &[u8]) -> Option<JsonValue> {
+            let data = <[u8; 4]>::try_from(data).ok()?;
+            Some(u32::from_le_bytes(data).to_string().into())
+        }
+
+        fn parse(&self, _ty: u8, json: &JsonValue) -> Option<Vec<u8>> {
+            let n: u32 = json.as_str()?.parse().ok()?;
+            Some(n.to_le_bytes().to_vec())
+        }
+    }
+
+    fn book() -> Book {
+        let mut depth = HashMap::new();
+        depth.insert("buy".to_string(), 10);
+        depth.insert("sell".to_string(), 20);
+        let mut levels = BTreeMap::new();
+        levels.insert(5, (true, -1));
+        Book {
+            orders: vec![
+                Order {
+                    price: u128::MAX,
+                    size: 2,
+                    side: Side::Buy,
+                    note: Some(()),
+                },
+                Order {
+                    price: 3,
+                    size: 4,
+                    side: Side::Sell(-5),
+                    note: None,
+                },
+            ],
+            depth,
+            levels,
+            last: Err("none".to_string()),
+        }
+    }
+
+    fn encode_value(value: &Value) -> Vec<u8> {
+        let mut enc = Encoder::with_type(Vec::new());
+        encode_any(None, value, &mut enc);
+        enc.into()
+    }
+
+    #[test]
+    pub fn test_tagged() {
+        let value = decode_any(&encode_with_type(Vec::new(), &(1u8, Some(-2i128)))).unwrap();
+        let json = to_json(&value, &());
+        assert_eq!(
+            json,
+            json!({
+                "type": "Tuple",
+                "elements": [
+                    {"type": "U8", "value": 1},
+                    {"type": "Option", "value": {"type": "I128", "value": "-2"}}
+                ]
+            })
+        );
+        assert_eq!(from_json(&json, &()).unwrap(), value);
+    }
+
+    #[test]
+    pub fn test_tagged_round_trip() {
+        let value = decode_any(&encode_with_type(Vec::new(), &book())).unwrap();
+        let json = to_json(&value, &());
+        assert_eq!(from_json(&json, &()).unwrap(), value);
+    }
+
+    #[test]
+    pub fn test_tagged_custom() {
+        let value = Value::Vec(0x80, vec![Value::Custom(0x80, vec![1, 1, 0, 0])]);
+        let json = to_json(&value, &Amounts);
+        assert_eq!(
+            json,
+            json!({
+                "type": "Vec",
+                "element_type": "Amount",
+                "elements": [{"type": "Amount", "value": "257"}]
+            })
+        );
+        assert_eq!(from_json(&json, &Amounts).unwrap(), value);
+
+        let json = to_json(&value, &());
+        assert_eq!(
+            json,
+            json!({
+                "type": "Vec",
+                "element_type": "Custom(0x80)",
+                "elements": [{"type": "Custom", "id": 128, "data": "01010000"}]
+            })
+        );
+        assert_eq!(from_json(&json, &()).unwrap(), value);
+    }
+
+    #[test]
+    pub fn test_schema() {
+        let bytes = encode_with_type(Vec::new(), &book());
+        let value = decode_any(&bytes).unwrap();
+        let ty = Book::describe();
+        let json = to_json_with_schema(&value, &ty, &()).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "orders": [
+                    {"price": u128::MAX.to_string(), "size": 2, "side": "Buy", "note": [null]},
+                    {"price": "3", "size": 4, "side": {"Sell": [-5]}, "note": null}
+                ],
+                "depth": {"buy": 10, "sell": 20},
+                "levels": [[5, [true, "-1"]]],
+                "last": {"Err": "none"}
+            })
+        );
+
+        let value = from_json_with_schema(&json, &ty, &()).unwrap();
+        assert_eq!(encode_value(&value), bytes);
+    }
+
+    #[test]
+    pub fn test_schema_custom() {
+        let ty = Type::Custom {
+            name: "Amount".to_string(),
+            generics: vec![],
+        };
+        let value = Value::Custom(0x80, vec![1, 0, 0, 0]);
+        let json = to_json_with_schema(&value, &ty, &Amounts).unwrap();
+        assert_eq!(json, json!("1"));
+        assert_eq!(from_json_with_schema(&json, &ty, &Amounts).unwrap(), value);
+
+        let json = to_json_with_schema(&value, &ty, &()).unwrap();
+        assert_eq!(json, json!("01000000"));
+        assert!(matches!(
+            from_json_with_schema(&json, &ty, &()),
+            Err(JsonError::UnknownType(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let ty = Book::describe();
+        let json = json!({
+            "orders": [{"price": "1", "size": -1, "side": "Buy", "note": null}],
+            "depth": {},
+            "levels": [],
+            "last": {"Ok": [1, 2]}
+        });
+        assert_eq!(
+            from_json_with_schema(&json, &ty, &())
+                .unwrap_err()
+                .to_string(),
+            "orders[0].size: expected U32"
+        );
+
+        let value = decode_any(&encode_with_type(Vec::new(), &1u32)).unwrap();
+        assert_eq!(
+            to_json_with_schema(&value, &ty, &())
+                .unwrap_err()
+                .to_string(),
+            "expected Book, got U32"
+        );
+
+        assert_eq!(
+            from_json(&json!({"type": "Vec", "element_type": "U8", "elements": [{"type": "U16", "value": 1}]}), &())
+                .unwrap_err()
+                .to_string(),
+            "elements[0]: expected U8"
+        );
+    }
+}
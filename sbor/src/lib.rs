@@ -7,22 +7,37 @@ compile_error!("Feature `std` and `alloc` can't be enabled at the same time.");
 
 /// SBOR any data encoding and decoding.
 pub mod any;
+/// SBOR schema compatibility checks.
+pub mod compat;
 /// SBOR decoding.
 pub mod decode;
 /// SBOR describing.
 pub mod describe;
 /// SBOR encoding.
 pub mod encode;
+/// SBOR payload inspection.
+pub mod inspect;
+/// SBOR to JSON conversion.
+#[cfg(any(feature = "serde_std", feature = "serde_alloc"))]
+pub mod json;
 /// A facade of Rust types.
 pub mod rust;
+/// SBOR encoding and decoding over `std::io` streams.
+#[cfg(feature = "std")]
+pub mod stream;
 /// SBOR type ids.
 pub mod type_id;
+/// SBOR validation against type descriptions.
+pub mod validate;
 
-pub use any::{decode_any, encode_any};
-pub use decode::{Decode, DecodeError, Decoder};
+pub use any::{decode_any, decode_any_strict, decode_any_with_limits, encode_any};
+pub use decode::{Decode, DecodeError, DecodeLimits, DecodeOwned, Decoder, Versioned};
 pub use describe::Describe;
 pub use encode::{Encode, Encoder};
+#[cfg(feature = "std")]
+pub use stream::{StreamDecoder, StreamEncoder, StreamError};
 pub use type_id::TypeId;
+pub use validate::{validate, validate_value, validate_with, ValidateError};
 
 use crate::rust::vec::Vec;
 
@@ -41,15 +56,34 @@ pub fn encode_no_type<T: Encode + ?Sized>(buf: Vec<u8>, v: &T) -> Vec<u8> {
 }
 
 /// Decode an instance of `T` from a slice, with type info included.
-pub fn decode_with_type<T: Decode>(buf: &[u8]) -> Result<T, DecodeError> {
+pub fn decode_with_type<'de, T: Decode<'de>>(buf: &'de [u8]) -> Result<T, DecodeError> {
     let mut dec = Decoder::with_type(buf);
     let v = T::decode(&mut dec)?;
     dec.check_end()?;
     Ok(v)
 }
 
+/// Decode an instance of `T` from a slice, with type info included, within the given limits.
+pub fn decode_with_limits<'de, T: Decode<'de>>(
+    buf: &'de [u8],
+    limits: DecodeLimits,
+) -> Result<T, DecodeError> {
+    let mut dec = Decoder::with_limits(buf, true, limits);
+    let v = T::decode(&mut dec)?;
+    dec.check_end()?;
+    Ok(v)
+}
+
+/// Decode an instance of `T` from a canonically encoded slice, with type info included.
+pub fn decode_strict<'de, T: Decode<'de>>(buf: &'de [u8]) -> Result<T, DecodeError> {
+    let mut dec = Decoder::with_type(buf).strict();
+    let v = T::decode(&mut dec)?;
+    dec.check_end()?;
+    Ok(v)
+}
+
 /// Decode an instance of `T` from a slice, with no type info.
-pub fn decode_no_type<T: Decode>(buf: &[u8]) -> Result<T, DecodeError> {
+pub fn decode_no_type<'de, T: Decode<'de>>(buf: &'de [u8]) -> Result<T, DecodeError> {
     let mut dec = Decoder::no_type(buf);
     let v = T::decode(&mut dec)?;
     dec.check_end()?;
@@ -8,6 +8,10 @@ pub use alloc::boxed;
 #[cfg(feature = "alloc")]
 pub use alloc::cell;
 #[cfg(feature = "alloc")]
+pub use alloc::fmt;
+#[cfg(feature = "alloc")]
+pub use alloc::format;
+#[cfg(feature = "alloc")]
 pub use alloc::rc;
 #[cfg(feature = "alloc")]
 pub use alloc::string;
@@ -18,6 +22,8 @@ pub use core::convert;
 #[cfg(feature = "alloc")]
 pub use core::hash;
 #[cfg(feature = "alloc")]
+pub use core::marker;
+#[cfg(feature = "alloc")]
 pub use core::mem;
 #[cfg(feature = "alloc")]
 pub use core::ptr;
@@ -31,8 +37,14 @@ pub use std::cell;
 #[cfg(not(feature = "alloc"))]
 pub use std::convert;
 #[cfg(not(feature = "alloc"))]
+pub use std::fmt;
+#[cfg(not(feature = "alloc"))]
+pub use std::format;
+#[cfg(not(feature = "alloc"))]
 pub use std::hash;
 #[cfg(not(feature = "alloc"))]
+pub use std::marker;
+#[cfg(not(feature = "alloc"))]
 pub use std::mem;
 #[cfg(not(feature = "alloc"))]
 pub use std::ptr;
@@ -0,0 +1,444 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::mem;
+
+use crate::decode::*;
+use crate::encode::*;
+use crate::type_id::*;
+
+/// The number of bytes a `StreamDecoder` asks its reader for at a time.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Represents an error ocurred while encoding to or decoding from a stream.
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+
+    Decode(DecodeError),
+
+    /// A value was written while `n` elements of a vec were still expected, or vice versa.
+    UnexpectedElement(usize),
+}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<DecodeError> for StreamError {
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Decode(e) => write!(f, "{:?}", e),
+            Self::UnexpectedElement(n) => write!(f, "{} vec elements were expected", n),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// An encoder that writes values to an `io::Write` as they are encoded, instead of into a
+/// byte buffer of the whole output.
+///
+/// Only one value is buffered at a time, and the elements of a vec can be written one at a
+/// time with `begin_vec` and `encode_element`.
+pub struct StreamEncoder<W: Write> {
+    writer: W,
+    with_type: bool,
+    buf: Vec<u8>,
+    elements: usize,
+}
+
+impl<W: Write> StreamEncoder<W> {
+    pub fn new(writer: W, with_type: bool) -> Self {
+        Self {
+            writer,
+            with_type,
+            buf: Vec::new(),
+            elements: 0,
+        }
+    }
+
+    pub fn with_type(writer: W) -> Self {
+        Self::new(writer, true)
+    }
+
+    pub fn no_type(writer: W) -> Self {
+        Self::new(writer, false)
+    }
+
+    /// Encodes a value and writes it out.
+    pub fn encode<T: Encode + ?Sized>(&mut self, v: &T) -> Result<(), StreamError> {
+        self.check_elements()?;
+        self.write_with(|encoder| v.encode(encoder))
+    }
+
+    /// Writes the header of a `Vec<T>` of `len` elements, which must then be written with
+    /// `encode_element`.
+    pub fn begin_vec<T: Encode>(&mut self, len: usize) -> Result<(), StreamError> {
+        self.check_elements()?;
+        self.write_with(|encoder| {
+            encoder.write_type(TYPE_VEC);
+            encoder.write_type(T::type_id());
+            encoder.write_len(len);
+        })?;
+        self.elements = len;
+        Ok(())
+    }
+
+    /// Encodes the next element of the vec begun with `begin_vec` and writes it out.
+    pub fn encode_element<T: Encode>(&mut self, v: &T) -> Result<(), StreamError> {
+        if self.elements == 0 {
+            return Err(StreamError::UnexpectedElement(0));
+        }
+        self.write_with(|encoder| v.encode_value(encoder))?;
+        self.elements -= 1;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), StreamError> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Returns the writer, once all elements of any vec have been written.
+    pub fn into_inner(mut self) -> Result<W, StreamError> {
+        self.check_elements()?;
+        self.flush()?;
+        Ok(self.writer)
+    }
+
+    fn check_elements(&self) -> Result<(), StreamError> {
+        if self.elements != 0 {
+            Err(StreamError::UnexpectedElement(self.elements))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn write_with<F: FnOnce(&mut Encoder)>(&mut self, f: F) -> Result<(), StreamError> {
+        // The buffer is reused across values
+        let mut encoder = Encoder::new(mem::take(&mut self.buf), self.with_type);
+        f(&mut encoder);
+        let mut buf: Vec<u8> = encoder.into();
+        let result = self.writer.write_all(&buf);
+        buf.clear();
+        self.buf = buf;
+        Ok(result?)
+    }
+}
+
+/// A decoder that reads values from an `io::Read` as they are decoded, instead of from a slice
+/// of the whole input.
+///
+/// Only the value being decoded is buffered, and the elements of a vec can be decoded one at a
+/// time with `decode_vec`. The limits apply to each value or element on its own.
+pub struct StreamDecoder<R: Read> {
+    reader: R,
+    with_type: bool,
+    limits: DecodeLimits,
+    buf: Vec<u8>,
+    offset: usize,
+    eof: bool,
+}
+
+impl<R: Read> StreamDecoder<R> {
+    pub fn new(reader: R, with_type: bool) -> Self {
+        Self::with_limits(reader, with_type, DecodeLimits::default())
+    }
+
+    pub fn with_limits(reader: R, with_type: bool, limits: DecodeLimits) -> Self {
+        Self {
+            reader,
+            with_type,
+            limits,
+            buf: Vec::new(),
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    pub fn with_type(reader: R) -> Self {
+        Self::new(reader, true)
+    }
+
+    pub fn no_type(reader: R) -> Self {
+        Self::new(reader, false)
+    }
+
+    /// Reads and decodes the next value.
+    pub fn decode<T: DecodeOwned>(&mut self) -> Result<T, StreamError> {
+        self.read_with(|decoder| T::decode(decoder))
+    }
+
+    /// Reads the header of a `Vec<T>`, returning an iterator that decodes its elements as they
+    /// are read.
+    pub fn decode_vec<T: DecodeOwned>(&mut self) -> Result<Elements<'_, R, T>, StreamError> {
+        let len = self.read_with(|decoder| {
+            decoder.check_type(TYPE_VEC)?;
+            decoder.check_type(T::type_id())?;
+            decoder.read_len()
+        })?;
+        Ok(Elements {
+            decoder: self,
+            remaining: len,
+            element: PhantomData,
+        })
+    }
+
+    /// Returns whether all of the input has been decoded.
+    pub fn is_end(&mut self) -> Result<bool, StreamError> {
+        if self.offset == self.buf.len() && !self.eof {
+            self.fill(1, 0)?;
+        }
+        Ok(self.offset == self.buf.len())
+    }
+
+    pub fn check_end(&mut self) -> Result<(), StreamError> {
+        if self.is_end()? {
+            Ok(())
+        } else {
+            Err(DecodeError::NotAllBytesUsed(self.buf.len() - self.offset).into())
+        }
+    }
+
+    /// Runs `f` on a decoder of the buffered input, reading more of it until `f` no longer
+    /// underflows.
+    ///
+    /// `f` starts over after every fill, so each fill offers room for as much input as is already
+    /// buffered. A reader with that much at hand doubles the buffer, which keeps the total
+    /// decoding work linear in the size of the value, while only the missing bytes are waited for.
+    fn read_with<T, F>(&mut self, f: F) -> Result<T, StreamError>
+    where
+        F: for<'de> Fn(&mut Decoder<'de>) -> Result<T, DecodeError>,
+    {
+        loop {
+            let mut decoder =
+                Decoder::with_limits(&self.buf[self.offset..], self.with_type, self.limits);
+            match f(&mut decoder) {
+                Ok(value) => {
+                    self.offset += decoder.offset();
+                    return Ok(value);
+                }
+                Err(DecodeError::Underflow {
+                    required,
+                    remaining,
+                }) if !self.eof => {
+                    let buffered = self.buf.len() - self.offset;
+                    self.fill(required - remaining, buffered)?
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Reads at least `missing` more bytes into the buffer, unless the input ends first.
+    ///
+    /// The first read may return up to `capacity` bytes, or a chunk if that is more.
+    fn fill(&mut self, missing: usize, capacity: usize) -> Result<(), StreamError> {
+        // Drop what has been decoded
+        self.buf.drain(..self.offset);
+        self.offset = 0;
+
+        // Read whatever is available, then wait only for the bytes still missing
+        let len = self.buf.len();
+        self.buf.resize(len + capacity.max(CHUNK_SIZE), 0);
+        let n = loop {
+            match self.reader.read(&mut self.buf[len..]) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buf.truncate(len);
+                    return Err(e.into());
+                }
+            }
+        };
+        self.buf.truncate(len + n);
+        if n > 0 && n < missing {
+            (&mut self.reader)
+                .take((missing - n) as u64)
+                .read_to_end(&mut self.buf)?;
+        }
+        self.eof = self.buf.len() < len + missing;
+        Ok(())
+    }
+}
+
+/// An iterator that decodes the elements of a vec from a `StreamDecoder` as they are read.
+pub struct Elements<'a, R: Read, T> {
+    decoder: &'a mut StreamDecoder<R>,
+    remaining: usize,
+    element: PhantomData<T>,
+}
+
+impl<'a, R: Read, T: DecodeOwned> Iterator for Elements<'a, R, T> {
+    type Item = Result<T, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = self.decoder.read_with(|decoder| {
+            // At the depth of elements within a vec, as in `Vec::decode()`
+            decoder.enter()?;
+            decoder.enter()?;
+            T::decode_value(decoder)
+        });
+        // Stop at the first error, as the position in the input is lost
+        self.remaining = if result.is_ok() {
+            self.remaining - 1
+        } else {
+            0
+        };
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust::collections::*;
+
+    /// A reader that returns at most one byte per read, to split values across reads.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((b, rest)), Some(out)) => {
+                    *out = *b;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    /// A reader of a stream which has sent only the given bytes so far, returning at most the
+    /// given number per read. It fails where a stream would block waiting for more.
+    struct Stall<'a>(&'a [u8], usize);
+
+    impl<'a> Read for Stall<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let n = self.0.len().min(buf.len()).min(self.1);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    pub fn test_stream_encode() {
+        let mut map = BTreeMap::new();
+        map.insert("a".to_owned(), 1u32);
+
+        let mut enc = StreamEncoder::with_type(Vec::new());
+        enc.encode(&map).unwrap();
+        enc.begin_vec::<String>(2).unwrap();
+        enc.encode_element(&"x".to_owned()).unwrap();
+        assert!(matches!(
+            enc.encode(&1u8),
+            Err(StreamError::UnexpectedElement(1))
+        ));
+        enc.encode_element(&"y".to_owned()).unwrap();
+        assert!(matches!(
+            enc.encode_element(&"z".to_owned()),
+            Err(StreamError::UnexpectedElement(0))
+        ));
+        let bytes = enc.into_inner().unwrap();
+
+        let mut expected = crate::encode_with_type(Vec::new(), &map);
+        expected.extend(crate::encode_with_type(
+            Vec::new(),
+            &vec!["x".to_owned(), "y".to_owned()],
+        ));
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    pub fn test_stream_decode() {
+        let values: Vec<Vec<u32>> = vec![vec![1, 2], vec![], vec![3]];
+        let mut bytes = crate::encode_with_type(Vec::new(), &values);
+        bytes.extend(crate::encode_with_type(Vec::new(), &"end".to_owned()));
+
+        let mut dec = StreamDecoder::with_type(Trickle(&bytes));
+        let elements: Result<Vec<Vec<u32>>, _> = dec.decode_vec::<Vec<u32>>().unwrap().collect();
+        assert_eq!(elements.unwrap(), values);
+        assert!(!dec.is_end().unwrap());
+        assert_eq!(dec.decode::<String>().unwrap(), "end");
+        assert!(dec.is_end().unwrap());
+        dec.check_end().unwrap();
+
+        // Truncated input
+        let mut dec = StreamDecoder::with_type(&bytes[..bytes.len() - 1]);
+        assert_eq!(dec.decode::<Vec<Vec<u32>>>().unwrap(), values);
+        assert!(matches!(
+            dec.decode::<String>(),
+            Err(StreamError::Decode(DecodeError::Underflow { .. }))
+        ));
+    }
+
+    #[test]
+    pub fn test_stream_decode_large_value() {
+        use crate::rust::cell::Cell;
+
+        let value: Vec<u32> = (0..10 * CHUNK_SIZE as u32).collect();
+        let bytes = crate::encode_with_type(Vec::new(), &value);
+
+        // The value is decoded again after each fill, which must not happen per chunk
+        let attempts = Cell::new(0);
+        let mut dec = StreamDecoder::with_type(Stall(&bytes, usize::MAX));
+        let decoded = dec
+            .read_with(|decoder| {
+                attempts.set(attempts.get() + 1);
+                <Vec<u32>>::decode(decoder)
+            })
+            .unwrap();
+        assert_eq!(decoded, value);
+        assert!(attempts.get() < 16);
+
+        // Nothing beyond the value is waited for, however the stream splits it
+        let value = vec!["abc".to_owned(); 100];
+        let bytes = crate::encode_with_type(Vec::new(), &value);
+        let mut dec = StreamDecoder::with_type(Stall(&bytes, 3));
+        assert_eq!(dec.decode::<Vec<String>>().unwrap(), value);
+    }
+
+    #[test]
+    pub fn test_stream_decode_limits() {
+        let bytes = crate::encode_with_type(Vec::new(), &vec![vec![1u8; 8], vec![2u8; 8]]);
+        let limits = DecodeLimits {
+            max_size: 16,
+            ..DecodeLimits::default()
+        };
+
+        // Too big as a whole, but not element by element
+        let mut dec = StreamDecoder::with_limits(&bytes[..], true, limits);
+        assert!(matches!(
+            dec.decode::<Vec<Vec<u8>>>(),
+            Err(StreamError::Decode(DecodeError::MaxSizeExceeded(16)))
+        ));
+        let mut dec = StreamDecoder::with_limits(&bytes[..], true, limits);
+        let elements: Vec<Vec<u8>> = dec
+            .decode_vec::<Vec<u8>>()
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(elements, vec![vec![1u8; 8], vec![2u8; 8]]);
+    }
+}
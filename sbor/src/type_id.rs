@@ -1,6 +1,8 @@
+use crate::rust::borrow::{Cow, ToOwned};
 use crate::rust::boxed::Box;
 use crate::rust::cell::RefCell;
 use crate::rust::collections::*;
+use crate::rust::marker::PhantomData;
 use crate::rust::rc::Rc;
 use crate::rust::string::String;
 use crate::rust::vec::Vec;
@@ -25,6 +27,9 @@ pub const TYPE_ENUM: u8 = 0x11;
 pub const TYPE_FIELDS_NAMED: u8 = 0x12;
 pub const TYPE_FIELDS_UNNAMED: u8 = 0x13;
 pub const TYPE_FIELDS_UNIT: u8 = 0x14;
+// enums identified by a `u16` or a string instead of a `u8`
+pub const TYPE_ENUM_U16: u8 = 0x15;
+pub const TYPE_ENUM_STRING: u8 = 0x16;
 // composite types
 pub const TYPE_OPTION: u8 = 0x20;
 pub const TYPE_BOX: u8 = 0x21;
@@ -52,6 +57,13 @@ impl TypeId for () {
     }
 }
 
+impl<T: ?Sized> TypeId for PhantomData<T> {
+    #[inline]
+    fn type_id() -> u8 {
+        TYPE_UNIT
+    }
+}
+
 impl TypeId for bool {
     #[inline]
     fn type_id() -> u8 {
@@ -203,6 +215,13 @@ impl<T: TypeId> TypeId for [T] {
     }
 }
 
+impl<T: TypeId> TypeId for &[T] {
+    #[inline]
+    fn type_id() -> u8 {
+        TYPE_VEC
+    }
+}
+
 impl<T: TypeId> TypeId for BTreeSet<T> {
     #[inline]
     fn type_id() -> u8 {
@@ -230,3 +249,10 @@ impl<K: TypeId, V: TypeId> TypeId for HashMap<K, V> {
         TYPE_HASH_MAP
     }
 }
+
+impl<'a, B: TypeId + ToOwned + ?Sized> TypeId for Cow<'a, B> {
+    #[inline]
+    fn type_id() -> u8 {
+        B::type_id()
+    }
+}
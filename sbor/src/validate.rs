@@ -0,0 +1,580 @@
+use crate::any::*;
+use crate::decode::*;
+use crate::describe::{Fields as TypeFields, Type, Variant};
+use crate::rust::fmt;
+use crate::rust::format;
+use crate::rust::string::String;
+use crate::rust::string::ToString;
+use crate::type_id::*;
+
+/// Represents an error ocurred during validation.
+#[derive(Debug, Clone)]
+pub enum ValidateError {
+    /// The payload is not valid SBOR.
+    InvalidData(DecodeError),
+
+    /// The payload does not match the schema at `path`.
+    Mismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl ValidateError {
+    /// Puts the path of this error under the given parent, e.g. `fields.x` under `args[1]`.
+    pub fn at(self, parent: &str) -> Self {
+        match self {
+            Self::Mismatch {
+                path,
+                expected,
+                actual,
+            } => Self::Mismatch {
+                path: join(parent, &path),
+                expected,
+                actual,
+            },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for ValidateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidData(e) => write!(f, "invalid data: {:?}", e),
+            Self::Mismatch {
+                path,
+                expected,
+                actual,
+            } if path.is_empty() => write!(f, "expected {}, got {}", expected, actual),
+            Self::Mismatch {
+                path,
+                expected,
+                actual,
+            } => write!(f, "{}: expected {}, got {}", path, expected, actual),
+        }
+    }
+}
+
+/// Checks that a payload, with type info included, is an instance of the given type.
+///
+/// Custom types are accepted whatever their content; use `validate_with` to check them.
+pub fn validate(payload: &[u8], ty: &Type) -> Result<(), ValidateError> {
+    validate_with(payload, ty, &|_| None)
+}
+
+/// Checks that a payload, with type info included, is an instance of the given type.
+///
+/// `custom_type_id` maps the name of a `Type::Custom` to the type id its values are encoded with,
+/// or `None` if any custom value is acceptable.
+pub fn validate_with(
+    payload: &[u8],
+    ty: &Type,
+    custom_type_id: &dyn Fn(&str) -> Option<u8>,
+) -> Result<(), ValidateError> {
+    let value = decode_any(payload).map_err(ValidateError::InvalidData)?;
+    validate_value(&value, ty, custom_type_id)
+}
+
+/// Checks that a decoded value is an instance of the given type, as `validate_with` does.
+pub fn validate_value(
+    value: &Value,
+    ty: &Type,
+    custom_type_id: &dyn Fn(&str) -> Option<u8>,
+) -> Result<(), ValidateError> {
+    Validator { custom_type_id }.validate(value, ty, "")
+}
+
+struct Validator<'a> {
+    custom_type_id: &'a dyn Fn(&str) -> Option<u8>,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(&self, value: &Value, ty: &Type, path: &str) -> Result<(), ValidateError> {
+        match (value, ty) {
+            (Value::Unit, Type::Unit)
+            | (Value::Bool(_), Type::Bool)
+            | (Value::I8(_), Type::I8)
+            | (Value::I16(_), Type::I16)
+            | (Value::I32(_), Type::I32)
+            | (Value::I64(_), Type::I64)
+            | (Value::I128(_), Type::I128)
+            | (Value::U8(_), Type::U8)
+            | (Value::U16(_), Type::U16)
+            | (Value::U32(_), Type::U32)
+            | (Value::U64(_), Type::U64)
+            | (Value::U128(_), Type::U128)
+            | (Value::String(_), Type::String) => Ok(()),
+            (Value::Struct(fields), Type::Struct { fields: tys, .. }) => {
+                self.validate_fields(fields, tys, &join(path, "fields"))
+            }
+            (Value::Enum(discriminator, fields), Type::Enum { variants, .. }) => {
+                if discriminator.enum_type_id() != enum_type_id(variants) {
+                    return Err(mismatch(path, describe_type(ty), describe_value(value)));
+                }
+                let variant = variants
+                    .iter()
+                    .enumerate()
+                    .find(|(i, v)| v.discriminator_at(*i) == *discriminator)
+                    .map(|(_, v)| v)
+                    .ok_or_else(|| {
+                        mismatch(
+                            path,
+                            format!("a variant of {}", describe_type(ty)),
+                            format!("variant {}", discriminator),
+                        )
+                    })?;
+                self.validate_fields(
+                    fields,
+                    &variant.fields,
+                    &join(&join(path, &variant.name), "fields"),
+                )
+            }
+            (Value::Option(v), Type::Option { value: ty }) => match v.as_ref() {
+                Some(v) => self.validate(v, ty, &join(path, "value")),
+                None => Ok(()),
+            },
+            (Value::Box(v), Type::Box { value: ty }) => self.validate(v, ty, &join(path, "value")),
+            (
+                Value::Array(ele_ty, elements),
+                Type::Array {
+                    element: ty,
+                    length,
+                },
+            ) => {
+                if elements.len() != *length as usize {
+                    return Err(mismatch(
+                        path,
+                        format!("{} elements", length),
+                        format!("{} elements", elements.len()),
+                    ));
+                }
+                self.validate_elements(*ele_ty, elements, ty, path)
+            }
+            (Value::Tuple(elements), Type::Tuple { elements: tys }) => {
+                if elements.len() != tys.len() {
+                    return Err(mismatch(
+                        path,
+                        format!("{} elements", tys.len()),
+                        format!("{} elements", elements.len()),
+                    ));
+                }
+                for (i, (e, ty)) in elements.iter().zip(tys).enumerate() {
+                    self.validate(e, ty, &index(path, i))?;
+                }
+                Ok(())
+            }
+            (Value::Result(v), Type::Result { okay, error }) => match v.as_ref() {
+                Ok(v) => self.validate(v, okay, &join(path, "okay")),
+                Err(v) => self.validate(v, error, &join(path, "error")),
+            },
+            (Value::Vec(ele_ty, elements), Type::Vec { element: ty })
+            | (Value::TreeSet(ele_ty, elements), Type::TreeSet { element: ty })
+            | (Value::HashSet(ele_ty, elements), Type::HashSet { element: ty }) => {
+                self.validate_elements(*ele_ty, elements, ty, path)
+            }
+            (Value::TreeMap(key_ty, value_ty, elements), Type::TreeMap { key, value })
+            | (Value::HashMap(key_ty, value_ty, elements), Type::HashMap { key, value }) => {
+                self.check_type_id(*key_ty, key, &join(path, "key"))?;
+                self.check_type_id(*value_ty, value, &join(path, "value"))?;
+                for (i, (k, v)) in elements.iter().enumerate() {
+                    self.validate(k, key, &join(&index(path, i), "key"))?;
+                    self.validate(v, value, &join(&index(path, i), "value"))?;
+                }
+                Ok(())
+            }
+            (value, Type::Custom { name, .. }) => {
+                let actual = value_type_id(value);
+                match (self.custom_type_id)(name) {
+                    Some(expected) if expected == actual => Ok(()),
+                    None if actual >= TYPE_CUSTOM_START => Ok(()),
+                    _ => Err(mismatch(path, name.to_string(), describe_value(value))),
+                }
+            }
+            (value, ty) => Err(mismatch(path, describe_type(ty), describe_value(value))),
+        }
+    }
+
+    fn validate_fields(
+        &self,
+        fields: &Fields,
+        tys: &TypeFields,
+        path: &str,
+    ) -> Result<(), ValidateError> {
+        match (fields, tys) {
+            (Fields::Named(values), TypeFields::Named { named }) => {
+                if values.len() != named.len() {
+                    return Err(mismatch(
+                        path,
+                        format!("{} named fields", named.len()),
+                        format!("{} named fields", values.len()),
+                    ));
+                }
+                for (v, (name, ty)) in values.iter().zip(named) {
+                    self.validate(v, ty, &join(path, name))?;
+                }
+                Ok(())
+            }
+            (Fields::Unnamed(values), TypeFields::Unnamed { unnamed }) => {
+                if values.len() != unnamed.len() {
+                    return Err(mismatch(
+                        path,
+                        format!("{} unnamed fields", unnamed.len()),
+                        format!("{} unnamed fields", values.len()),
+                    ));
+                }
+                for (i, (v, ty)) in values.iter().zip(unnamed).enumerate() {
+                    self.validate(v, ty, &index(path, i))?;
+                }
+                Ok(())
+            }
+            (Fields::Unit, TypeFields::Unit) => Ok(()),
+            (fields, tys) => Err(mismatch(
+                path,
+                match tys {
+                    TypeFields::Named { .. } => "named fields",
+                    TypeFields::Unnamed { .. } => "unnamed fields",
+                    TypeFields::Unit => "no fields",
+                }
+                .to_string(),
+                match fields {
+                    Fields::Named(_) => "named fields",
+                    Fields::Unnamed(_) => "unnamed fields",
+                    Fields::Unit => "no fields",
+                }
+                .to_string(),
+            )),
+        }
+    }
+
+    fn validate_elements(
+        &self,
+        ele_ty: u8,
+        elements: &[Value],
+        ty: &Type,
+        path: &str,
+    ) -> Result<(), ValidateError> {
+        // The element type is checked up front, so that empty collections are checked too
+        self.check_type_id(ele_ty, ty, &join(path, "element"))?;
+        for (i, e) in elements.iter().enumerate() {
+            self.validate(e, ty, &index(path, i))?;
+        }
+        Ok(())
+    }
+
+    fn check_type_id(&self, actual: u8, ty: &Type, path: &str) -> Result<(), ValidateError> {
+        let ok = match self.type_id(ty) {
+            Some(expected) => expected == actual,
+            None => actual >= TYPE_CUSTOM_START,
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(mismatch(path, describe_type(ty), describe_type_id(actual)))
+        }
+    }
+
+    /// Returns the type id of a type, or `None` for a custom type of unknown encoding.
+    fn type_id(&self, ty: &Type) -> Option<u8> {
+        match ty {
+            Type::Custom { name, .. } => (self.custom_type_id)(name),
+            ty => type_id(ty),
+        }
+    }
+}
+
+fn mismatch(path: &str, expected: String, actual: String) -> ValidateError {
+    ValidateError::Mismatch {
+        path: path.to_string(),
+        expected,
+        actual,
+    }
+}
+
+pub(crate) fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else if name.is_empty() {
+        path.to_string()
+    } else if name.starts_with('[') {
+        format!("{}{}", path, name)
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+pub(crate) fn index(path: &str, i: usize) -> String {
+    format!("{}[{}]", path, i)
+}
+
+pub(crate) fn type_id(ty: &Type) -> Option<u8> {
+    Some(match ty {
+        Type::Unit => TYPE_UNIT,
+        Type::Bool => TYPE_BOOL,
+        Type::I8 => TYPE_I8,
+        Type::I16 => TYPE_I16,
+        Type::I32 => TYPE_I32,
+        Type::I64 => TYPE_I64,
+        Type::I128 => TYPE_I128,
+        Type::U8 => TYPE_U8,
+        Type::U16 => TYPE_U16,
+        Type::U32 => TYPE_U32,
+        Type::U64 => TYPE_U64,
+        Type::U128 => TYPE_U128,
+        Type::String => TYPE_STRING,
+        Type::Struct { .. } => TYPE_STRUCT,
+        Type::Enum { variants, .. } => enum_type_id(variants),
+        Type::Option { .. } => TYPE_OPTION,
+        Type::Box { .. } => TYPE_BOX,
+        Type::Array { .. } => TYPE_ARRAY,
+        Type::Tuple { .. } => TYPE_TUPLE,
+        Type::Result { .. } => TYPE_RESULT,
+        Type::Vec { .. } => TYPE_VEC,
+        Type::TreeSet { .. } => TYPE_TREE_SET,
+        Type::TreeMap { .. } => TYPE_TREE_MAP,
+        Type::HashSet { .. } => TYPE_HASH_SET,
+        Type::HashMap { .. } => TYPE_HASH_MAP,
+        Type::Custom { .. } => return None,
+    })
+}
+
+/// Returns the type id of an enum, which depends on the kind of its discriminators.
+pub(crate) fn enum_type_id(variants: &[Variant]) -> u8 {
+    variants
+        .first()
+        .map_or(TYPE_ENUM, |v| v.discriminator_at(0).enum_type_id())
+}
+
+pub(crate) fn value_type_id(value: &Value) -> u8 {
+    match value {
+        Value::Unit => TYPE_UNIT,
+        Value::Bool(_) => TYPE_BOOL,
+        Value::I8(_) => TYPE_I8,
+        Value::I16(_) => TYPE_I16,
+        Value::I32(_) => TYPE_I32,
+        Value::I64(_) => TYPE_I64,
+        Value::I128(_) => TYPE_I128,
+        Value::U8(_) => TYPE_U8,
+        Value::U16(_) => TYPE_U16,
+        Value::U32(_) => TYPE_U32,
+        Value::U64(_) => TYPE_U64,
+        Value::U128(_) => TYPE_U128,
+        Value::String(_) => TYPE_STRING,
+        Value::Struct(_) => TYPE_STRUCT,
+        Value::Enum(discriminator, _) => discriminator.enum_type_id(),
+        Value::Option(_) => TYPE_OPTION,
+        Value::Box(_) => TYPE_BOX,
+        Value::Array(_, _) => TYPE_ARRAY,
+        Value::Tuple(_) => TYPE_TUPLE,
+        Value::Result(_) => TYPE_RESULT,
+        Value::Vec(_, _) => TYPE_VEC,
+        Value::TreeSet(_, _) => TYPE_TREE_SET,
+        Value::TreeMap(_, _, _) => TYPE_TREE_MAP,
+        Value::HashSet(_, _) => TYPE_HASH_SET,
+        Value::HashMap(_, _, _) => TYPE_HASH_MAP,
+        Value::Custom(ty, _) => *ty,
+    }
+}
+
+pub(crate) fn describe_type(ty: &Type) -> String {
+    match ty {
+        Type::Struct { name, .. } | Type::Enum { name, .. } | Type::Custom { name, .. } => {
+            name.to_string()
+        }
+        ty => describe_type_id(type_id(ty).unwrap_or(TYPE_CUSTOM_START)),
+    }
+}
+
+pub(crate) fn describe_value(value: &Value) -> String {
+    describe_type_id(value_type_id(value))
+}
+
+pub(crate) fn describe_type_id(ty: u8) -> String {
+    match ty {
+        TYPE_UNIT => "Unit",
+        TYPE_BOOL => "Bool",
+        TYPE_I8 => "I8",
+        TYPE_I16 => "I16",
+        TYPE_I32 => "I32",
+        TYPE_I64 => "I64",
+        TYPE_I128 => "I128",
+        TYPE_U8 => "U8",
+        TYPE_U16 => "U16",
+        TYPE_U32 => "U32",
+        TYPE_U64 => "U64",
+        TYPE_U128 => "U128",
+        TYPE_STRING => "String",
+        TYPE_STRUCT => "Struct",
+        TYPE_ENUM => "Enum",
+        TYPE_ENUM_U16 => "EnumU16",
+        TYPE_ENUM_STRING => "EnumString",
+        TYPE_OPTION => "Option",
+        TYPE_BOX => "Box",
+        TYPE_ARRAY => "Array",
+        TYPE_TUPLE => "Tuple",
+        TYPE_RESULT => "Result",
+        TYPE_VEC => "Vec",
+        TYPE_TREE_SET => "TreeSet",
+        TYPE_TREE_MAP => "TreeMap",
+        TYPE_HASH_SET => "HashSet",
+        TYPE_HASH_MAP => "HashMap",
+        ty => return format!("Custom({:#04x})", ty),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::describe::Discriminator;
+    use crate::rust::string::String;
+    use crate::rust::string::ToString;
+    use crate::rust::vec;
+    use crate::rust::vec::Vec;
+    use crate::*;
+
+    use super::*;
+
+    #[derive(TypeId, Encode, Describe)]
+    struct Order {
+        price: u128,
+        size: u32,
+    }
+
+    #[derive(TypeId, Encode)]
+    struct BadOrder {
+        price: String,
+        size: u32,
+    }
+
+    #[derive(TypeId, Encode, Describe)]
+    enum Side {
+        Buy { order: Order },
+        Sell(u32),
+    }
+
+    fn assert_mismatch(result: Result<(), ValidateError>, message: &str) {
+        match result {
+            Err(e @ ValidateError::Mismatch { .. }) => assert_eq!(e.to_string(), message),
+            other => panic!("Expected a mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn test_valid() {
+        let bytes = encode_with_type(
+            Vec::new(),
+            &(
+                Order { price: 1, size: 2 },
+                vec![
+                    Side::Buy {
+                        order: Order { price: 3, size: 4 },
+                    },
+                    Side::Sell(1),
+                ],
+                Some(String::from("a")),
+            ),
+        );
+        let ty = <(Order, Vec<Side>, Option<String>)>::describe();
+        assert!(validate(&bytes, &ty).is_ok());
+    }
+
+    #[test]
+    pub fn test_mismatch_path() {
+        let bytes = encode_with_type(
+            Vec::new(),
+            &BadOrder {
+                price: String::from("1"),
+                size: 2,
+            },
+        );
+        assert_mismatch(
+            validate(&bytes, &Order::describe()).map_err(|e| e.at("args[1]")),
+            "args[1].fields.price: expected U128, got String",
+        );
+    }
+
+    #[test]
+    pub fn test_enum() {
+        let bytes = encode_with_type(Vec::new(), &Side::Sell(1));
+        assert!(validate(&bytes, &Side::describe()).is_ok());
+
+        let bytes = encode_with_type(Vec::new(), &(1u32, vec![2u8]));
+        assert_mismatch(
+            validate(&bytes, &<(u32, Vec<Side>)>::describe()),
+            "[1].element: expected Side, got U8",
+        );
+
+        let bytes = encode_any_with_type(&Value::Enum(
+            Discriminator::U8(0),
+            Fields::Named(vec![Value::Struct(Fields::Unit)]),
+        ));
+        assert_mismatch(
+            validate(&bytes, &Side::describe()),
+            "Buy.fields.order.fields: expected named fields, got no fields",
+        );
+
+        let bytes = encode_any_with_type(&Value::Enum(Discriminator::U8(2), Fields::Unit));
+        assert_mismatch(
+            validate(&bytes, &Side::describe()),
+            "expected a variant of Side, got variant 2",
+        );
+    }
+
+    #[test]
+    pub fn test_lengths() {
+        let bytes = encode_with_type(Vec::new(), &[1u8, 2u8]);
+        assert_mismatch(
+            validate(&bytes, &<[u8; 3]>::describe()),
+            "expected 3 elements, got 2 elements",
+        );
+
+        let bytes = encode_with_type(Vec::new(), &(1u8, 2u8));
+        assert_mismatch(
+            validate(&bytes, &<(u8, u8, u8)>::describe()),
+            "expected 3 elements, got 2 elements",
+        );
+    }
+
+    #[test]
+    pub fn test_empty_collections() {
+        let bytes = encode_with_type(Vec::new(), &Vec::<u8>::new());
+        assert!(validate(&bytes, &Vec::<u8>::describe()).is_ok());
+        assert_mismatch(
+            validate(&bytes, &Vec::<String>::describe()),
+            "element: expected String, got U8",
+        );
+    }
+
+    #[test]
+    pub fn test_custom() {
+        let ty = Type::Custom {
+            name: "Amount".to_string(),
+            generics: vec![],
+        };
+        let bytes: Vec<u8> = vec![0x80, 0x02, 0x00, 0x00, 0x00, 0x01, 0x02];
+        assert!(validate(&bytes, &ty).is_ok());
+        assert!(validate_with(&bytes, &ty, &|_| Some(0x80)).is_ok());
+        assert_mismatch(
+            validate_with(&bytes, &ty, &|_| Some(0x81)),
+            "expected Amount, got Custom(0x80)",
+        );
+
+        let bytes = encode_with_type(Vec::new(), &1u32);
+        assert_mismatch(validate(&bytes, &ty), "expected Amount, got U32");
+    }
+
+    #[test]
+    pub fn test_invalid_data() {
+        assert!(matches!(
+            validate(&[TYPE_U8], &Type::U8),
+            Err(ValidateError::InvalidData(_))
+        ));
+    }
+
+    fn encode_any_with_type(value: &Value) -> Vec<u8> {
+        let mut enc = Encoder::with_type(Vec::new());
+        encode_any(None, value, &mut enc);
+        enc.into()
+    }
+}
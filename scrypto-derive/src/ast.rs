@@ -1,5 +1,6 @@
 use syn::parse::{Parse, ParseStream};
-use syn::{ItemImpl, ItemStruct, Result};
+use syn::punctuated::Punctuated;
+use syn::{ItemImpl, ItemStruct, ItemTrait, LitStr, Path, Result, Token};
 
 /// Represents the AST of blueprint.
 pub struct Blueprint {
@@ -14,4 +15,47 @@ impl Parse for Blueprint {
             implementation: input.parse()?,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Represents the AST of an external blueprint.
+pub struct ExternalBlueprint {
+    pub package: LitStr,
+    pub interface: ItemTrait,
+}
+
+impl Parse for ExternalBlueprint {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let package = input.parse()?;
+        input.parse::<Token![,]>()?;
+        Ok(Self {
+            package,
+            interface: input.parse()?,
+        })
+    }
+}
+
+/// Represents the AST of an external component.
+pub struct ExternalComponent {
+    pub interface: ItemTrait,
+}
+
+impl Parse for ExternalComponent {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            interface: input.parse()?,
+        })
+    }
+}
+
+/// Represents the AST of auth attribute.
+pub struct Auth {
+    pub allowed: Punctuated<Path, Token![,]>,
+}
+
+impl Parse for Auth {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            allowed: input.parse_terminated(Path::parse)?,
+        })
+    }
+}
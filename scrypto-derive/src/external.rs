@@ -0,0 +1,326 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::*;
+
+use crate::ast;
+
+macro_rules! trace {
+    ($($arg:expr),*) => {{
+        #[cfg(feature = "trace")]
+        println!($($arg),*);
+    }};
+}
+
+pub fn handle_external_blueprint(input: TokenStream) -> Result<TokenStream> {
+    trace!("Started processing external_blueprint macro");
+
+    let external = parse2::<ast::ExternalBlueprint>(input)?;
+    let package = &external.package;
+    let ident = &external.interface.ident;
+    let name = ident.to_string();
+    trace!("Blueprint name: {}", name);
+
+    let mut abis = Vec::<TokenStream>::new();
+    let mut stubs = Vec::<ItemFn>::new();
+    for sig in get_signatures(&external.interface)? {
+        if let Some(r) = sig.receiver() {
+            return Err(Error::new(
+                r.span(),
+                "Methods are not supported in external blueprints. Try external_component! instead.",
+            ));
+        }
+
+        let func_name = sig.ident.to_string();
+        let func_ident = &sig.ident;
+        let (func_args, func_types) = get_args(sig);
+        let func_output = &sig.output;
+        let output = describe_output(&sig.output);
+
+        abis.push(quote! {
+            ::scrypto::abi::Function {
+                name: #func_name.to_owned(),
+                inputs: vec![#(<#func_types>::describe()),*],
+                output: #output,
+            }
+        });
+        stubs.push(parse_quote! {
+            pub fn #func_ident(#(#func_args: #func_types),*) #func_output {
+                Self::__check_abi();
+                let rtn = ::scrypto::core::call_function(
+                    Self::__package(),
+                    #name,
+                    #func_name,
+                    ::scrypto::args!(#(#func_args),*)
+                );
+                ::scrypto::utils::scrypto_unwrap(::scrypto::buffer::scrypto_decode(&rtn))
+            }
+        });
+    }
+
+    let output = quote! {
+        pub struct #ident;
+
+        impl #ident {
+            fn __package() -> ::scrypto::types::Address {
+                ::scrypto::utils::scrypto_unwrap(
+                    <::scrypto::types::Address as ::scrypto::rust::str::FromStr>::from_str(#package)
+                )
+            }
+
+            fn __check_abi() {
+                use ::sbor::Describe;
+                use ::scrypto::rust::borrow::ToOwned;
+                use ::scrypto::rust::vec;
+                use ::scrypto::rust::sync::atomic::{AtomicBool, Ordering};
+
+                // a blueprint has a single package address, so it only needs checking once
+                static CHECKED: AtomicBool = AtomicBool::new(false);
+                if !CHECKED.swap(true, Ordering::Relaxed) {
+                    ::scrypto::core::check_blueprint_abi(
+                        Self::__package(),
+                        #name,
+                        &[#(#abis),*],
+                        &[],
+                    );
+                }
+            }
+
+            #(#stubs)*
+        }
+    };
+    trace!("Finished processing external_blueprint macro");
+
+    #[cfg(feature = "trace")]
+    crate::utils::print_compiled_code("external_blueprint!", &output);
+
+    Ok(output)
+}
+
+pub fn handle_external_component(input: TokenStream) -> Result<TokenStream> {
+    trace!("Started processing external_component macro");
+
+    let external = parse2::<ast::ExternalComponent>(input)?;
+    let ident = &external.interface.ident;
+
+    let mut abis = Vec::<TokenStream>::new();
+    let mut stubs = Vec::<ItemFn>::new();
+    for sig in get_signatures(&external.interface)? {
+        let mutability = match sig.receiver() {
+            Some(FnArg::Receiver(r)) => {
+                if r.reference.is_none() {
+                    return Err(Error::new(
+                        r.span(),
+                        "Function input `self` is not supported. Try replacing it with &self.",
+                    ));
+                }
+                if r.mutability.is_some() {
+                    quote! { ::scrypto::abi::Mutability::Mutable }
+                } else {
+                    quote! { ::scrypto::abi::Mutability::Immutable }
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    sig.span(),
+                    "Functions are not supported in external components. Try external_blueprint! instead.",
+                ));
+            }
+        };
+
+        let method_name = sig.ident.to_string();
+        let method_ident = &sig.ident;
+        let (method_args, method_types) = get_args(sig);
+        let method_output = &sig.output;
+        let output = describe_output(&sig.output);
+
+        abis.push(quote! {
+            ::scrypto::abi::Method {
+                name: #method_name.to_owned(),
+                mutability: #mutability,
+                inputs: vec![#(<#method_types>::describe()),*],
+                output: #output,
+            }
+        });
+        stubs.push(parse_quote! {
+            pub fn #method_ident(&self #(, #method_args: #method_types)*) #method_output {
+                let rtn = ::scrypto::core::call_method(
+                    self.address,
+                    #method_name,
+                    ::scrypto::args!(#(#method_args),*)
+                );
+                ::scrypto::utils::scrypto_unwrap(::scrypto::buffer::scrypto_decode(&rtn))
+            }
+        });
+    }
+
+    let output = quote! {
+        #[derive(::sbor::TypeId, ::sbor::Encode)]
+        pub struct #ident {
+            address: ::scrypto::types::Address,
+        }
+
+        impl #ident {
+            /// Wraps a component address, checking the ABI of the component it refers to.
+            fn __checked(address: ::scrypto::types::Address) -> Self {
+                use ::sbor::Describe;
+                use ::scrypto::rust::borrow::ToOwned;
+                use ::scrypto::rust::vec;
+
+                let blueprint = ::scrypto::core::Component::from(address).blueprint();
+                ::scrypto::core::check_blueprint_abi(
+                    blueprint.package().address(),
+                    blueprint.name(),
+                    &[],
+                    &[#(#abis),*],
+                );
+                Self {
+                    address
+                }
+            }
+
+            #(#stubs)*
+        }
+
+        impl<'de> ::sbor::Decode<'de> for #ident {
+            fn decode_value(decoder: &mut ::sbor::Decoder<'de>) -> Result<Self, ::sbor::DecodeError> {
+                #[derive(::sbor::TypeId, ::sbor::Decode)]
+                struct Unchecked {
+                    address: ::scrypto::types::Address,
+                }
+
+                let unchecked = <Unchecked as ::sbor::Decode<'de>>::decode_value(decoder)?;
+                Ok(Self::__checked(unchecked.address))
+            }
+        }
+
+        impl From<::scrypto::types::Address> for #ident {
+            fn from(address: ::scrypto::types::Address) -> Self {
+                Self::__checked(address)
+            }
+        }
+
+        impl From<#ident> for ::scrypto::types::Address {
+            fn from(a: #ident) -> ::scrypto::types::Address {
+                a.address
+            }
+        }
+
+        impl From<::scrypto::core::Component> for #ident {
+            fn from(component: ::scrypto::core::Component) -> Self {
+                Self::__checked(component.into())
+            }
+        }
+
+        impl From<#ident> for ::scrypto::core::Component {
+            fn from(a: #ident) -> ::scrypto::core::Component {
+                a.address.into()
+            }
+        }
+    };
+    trace!("Finished processing external_component macro");
+
+    #[cfg(feature = "trace")]
+    crate::utils::print_compiled_code("external_component!", &output);
+
+    Ok(output)
+}
+
+fn get_signatures(interface: &ItemTrait) -> Result<Vec<&Signature>> {
+    let mut signatures = vec![];
+    for item in &interface.items {
+        match item {
+            TraitItem::Method(m) => {
+                if m.default.is_some() {
+                    return Err(Error::new(
+                        m.span(),
+                        "Default implementations are not supported!",
+                    ));
+                }
+                signatures.push(&m.sig);
+            }
+            _ => {
+                return Err(Error::new(
+                    item.span(),
+                    "Non-method interface items are not supported!",
+                ));
+            }
+        }
+    }
+    Ok(signatures)
+}
+
+fn get_args(sig: &Signature) -> (Vec<Ident>, Vec<Type>) {
+    let mut args = Vec::<Ident>::new();
+    let mut types = Vec::<Type>::new();
+    for (i, input) in sig.inputs.iter().enumerate() {
+        if let FnArg::Typed(t) = input {
+            args.push(format_ident!("arg{}", i));
+            types.push((*t.ty).clone());
+        }
+    }
+    (args, types)
+}
+
+fn describe_output(output: &ReturnType) -> TokenStream {
+    match output {
+        ReturnType::Default => quote! {
+            ::sbor::describe::Type::Unit
+        },
+        ReturnType::Type(_, t) => quote! {
+            <#t>::describe()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::TokenStream;
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_external_blueprint() {
+        let input = TokenStream::from_str(
+            r#""056967d3d49213394892980af59be76e9b3e7cc4cb78237460d0c7", trait Simple { fn new() -> Component; fn add(a: u32, b: u32) -> u32; }"#,
+        )
+        .unwrap();
+        let output = handle_external_blueprint(input).unwrap().to_string();
+
+        assert!(output.contains("pub struct Simple ;"));
+        assert!(output.contains("pub fn new () -> Component"));
+        assert!(output.contains("pub fn add (arg0 : u32 , arg1 : u32) -> u32"));
+        assert!(output.contains("check_blueprint_abi"));
+    }
+
+    #[test]
+    fn test_external_blueprint_rejects_methods() {
+        let input = TokenStream::from_str(
+            r#""056967d3d49213394892980af59be76e9b3e7cc4cb78237460d0c7", trait Simple { fn get(&self) -> u32; }"#,
+        )
+        .unwrap();
+        assert!(handle_external_blueprint(input).is_err());
+    }
+
+    #[test]
+    fn test_external_component() {
+        let input =
+            TokenStream::from_str("trait Counter { fn get(&self) -> u32; fn incr(&mut self); }")
+                .unwrap();
+        let output = handle_external_component(input).unwrap().to_string();
+
+        assert!(
+            output.contains("pub struct Counter { address : :: scrypto :: types :: Address , }")
+        );
+        assert!(output.contains("pub fn get (& self) -> u32"));
+        assert!(output.contains("pub fn incr (& self)"));
+        assert!(output.contains(":: scrypto :: abi :: Mutability :: Mutable"));
+    }
+
+    #[test]
+    fn test_external_component_rejects_functions() {
+        let input = TokenStream::from_str("trait Counter { fn new() -> Component; }").unwrap();
+        assert!(handle_external_component(input).is_err());
+    }
+}
@@ -167,7 +167,7 @@ fn get_native_type(ty: &des::Type) -> Result<(Type, Vec<Item>)> {
         des::Type::U128 => parse_quote! { u128 },
         des::Type::String => parse_quote! { String },
         // struct & enum
-        des::Type::Struct { name, fields } => {
+        des::Type::Struct { name, fields, .. } => {
             let ident = format_ident!("{}", name);
 
             match fields {
@@ -211,12 +211,20 @@ fn get_native_type(ty: &des::Type) -> Result<(Type, Vec<Item>)> {
 
             parse_quote! { #ident }
         }
-        des::Type::Enum { name, variants } => {
+        des::Type::Enum { name, variants, .. } => {
             let ident = format_ident!("{}", name);
             let mut native_variants = Vec::<Variant>::new();
 
             for variant in variants {
                 let v_ident = format_ident!("{}", variant.name);
+                let discriminator = variant.discriminator.as_ref().map(|d| {
+                    let d = match d {
+                        des::Discriminator::U8(d) => quote! { #d },
+                        des::Discriminator::U16(d) => quote! { #d },
+                        des::Discriminator::String(d) => quote! { #d },
+                    };
+                    quote! { #[sbor(discriminator = #d)] }
+                });
 
                 match &variant.fields {
                     des::Fields::Named { named } => {
@@ -229,6 +237,7 @@ fn get_native_type(ty: &des::Type) -> Result<(Type, Vec<Item>)> {
                             structs.extend(new_structs);
                         }
                         native_variants.push(parse_quote! {
+                            #discriminator
                             #v_ident {
                                 #(#names: #types),*
                             }
@@ -242,11 +251,13 @@ fn get_native_type(ty: &des::Type) -> Result<(Type, Vec<Item>)> {
                             structs.extend(new_structs);
                         }
                         native_variants.push(parse_quote! {
+                            #discriminator
                             #v_ident ( #(#types),* )
                         });
                     }
                     des::Fields::Unit => {
                         native_variants.push(parse_quote! {
+                            #discriminator
                             #v_ident
                         });
                     }
@@ -1,6 +1,7 @@
 mod ast;
 mod auth;
 mod blueprint;
+mod external;
 mod import;
 mod utils;
 
@@ -98,6 +99,53 @@ pub fn import(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Declares a blueprint in another package through a trait-like interface.
+///
+/// This macro will generate type-safe stubs for calling the functions of the blueprint.
+/// On first call, the declared signatures are checked against the ABI exported by the
+/// package, and the transaction is aborted on any mismatch.
+///
+/// # Example
+/// ```ignore
+/// use scrypto::prelude::*;
+///
+/// external_blueprint! {
+///     "01a405d3129b61e86c51c3168d553d2ffd7a3f0bd2f66b5a3e9876",
+///     trait GumballMachine {
+///         fn new() -> Component;
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn external_blueprint(input: TokenStream) -> TokenStream {
+    external::handle_external_blueprint(proc_macro2::TokenStream::from(input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Declares a component of another blueprint through a trait-like interface.
+///
+/// This macro will generate type-safe stubs for calling the methods of the component.
+/// On first call, the declared signatures are checked against the ABI exported by the
+/// component's blueprint, and the transaction is aborted on any mismatch.
+///
+/// # Example
+/// ```ignore
+/// use scrypto::prelude::*;
+///
+/// external_component! {
+///     trait GumballMachineComponent {
+///         fn get_gumball(&mut self, payment: Bucket) -> Bucket;
+///     }
+/// }
+/// ```
+#[proc_macro]
+pub fn external_component(input: TokenStream) -> TokenStream {
+    external::handle_external_component(proc_macro2::TokenStream::from(input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 #[proc_macro_attribute]
 pub fn auth(attr: TokenStream, item: TokenStream) -> TokenStream {
     auth::handle_auth(
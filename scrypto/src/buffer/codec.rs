@@ -1,5 +1,8 @@
+use sbor::describe::Type;
+use sbor::type_id::*;
 use sbor::*;
 
+use crate::buffer::*;
 use crate::rust::vec::Vec;
 
 /// Encodes a data structure into byte array.
@@ -24,15 +27,40 @@ pub fn scrypto_encode_for_kernel<T: Encode + ?Sized>(v: &T) -> Vec<u8> {
 }
 
 /// Decodes an instance of `T` from a slice.
-pub fn scrypto_decode<T: Decode>(buf: &[u8]) -> Result<T, DecodeError> {
+pub fn scrypto_decode<'de, T: Decode<'de>>(buf: &'de [u8]) -> Result<T, DecodeError> {
     sbor::decode_with_type(buf)
 }
 
+/// Checks that a payload is an instance of the given type, including Scrypto types.
+pub fn scrypto_validate(buf: &[u8], ty: &Type) -> Result<(), ValidateError> {
+    sbor::validate_with(buf, ty, &scrypto_type_id)
+}
+
+/// Returns the type id that values of a Scrypto type are encoded with.
+pub fn scrypto_type_id(name: &str) -> Option<u8> {
+    match name {
+        SCRYPTO_NAME_AMOUNT => Some(SCRYPTO_TYPE_AMOUNT),
+        SCRYPTO_NAME_ADDRESS
+        | SCRYPTO_NAME_ACCOUNT
+        | SCRYPTO_NAME_PACKAGE
+        | SCRYPTO_NAME_COMPONENT
+        | SCRYPTO_NAME_RESOURCE_DEF => Some(SCRYPTO_TYPE_ADDRESS),
+        SCRYPTO_NAME_H256 => Some(SCRYPTO_TYPE_H256),
+        SCRYPTO_NAME_BID | SCRYPTO_NAME_BUCKET => Some(SCRYPTO_TYPE_BID),
+        SCRYPTO_NAME_RID | SCRYPTO_NAME_BUCKET_REF => Some(SCRYPTO_TYPE_RID),
+        SCRYPTO_NAME_MID | SCRYPTO_NAME_LAZY_MAP => Some(SCRYPTO_TYPE_MID),
+        SCRYPTO_NAME_VID | SCRYPTO_NAME_VAULT => Some(SCRYPTO_TYPE_VID),
+        SCRYPTO_NAME_BLUEPRINT => Some(TYPE_TUPLE),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sbor::*;
 
     use crate::buffer::*;
+    use crate::core::*;
     use crate::kernel::*;
     use crate::resource::*;
     use crate::rust::borrow::ToOwned;
@@ -58,6 +86,13 @@ mod tests {
         assert_eq!(vec![8, 0, 0, 0, 12, 3, 0, 0, 0, 97, 98, 99], encoded);
     }
 
+    #[test]
+    fn test_validate() {
+        let encoded = scrypto_encode(&(Bid(1), Address::Component([1u8; 26])));
+        assert!(scrypto_validate(&encoded, &<(Bucket, Component)>::describe()).is_ok());
+        assert!(scrypto_validate(&encoded, &<(Bucket, Vault)>::describe()).is_err());
+    }
+
     #[derive(TypeId, Encode, Decode)]
     struct ComponentTest {
         resource_def: Address,
@@ -0,0 +1,127 @@
+use sbor::json::*;
+
+use crate::buffer::*;
+use crate::rust::borrow::ToOwned;
+use crate::rust::convert::TryFrom;
+use crate::rust::str::FromStr;
+use crate::rust::string::String;
+use crate::rust::string::ToString;
+use crate::rust::vec::Vec;
+use crate::types::*;
+
+/// Renders Scrypto types readably in JSON.
+///
+/// Amounts, addresses and hashes are written as strings, bucket and reference ids as numbers, and
+/// lazy map and vault ids as `{"hash": "<hex>", "index": 0}`.
+pub struct ScryptoJson;
+
+impl CustomJson for ScryptoJson {
+    fn type_name(&self, ty: u8) -> Option<String> {
+        let name = match ty {
+            SCRYPTO_TYPE_AMOUNT => "Amount",
+            SCRYPTO_TYPE_ADDRESS => "Address",
+            SCRYPTO_TYPE_H256 => "H256",
+            SCRYPTO_TYPE_BID => "Bid",
+            SCRYPTO_TYPE_RID => "Rid",
+            SCRYPTO_TYPE_MID => "Mid",
+            SCRYPTO_TYPE_VID => "Vid",
+            _ => return None,
+        };
+        Some(name.to_owned())
+    }
+
+    fn type_id(&self, name: &str) -> Option<u8> {
+        match name {
+            "Amount" => Some(SCRYPTO_TYPE_AMOUNT),
+            "Address" => Some(SCRYPTO_TYPE_ADDRESS),
+            "H256" => Some(SCRYPTO_TYPE_H256),
+            "Bid" => Some(SCRYPTO_TYPE_BID),
+            "Rid" => Some(SCRYPTO_TYPE_RID),
+            "Mid" => Some(SCRYPTO_TYPE_MID),
+            "Vid" => Some(SCRYPTO_TYPE_VID),
+            name => scrypto_type_id(name),
+        }
+    }
+
+    fn render(&self, ty: u8, data: &[u8]) -> Option<JsonValue> {
+        match ty {
+            SCRYPTO_TYPE_AMOUNT => Some(Amount::try_from(data).ok()?.to_string().into()),
+            SCRYPTO_TYPE_ADDRESS => Some(Address::try_from(data).ok()?.to_string().into()),
+            SCRYPTO_TYPE_H256 => Some(H256::try_from(data).ok()?.to_string().into()),
+            SCRYPTO_TYPE_BID => Some(Bid::try_from(data).ok()?.0.into()),
+            SCRYPTO_TYPE_RID => Some(Rid::try_from(data).ok()?.0.into()),
+            SCRYPTO_TYPE_MID => {
+                let mid = Mid::try_from(data).ok()?;
+                Some(render_id(mid.0, mid.1))
+            }
+            SCRYPTO_TYPE_VID => {
+                let vid = Vid::try_from(data).ok()?;
+                Some(render_id(vid.0, vid.1))
+            }
+            _ => None,
+        }
+    }
+
+    fn parse(&self, ty: u8, json: &JsonValue) -> Option<Vec<u8>> {
+        match ty {
+            SCRYPTO_TYPE_AMOUNT => Some(Amount::from_str(json.as_str()?).ok()?.to_vec()),
+            SCRYPTO_TYPE_ADDRESS => Some(Address::from_str(json.as_str()?).ok()?.to_vec()),
+            SCRYPTO_TYPE_H256 => Some(H256::from_str(json.as_str()?).ok()?.to_vec()),
+            SCRYPTO_TYPE_BID => Some(Bid(u32::try_from(json.as_u64()?).ok()?).to_vec()),
+            SCRYPTO_TYPE_RID => Some(Rid(u32::try_from(json.as_u64()?).ok()?).to_vec()),
+            SCRYPTO_TYPE_MID => {
+                let (hash, index) = parse_id(json)?;
+                Some(Mid(hash, index).to_vec())
+            }
+            SCRYPTO_TYPE_VID => {
+                let (hash, index) = parse_id(json)?;
+                Some(Vid(hash, index).to_vec())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn render_id(hash: H256, index: u32) -> JsonValue {
+    let mut json = JsonMap::new();
+    json.insert("hash".to_owned(), hash.to_string().into());
+    json.insert("index".to_owned(), index.into());
+    JsonValue::Object(json)
+}
+
+fn parse_id(json: &JsonValue) -> Option<(H256, u32)> {
+    let object = json.as_object()?;
+    let hash = H256::from_str(object.get("hash")?.as_str()?).ok()?;
+    let index = u32::try_from(object.get("index")?.as_u64()?).ok()?;
+    Some((hash, index))
+}
+
+#[cfg(test)]
+mod tests {
+    use sbor::describe::Describe;
+    use sbor::json::*;
+
+    use crate::buffer::*;
+    use crate::resource::*;
+    use crate::types::*;
+
+    #[test]
+    fn test_json() {
+        let payload = scrypto_encode(&(Amount::from(5u32), Bid(1), Mid(H256([2u8; 32]), 3)));
+        let value = sbor::decode_any(&payload).unwrap();
+
+        let json = to_json(&value, &ScryptoJson);
+        assert_eq!(json["elements"][0]["type"], "Amount");
+        assert_eq!(json["elements"][0]["value"], "5");
+        assert_eq!(from_json(&json, &ScryptoJson).unwrap(), value);
+
+        let ty = <(Amount, Bucket, Mid)>::describe();
+        let json = to_json_with_schema(&value, &ty, &ScryptoJson).unwrap();
+        assert_eq!(json[1], 1);
+        assert_eq!(json[2]["index"], 3);
+        assert_eq!(
+            from_json_with_schema(&json, &ty, &ScryptoJson).unwrap(),
+            value
+        );
+    }
+}
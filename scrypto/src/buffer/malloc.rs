@@ -1,3 +1,4 @@
+use crate::rust::boxed::Box;
 use crate::rust::mem::forget;
 use crate::rust::ptr::copy;
 use crate::rust::vec::Vec;
@@ -19,10 +20,9 @@ pub unsafe extern "C" fn scrypto_alloc(len: u32) -> *mut u8 {
 /// Wraps a byte array into a pointer.
 ///
 /// This function assumes the input byte array has the layout specified by `scrypto_alloc`.
-pub fn scrypto_wrap(mut buf: Vec<u8>) -> *mut u8 {
-    let ptr = buf.as_mut_ptr();
-    forget(buf);
-    ptr
+pub fn scrypto_wrap(buf: Vec<u8>) -> *mut u8 {
+    // drop spare capacity, so the buffer can be released as `len + 4` bytes
+    Box::into_raw(buf.into_boxed_slice()) as *mut u8
 }
 
 /// Consumes a memory chunk.
@@ -1,7 +1,11 @@
 mod codec;
 mod constants;
+#[cfg(any(feature = "serde_std", feature = "serde_alloc"))]
+mod json;
 mod malloc;
 
 pub use codec::*;
 pub use constants::*;
+#[cfg(any(feature = "serde_std", feature = "serde_alloc"))]
+pub use json::*;
 pub use malloc::*;
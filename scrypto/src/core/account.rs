@@ -68,8 +68,8 @@ impl Encode for Account {
     }
 }
 
-impl Decode for Account {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for Account {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Address::decode_value(decoder).map(Into::into)
     }
 }
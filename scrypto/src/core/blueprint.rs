@@ -40,7 +40,7 @@ impl Blueprint {
         &self.name
     }
 
-    pub fn call<T: Decode>(&self, function: &str, args: Vec<Vec<u8>>) -> T {
+    pub fn call<T: DecodeOwned>(&self, function: &str, args: Vec<Vec<u8>>) -> T {
         let output = call_function(self.package.address(), self.name(), function, args);
 
         scrypto_unwrap(scrypto_decode(&output))
@@ -63,8 +63,8 @@ impl Encode for Blueprint {
     }
 }
 
-impl Decode for Blueprint {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for Blueprint {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         <(Address, String)>::decode_value(decoder).map(Into::into)
     }
 }
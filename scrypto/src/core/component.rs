@@ -3,8 +3,11 @@ use sbor::{describe::Type, *};
 use crate::buffer::*;
 use crate::core::*;
 use crate::kernel::*;
+use crate::resource::*;
 use crate::rust::borrow::ToOwned;
+use crate::rust::collections::HashMap;
 use crate::rust::format;
+use crate::rust::string::String;
 use crate::rust::vec;
 use crate::rust::vec::Vec;
 use crate::types::*;
@@ -46,7 +49,7 @@ impl Component {
         output.component.into()
     }
 
-    pub fn call<T: Decode>(&self, method: &str, args: Vec<Vec<u8>>) -> T {
+    pub fn call<T: DecodeOwned>(&self, method: &str, args: Vec<Vec<u8>>) -> T {
         let output = call_method(self.address, method, args);
 
         scrypto_unwrap(scrypto_decode(&output))
@@ -69,6 +72,94 @@ impl Component {
         let _: PutComponentStateOutput = call_kernel(PUT_COMPONENT_STATE, input);
     }
 
+    /// Allows this component to be migrated to newer package versions by the holder of the given badge.
+    pub fn enable_upgrade<A: Into<ResourceDef>>(&self, upgrade_auth: A) {
+        let input = EnableComponentUpgradeInput {
+            component: self.address,
+            upgrade_auth: upgrade_auth.into().address(),
+        };
+        let _: EnableComponentUpgradeOutput = call_kernel(ENABLE_COMPONENT_UPGRADE, input);
+    }
+
+    /// Migrates this component to a newer version of its package.
+    ///
+    /// The blueprint in the new package must expose a `migrate(Vec<u8>) -> Vec<u8>` function,
+    /// which converts the old state into the new state.
+    pub fn migrate<P: Into<Package>>(&self, package: P, auth: BucketRef) {
+        let input = MigrateComponentInput {
+            component: self.address,
+            package: package.into().address(),
+            upgrade_auth: auth.into(),
+        };
+        let _: MigrateComponentOutput = call_kernel(MIGRATE_COMPONENT, input);
+    }
+
+    /// Returns the metadata of this component.
+    pub fn metadata(&self) -> HashMap<String, String> {
+        let input = GetComponentMetadataInput {
+            component: self.address,
+        };
+        let output: GetComponentMetadataOutput = call_kernel(GET_COMPONENT_METADATA, input);
+
+        output.metadata
+    }
+
+    /// Updates the metadata of this component, as its blueprint.
+    pub fn set_metadata(&self, metadata: HashMap<String, String>) {
+        let input = PutComponentMetadataInput {
+            component: self.address,
+            metadata,
+            auth: None,
+        };
+        let _: PutComponentMetadataOutput = call_kernel(PUT_COMPONENT_METADATA, input);
+    }
+
+    /// Updates the metadata of this component, using the owner badge.
+    pub fn update_metadata(&self, metadata: HashMap<String, String>, auth: BucketRef) {
+        let input = PutComponentMetadataInput {
+            component: self.address,
+            metadata,
+            auth: Some(auth.into()),
+        };
+        let _: PutComponentMetadataOutput = call_kernel(PUT_COMPONENT_METADATA, input);
+    }
+
+    /// Sets the owner badge, which is allowed to update metadata.
+    pub fn set_owner<A: Into<ResourceDef>>(&self, owner: A) {
+        let input = SetComponentOwnerInput {
+            component: self.address,
+            owner: owner.into().address(),
+        };
+        let _: SetComponentOwnerOutput = call_kernel(SET_COMPONENT_OWNER, input);
+    }
+
+    /// Charges XRD royalties on the given methods, which can be claimed with the royalty badge.
+    ///
+    /// Royalties are paid from the transaction context, so these methods can't be called by other blueprints.
+    pub fn set_royalties<A: Into<ResourceDef>>(
+        &self,
+        royalties: HashMap<String, Amount>,
+        royalty_auth: A,
+    ) {
+        let input = SetComponentRoyaltiesInput {
+            component: self.address,
+            royalties,
+            royalty_auth: royalty_auth.into().address(),
+        };
+        let _: SetComponentRoyaltiesOutput = call_kernel(SET_COMPONENT_ROYALTIES, input);
+    }
+
+    /// Claims all royalties collected so far.
+    pub fn claim_royalties(&self, auth: BucketRef) -> Bucket {
+        let input = ClaimComponentRoyaltiesInput {
+            component: self.address,
+            royalty_auth: auth.into(),
+        };
+        let output: ClaimComponentRoyaltiesOutput = call_kernel(CLAIM_COMPONENT_ROYALTIES, input);
+
+        output.bucket.into()
+    }
+
     pub fn blueprint(&self) -> Blueprint {
         let input = GetComponentBlueprintInput {
             component: self.address,
@@ -99,8 +190,8 @@ impl Encode for Component {
     }
 }
 
-impl Decode for Component {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for Component {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Address::decode_value(decoder).map(Into::into)
     }
 }
@@ -0,0 +1,66 @@
+use crate::abi::{Function, Method, Mutability};
+use crate::kernel::*;
+use crate::rust::borrow::ToOwned;
+use crate::rust::format;
+use crate::types::*;
+use crate::utils::*;
+
+/// Checks the ABI exported by a blueprint against the declared function and method signatures.
+///
+/// Aborts with a description of the first mismatch found.
+pub fn check_blueprint_abi(
+    package: Address,
+    name: &str,
+    functions: &[Function],
+    methods: &[Method],
+) {
+    let input = GetBlueprintAbiInput {
+        package,
+        name: name.to_owned(),
+    };
+    let output: GetBlueprintAbiOutput = call_kernel(GET_BLUEPRINT_ABI, input);
+
+    for declared in functions {
+        match output.functions.iter().find(|f| f.name == declared.name) {
+            Some(f) => {
+                if f.inputs != declared.inputs || f.output != declared.output {
+                    scrypto_abort(format!(
+                        "ABI mismatch for function {}::{}: declared {:?} -> {:?}, exported {:?} -> {:?}",
+                        name, declared.name, declared.inputs, declared.output, f.inputs, f.output
+                    ));
+                }
+            }
+            None => scrypto_abort(format!(
+                "ABI mismatch: function {}::{} not found in package {}",
+                name, declared.name, package
+            )),
+        }
+    }
+
+    for declared in methods {
+        match output.methods.iter().find(|m| m.name == declared.name) {
+            Some(m) => {
+                if is_mutable(&m.mutability) != is_mutable(&declared.mutability) {
+                    scrypto_abort(format!(
+                        "ABI mismatch for method {}::{}: declared {:?}, exported {:?}",
+                        name, declared.name, declared.mutability, m.mutability
+                    ));
+                }
+                if m.inputs != declared.inputs || m.output != declared.output {
+                    scrypto_abort(format!(
+                        "ABI mismatch for method {}::{}: declared {:?} -> {:?}, exported {:?} -> {:?}",
+                        name, declared.name, declared.inputs, declared.output, m.inputs, m.output
+                    ));
+                }
+            }
+            None => scrypto_abort(format!(
+                "ABI mismatch: method {}::{} not found in package {}",
+                name, declared.name, package
+            )),
+        }
+    }
+}
+
+fn is_mutable(mutability: &Mutability) -> bool {
+    matches!(mutability, Mutability::Mutable)
+}
@@ -10,13 +10,13 @@ use crate::utils::*;
 
 /// A scalable key-value map which loads values on demand.
 #[derive(Debug, Clone)]
-pub struct LazyMap<K: Encode + Decode, V: Encode + Decode> {
+pub struct LazyMap<K: Encode + DecodeOwned, V: Encode + DecodeOwned> {
     mid: Mid,
     key: PhantomData<K>,
     value: PhantomData<V>,
 }
 
-impl<K: Encode + Decode, V: Encode + Decode> From<Mid> for LazyMap<K, V> {
+impl<K: Encode + DecodeOwned, V: Encode + DecodeOwned> From<Mid> for LazyMap<K, V> {
     fn from(mid: Mid) -> Self {
         Self {
             mid,
@@ -26,13 +26,13 @@ impl<K: Encode + Decode, V: Encode + Decode> From<Mid> for LazyMap<K, V> {
     }
 }
 
-impl<K: Encode + Decode, V: Encode + Decode> From<LazyMap<K, V>> for Mid {
+impl<K: Encode + DecodeOwned, V: Encode + DecodeOwned> From<LazyMap<K, V>> for Mid {
     fn from(a: LazyMap<K, V>) -> Mid {
         a.mid
     }
 }
 
-impl<K: Encode + Decode, V: Encode + Decode> LazyMap<K, V> {
+impl<K: Encode + DecodeOwned, V: Encode + DecodeOwned> LazyMap<K, V> {
     pub fn new() -> Self {
         let input = CreateLazyMapInput {};
         let output: CreateLazyMapOutput = call_kernel(CREATE_LAZY_MAP, input);
@@ -64,7 +64,7 @@ impl<K: Encode + Decode, V: Encode + Decode> LazyMap<K, V> {
     }
 }
 
-impl<K: Encode + Decode, V: Encode + Decode> Default for LazyMap<K, V> {
+impl<K: Encode + DecodeOwned, V: Encode + DecodeOwned> Default for LazyMap<K, V> {
     fn default() -> Self {
         Self::new()
     }
@@ -74,25 +74,27 @@ impl<K: Encode + Decode, V: Encode + Decode> Default for LazyMap<K, V> {
 // SBOR
 //========
 
-impl<K: Encode + Decode, V: Encode + Decode> TypeId for LazyMap<K, V> {
+impl<K: Encode + DecodeOwned, V: Encode + DecodeOwned> TypeId for LazyMap<K, V> {
     fn type_id() -> u8 {
         Mid::type_id()
     }
 }
 
-impl<K: Encode + Decode, V: Encode + Decode> Encode for LazyMap<K, V> {
+impl<K: Encode + DecodeOwned, V: Encode + DecodeOwned> Encode for LazyMap<K, V> {
     fn encode_value(&self, encoder: &mut Encoder) {
         self.mid.encode_value(encoder);
     }
 }
 
-impl<K: Encode + Decode, V: Encode + Decode> Decode for LazyMap<K, V> {
+impl<'de, K: Encode + DecodeOwned, V: Encode + DecodeOwned> Decode<'de> for LazyMap<K, V> {
     fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
         Mid::decode_value(decoder).map(Into::into)
     }
 }
 
-impl<K: Encode + Decode + Describe, V: Encode + Decode + Describe> Describe for LazyMap<K, V> {
+impl<K: Encode + DecodeOwned + Describe, V: Encode + DecodeOwned + Describe> Describe
+    for LazyMap<K, V>
+{
     fn describe() -> Type {
         Type::Custom {
             name: SCRYPTO_NAME_LAZY_MAP.to_owned(),
@@ -3,6 +3,7 @@ mod blueprint;
 mod call;
 mod component;
 mod context;
+mod interface;
 mod lazy_map;
 mod logger;
 mod package;
@@ -12,12 +13,13 @@ pub use blueprint::Blueprint;
 pub use call::{call_function, call_method};
 pub use component::Component;
 pub use context::Context;
+pub use interface::check_blueprint_abi;
 pub use lazy_map::LazyMap;
 pub use logger::{Level, Logger};
 pub use package::Package;
 
 /// Represents the state of a component.
-pub trait State: sbor::Encode + sbor::Decode {
+pub trait State: sbor::Encode + sbor::DecodeOwned {
     fn name() -> &'static str;
 
     fn instantiate(self) -> Component;
@@ -2,6 +2,7 @@ use sbor::{describe::Type, *};
 
 use crate::buffer::*;
 use crate::kernel::*;
+use crate::resource::*;
 use crate::rust::borrow::ToOwned;
 use crate::rust::format;
 use crate::rust::vec;
@@ -43,6 +44,33 @@ impl Package {
         output.package.into()
     }
 
+    /// Publishes a new version of the `previous` package.
+    ///
+    /// Without an upgrade badge, this must be called from the `previous` package itself.
+    pub fn new_version<P: Into<Package>>(
+        previous: P,
+        code: &[u8],
+        auth: Option<BucketRef>,
+    ) -> Self {
+        let input = PublishPackageUpgradeInput {
+            code: code.to_vec(),
+            previous: previous.into().address(),
+            upgrade_auth: auth.map(Into::into),
+        };
+        let output: PublishPackageUpgradeOutput = call_kernel(PUBLISH_PACKAGE_UPGRADE, input);
+
+        output.package.into()
+    }
+
+    /// Allows newer versions of this package to be published by the holder of the given badge.
+    pub fn enable_upgrade<A: Into<ResourceDef>>(&self, upgrade_auth: A) {
+        let input = EnablePackageUpgradeInput {
+            package: self.address,
+            upgrade_auth: upgrade_auth.into().address(),
+        };
+        let _: EnablePackageUpgradeOutput = call_kernel(ENABLE_PACKAGE_UPGRADE, input);
+    }
+
     pub fn address(&self) -> Address {
         self.address
     }
@@ -64,8 +92,8 @@ impl Encode for Package {
     }
 }
 
-impl Decode for Package {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for Package {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Address::decode_value(decoder).map(Into::into)
     }
 }
@@ -1,5 +1,7 @@
 use sbor::{Decode, Encode, TypeId};
 
+use crate::abi::{Function, Method};
+
 use crate::rust::collections::HashMap;
 use crate::rust::string::String;
 use crate::rust::vec::Vec;
@@ -17,6 +19,12 @@ pub const PUBLISH_PACKAGE: u32 = 0x00;
 pub const CALL_FUNCTION: u32 = 0x01;
 /// Call a method
 pub const CALL_METHOD: u32 = 0x02;
+/// Publish a new version of a code package
+pub const PUBLISH_PACKAGE_UPGRADE: u32 = 0x03;
+/// Retrieve the ABI of a blueprint
+pub const GET_BLUEPRINT_ABI: u32 = 0x04;
+/// Allow newer versions of a code package to be published
+pub const ENABLE_PACKAGE_UPGRADE: u32 = 0x05;
 
 /// Create a component
 pub const CREATE_COMPONENT: u32 = 0x10;
@@ -26,6 +34,20 @@ pub const GET_COMPONENT_BLUEPRINT: u32 = 0x11;
 pub const GET_COMPONENT_STATE: u32 = 0x12;
 /// Update component state
 pub const PUT_COMPONENT_STATE: u32 = 0x13;
+/// Allow a component to be upgraded
+pub const ENABLE_COMPONENT_UPGRADE: u32 = 0x14;
+/// Migrate a component to a newer package version
+pub const MIGRATE_COMPONENT: u32 = 0x15;
+/// Retrieve component metadata
+pub const GET_COMPONENT_METADATA: u32 = 0x16;
+/// Update component metadata
+pub const PUT_COMPONENT_METADATA: u32 = 0x17;
+/// Set the owner badge of a component
+pub const SET_COMPONENT_OWNER: u32 = 0x18;
+/// Configure component method royalties
+pub const SET_COMPONENT_ROYALTIES: u32 = 0x19;
+/// Claim the royalties collected by a component
+pub const CLAIM_COMPONENT_ROYALTIES: u32 = 0x1a;
 
 /// Create a lazy map
 pub const CREATE_LAZY_MAP: u32 = 0x20;
@@ -132,6 +154,39 @@ pub struct CallMethodOutput {
     pub rtn: Vec<u8>,
 }
 
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct PublishPackageUpgradeInput {
+    pub code: Vec<u8>,
+    pub previous: Address,
+    pub upgrade_auth: Option<Rid>,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct PublishPackageUpgradeOutput {
+    pub package: Address,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct EnablePackageUpgradeInput {
+    pub package: Address,
+    pub upgrade_auth: Address,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct EnablePackageUpgradeOutput {}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct GetBlueprintAbiInput {
+    pub package: Address,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct GetBlueprintAbiOutput {
+    pub functions: Vec<Function>,
+    pub methods: Vec<Method>,
+}
+
 //==========
 // component
 //==========
@@ -177,6 +232,75 @@ pub struct PutComponentStateInput {
 #[derive(Debug, Clone, TypeId, Encode, Decode)]
 pub struct PutComponentStateOutput {}
 
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct EnableComponentUpgradeInput {
+    pub component: Address,
+    pub upgrade_auth: Address,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct EnableComponentUpgradeOutput {}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct MigrateComponentInput {
+    pub component: Address,
+    pub package: Address,
+    pub upgrade_auth: Rid,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct MigrateComponentOutput {}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct GetComponentMetadataInput {
+    pub component: Address,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct GetComponentMetadataOutput {
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct PutComponentMetadataInput {
+    pub component: Address,
+    pub metadata: HashMap<String, String>,
+    pub auth: Option<Rid>,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct PutComponentMetadataOutput {}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct SetComponentOwnerInput {
+    pub component: Address,
+    pub owner: Address,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct SetComponentOwnerOutput {}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct SetComponentRoyaltiesInput {
+    pub component: Address,
+    pub royalties: HashMap<String, Amount>,
+    pub royalty_auth: Address,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct SetComponentRoyaltiesOutput {}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct ClaimComponentRoyaltiesInput {
+    pub component: Address,
+    pub royalty_auth: Rid,
+}
+
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct ClaimComponentRoyaltiesOutput {
+    pub bucket: Bid,
+}
+
 //==========
 // LazyMap
 //==========
@@ -6,7 +6,7 @@ use crate::utils::*;
 
 /// Utility function for making a kernel call.
 #[cfg(target_arch = "wasm32")]
-pub fn call_kernel<T: Encode, V: Decode>(op: u32, input: T) -> V {
+pub fn call_kernel<T: Encode, V: DecodeOwned>(op: u32, input: T) -> V {
     unsafe {
         // 1. serialize the input
         let input_bytes = scrypto_encode(&input);
@@ -23,7 +23,7 @@ pub fn call_kernel<T: Encode, V: Decode>(op: u32, input: T) -> V {
 
 /// Utility function for making a kernel call.
 #[cfg(not(target_arch = "wasm32"))]
-pub fn call_kernel<T: Encode, V: Decode>(op: u32, input: T) -> V {
+pub fn call_kernel<T: Encode, V: DecodeOwned>(op: u32, input: T) -> V {
     if op == EMIT_LOG {
         let input_bytes = scrypto_encode(&input);
         #[allow(unused_variables)]
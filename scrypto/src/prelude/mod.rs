@@ -8,7 +8,8 @@ pub use crate::resource::{Bucket, BucketRef, ResourceBuilder, ResourceDef, Vault
 pub use crate::types::*;
 pub use crate::utils::*;
 pub use crate::{
-    args, auth, blueprint, debug, error, import, include_code, info, scrypto_assert, trace, warn,
+    args, auth, blueprint, debug, error, external_blueprint, external_component, import,
+    include_code, info, scrypto_assert, trace, warn,
 };
 
 pub use crate::rust::borrow::ToOwned;
@@ -114,8 +114,8 @@ impl Encode for Bucket {
     }
 }
 
-impl Decode for Bucket {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for Bucket {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Bid::decode_value(decoder).map(Into::into)
     }
 }
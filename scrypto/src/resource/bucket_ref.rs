@@ -92,8 +92,8 @@ impl Encode for BucketRef {
     }
 }
 
-impl Decode for BucketRef {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for BucketRef {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Rid::decode_value(decoder).map(Into::into)
     }
 }
@@ -137,8 +137,8 @@ impl Encode for ResourceDef {
     }
 }
 
-impl Decode for ResourceDef {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for ResourceDef {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Address::decode_value(decoder).map(Into::into)
     }
 }
@@ -107,8 +107,8 @@ impl Encode for Vault {
     }
 }
 
-impl Decode for Vault {
-    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+impl<'de> Decode<'de> for Vault {
+    fn decode_value(decoder: &mut Decoder<'de>) -> Result<Self, DecodeError> {
         Vid::decode_value(decoder).map(Into::into)
     }
 }
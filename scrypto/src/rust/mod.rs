@@ -3,6 +3,8 @@ extern crate alloc;
 #[cfg(feature = "alloc")]
 pub use alloc::borrow;
 #[cfg(feature = "alloc")]
+pub use alloc::boxed;
+#[cfg(feature = "alloc")]
 pub use alloc::fmt;
 #[cfg(feature = "alloc")]
 pub use alloc::format;
@@ -24,10 +26,14 @@ pub use core::marker;
 pub use core::mem;
 #[cfg(feature = "alloc")]
 pub use core::ptr;
+#[cfg(feature = "alloc")]
+pub use core::sync;
 
 #[cfg(not(feature = "alloc"))]
 pub use std::borrow;
 #[cfg(not(feature = "alloc"))]
+pub use std::boxed;
+#[cfg(not(feature = "alloc"))]
 pub use std::cell;
 #[cfg(not(feature = "alloc"))]
 pub use std::convert;
@@ -48,6 +54,8 @@ pub use std::str;
 #[cfg(not(feature = "alloc"))]
 pub use std::string;
 #[cfg(not(feature = "alloc"))]
+pub use std::sync;
+#[cfg(not(feature = "alloc"))]
 pub use std::vec;
 
 /// Collection types.
@@ -0,0 +1,9 @@
+#[cfg(windows)]
+use colored::*;
+use simulator::sbor;
+
+pub fn main() -> Result<(), sbor::Error> {
+    #[cfg(windows)]
+    control::set_virtual_terminal(true).unwrap();
+    sbor::run(std::env::args())
+}
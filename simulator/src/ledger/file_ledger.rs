@@ -101,7 +101,7 @@ impl FileBasedLedger {
         sbor::encode_with_type(Vec::with_capacity(512), v)
     }
 
-    pub fn decode<T: sbor::Decode>(bytes: Vec<u8>) -> T {
+    pub fn decode<T: sbor::DecodeOwned>(bytes: Vec<u8>) -> T {
         sbor::decode_with_type(&bytes).unwrap()
     }
 }
@@ -163,4 +163,21 @@ impl Ledger for FileBasedLedger {
             Self::encode(&vault),
         )
     }
+
+    fn list_vaults(&self) -> Vec<Vid> {
+        let mut path = self.root.clone();
+        path.push(VAULTS);
+
+        let mut results = Vec::new();
+        for entry in fs::read_dir(path).unwrap() {
+            let entry = entry.unwrap();
+            let path = entry.path();
+            if path.is_file() {
+                let name = path.file_stem().unwrap().to_str().unwrap();
+                let (hash, index) = name.split_once('_').unwrap();
+                results.push(Vid(H256::from_str(hash).unwrap(), index.parse().unwrap()));
+            }
+        }
+        results
+    }
 }
@@ -2,6 +2,8 @@
 pub mod ledger;
 /// Radix Engine Simulator CLI.
 pub mod resim;
+/// SBOR CLI.
+pub mod sbor;
 /// Scrypto CLI.
 pub mod scrypto;
 /// Utility functions.
@@ -0,0 +1,76 @@
+use clap::{crate_version, App, Arg, ArgMatches, SubCommand};
+use radix_engine::transaction::*;
+
+use crate::ledger::*;
+use crate::resim::*;
+
+const ARG_COMPONENT: &str = "COMPONENT";
+const ARG_PACKAGE: &str = "PACKAGE";
+const ARG_UPGRADE_AUTH: &str = "UPGRADE_AUTH";
+
+const ARG_TRACE: &str = "TRACE";
+const ARG_SIGNERS: &str = "SIGNERS";
+
+/// Constructs a `migrate` subcommand.
+pub fn make_migrate<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(CMD_MIGRATE)
+        .about("Migrates a component to a newer version of its package")
+        .version(crate_version!())
+        .arg(
+            Arg::with_name(ARG_COMPONENT)
+                .help("Specify the component address.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(ARG_PACKAGE)
+                .help("Specify the address of the new package version.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(ARG_UPGRADE_AUTH)
+                .help("Specify the upgrade auth resource definition address.")
+                .required(true),
+        )
+        // options
+        .arg(
+            Arg::with_name(ARG_TRACE)
+                .long("trace")
+                .help("Turn on tracing."),
+        )
+        .arg(
+            Arg::with_name(ARG_SIGNERS)
+                .long("signers")
+                .takes_value(true)
+                .help("Specify the transaction signers, separated by comma."),
+        )
+}
+
+/// Handles a `migrate` request.
+pub fn handle_migrate(matches: &ArgMatches) -> Result<(), Error> {
+    let component = match_address(matches, ARG_COMPONENT)?;
+    let package = match_address(matches, ARG_PACKAGE)?;
+    let upgrade_auth = match_address(matches, ARG_UPGRADE_AUTH)?;
+    let trace = matches.is_present(ARG_TRACE);
+    let signers = match_signers(matches, ARG_SIGNERS)?;
+
+    let mut configs = get_configs()?;
+    let account = configs.default_account.ok_or(Error::NoDefaultAccount)?;
+    let mut ledger = FileBasedLedger::with_bootstrap(get_data_dir()?);
+    let mut executor = TransactionExecutor::new(&mut ledger, configs.current_epoch, configs.nonce);
+    let transaction = TransactionBuilder::new(&executor)
+        .withdraw(1.into(), upgrade_auth, account)
+        .migrate_component(component, package, upgrade_auth)
+        .deposit_all(account)
+        .build(signers)
+        .map_err(Error::TransactionConstructionError)?;
+    let receipt = executor.run(transaction, trace).unwrap();
+
+    println!("{:?}", receipt);
+    if receipt.success {
+        configs.nonce = executor.nonce();
+        set_configs(configs)?;
+        Ok(())
+    } else {
+        Err(Error::TransactionFailed)
+    }
+}
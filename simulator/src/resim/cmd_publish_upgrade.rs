@@ -0,0 +1,88 @@
+use std::ffi::OsStr;
+use std::fs;
+
+use clap::{crate_version, App, Arg, ArgMatches, SubCommand};
+use radix_engine::transaction::*;
+
+use crate::ledger::*;
+use crate::resim::*;
+use crate::utils::*;
+
+const ARG_PATH: &str = "PATH";
+const ARG_PREVIOUS: &str = "PREVIOUS";
+const ARG_UPGRADE_AUTH: &str = "UPGRADE_AUTH";
+
+const ARG_TRACE: &str = "TRACE";
+const ARG_SIGNERS: &str = "SIGNERS";
+
+/// Constructs a `publish-upgrade` subcommand.
+pub fn make_publish_upgrade<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(CMD_PUBLISH_UPGRADE)
+        .about("Publishes a new version of a package")
+        .version(crate_version!())
+        .arg(
+            Arg::with_name(ARG_PATH)
+                .help("Specify the the path to a Scrypto package or a .wasm file.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(ARG_PREVIOUS)
+                .help("Specify the address of the package being upgraded.")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(ARG_UPGRADE_AUTH)
+                .help("Specify the upgrade auth resource definition address.")
+                .required(true),
+        )
+        // options
+        .arg(
+            Arg::with_name(ARG_TRACE)
+                .long("trace")
+                .help("Turn on tracing."),
+        )
+        .arg(
+            Arg::with_name(ARG_SIGNERS)
+                .long("signers")
+                .takes_value(true)
+                .help("Specify the transaction signers, separated by comma."),
+        )
+}
+
+/// Handles a `publish-upgrade` request.
+pub fn handle_publish_upgrade(matches: &ArgMatches) -> Result<(), Error> {
+    let path = match_path(matches, ARG_PATH)?;
+    let previous = match_address(matches, ARG_PREVIOUS)?;
+    let upgrade_auth = match_address(matches, ARG_UPGRADE_AUTH)?;
+    let trace = matches.is_present(ARG_TRACE);
+    let signers = match_signers(matches, ARG_SIGNERS)?;
+
+    // Load wasm code
+    let code = fs::read(if path.extension() != Some(OsStr::new("wasm")) {
+        build_package(path, false).map_err(Error::CargoError)?
+    } else {
+        path
+    })
+    .map_err(Error::IOError)?;
+
+    let mut configs = get_configs()?;
+    let account = configs.default_account.ok_or(Error::NoDefaultAccount)?;
+    let mut ledger = FileBasedLedger::with_bootstrap(get_data_dir()?);
+    let mut executor = TransactionExecutor::new(&mut ledger, configs.current_epoch, configs.nonce);
+    let transaction = TransactionBuilder::new(&executor)
+        .withdraw(1.into(), upgrade_auth, account)
+        .publish_package_upgrade(&code, previous, upgrade_auth)
+        .deposit_all(account)
+        .build(signers)
+        .map_err(Error::TransactionConstructionError)?;
+    let receipt = executor.run(transaction, trace).unwrap();
+
+    println!("{:?}", receipt);
+    if receipt.success {
+        configs.nonce = executor.nonce();
+        set_configs(configs)?;
+        Ok(())
+    } else {
+        Err(Error::TransactionFailed)
+    }
+}
@@ -1,5 +1,9 @@
 use clap::{crate_version, App, Arg, ArgMatches, SubCommand};
 use radix_engine::transaction::*;
+use scrypto::types::*;
+use scrypto::utils::*;
+use std::collections::HashMap;
+use transaction_manifest::{ast, Environment};
 
 use crate::ledger::*;
 use crate::resim::*;
@@ -8,6 +12,9 @@ const ARG_TRANSACTION_MANIFEST: &str = "MANIFEST";
 
 const ARG_TRACE: &str = "TRACE";
 const ARG_SIGNERS: &str = "SIGNERS";
+const ARG_DEFINE: &str = "DEFINE";
+const ARG_BLOB: &str = "BLOB";
+const ARG_ARG: &str = "ARG";
 
 /// Constructs a `run` subcommand.
 pub fn make_run<'a, 'b>() -> App<'a, 'b> {
@@ -31,6 +38,30 @@ pub fn make_run<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .help("Specify the transaction signers, separated by comma."),
         )
+        .arg(
+            Arg::with_name(ARG_DEFINE)
+                .long("define")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Define a manifest variable, e.g. NAME=Address(\"...\")."),
+        )
+        .arg(
+            Arg::with_name(ARG_BLOB)
+                .long("blob")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Attach a file, referred to in the manifest as Blob(\"<sha256 hash>\")."),
+        )
+        .arg(
+            Arg::with_name(ARG_ARG)
+                .long("arg")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Fill in a manifest parameter, e.g. amount=100."),
+        )
 }
 
 /// Handles a `run` request.
@@ -38,14 +69,29 @@ pub fn handle_run(matches: &ArgMatches) -> Result<(), Error> {
     let manifest_path = match_path(matches, ARG_TRANSACTION_MANIFEST)?;
     let trace = matches.is_present(ARG_TRACE);
     let signers = match_signers(matches, ARG_SIGNERS)?;
+    let mut configs = get_configs()?;
+    let env = Environment {
+        variables: match_variables(matches, ARG_DEFINE, &configs)?,
+        args: match_parameters(matches, ARG_ARG)?,
+        blobs: match_blobs(matches, ARG_BLOB)?,
+    };
 
     let manifest = std::fs::read_to_string(manifest_path).map_err(Error::IOError)?;
-    let mut transaction = transaction_manifest::compile(&manifest).map_err(Error::CompileError)?;
-    transaction.instructions.push(Instruction::End {
-        signatures: signers,
-    });
+    let mut transaction = transaction_manifest::compile_with_env(&manifest, &env).map_err(|e| {
+        eprint!(
+            "{}",
+            transaction_manifest::diagnostic::render(&manifest, &e)
+        );
+        Error::CompileError(e)
+    })?;
+    // A manifest may end the transaction itself; otherwise it's signed by `--signers`
+    if !matches!(
+        transaction.instructions.last(),
+        Some(Instruction::End { .. })
+    ) {
+        transaction.instructions.push(Instruction::End { signers });
+    }
 
-    let mut configs = get_configs()?;
     let mut ledger = FileBasedLedger::with_bootstrap(get_data_dir()?);
     let mut executor = TransactionExecutor::new(&mut ledger, configs.current_epoch, configs.nonce);
     let receipt = executor.run(transaction, trace).unwrap();
@@ -59,3 +105,52 @@ pub fn handle_run(matches: &ArgMatches) -> Result<(), Error> {
         Err(Error::TransactionFailed)
     }
 }
+
+/// Collects manifest variables from resim configs and `NAME=VALUE` definitions.
+fn match_variables(
+    matches: &ArgMatches,
+    name: &str,
+    configs: &Configs,
+) -> Result<HashMap<String, ast::Value>, Error> {
+    let mut variables = HashMap::new();
+    if let Some(account) = configs.default_account {
+        variables.insert(
+            "DEFAULT_ACCOUNT".to_owned(),
+            ast::Value::Address(ast::Value::String(account.to_string()).into()),
+        );
+    }
+
+    for definition in match_args(matches, name)? {
+        let (key, value) = definition
+            .split_once('=')
+            .ok_or_else(|| Error::InvalidDefinition(definition.clone()))?;
+        let value = transaction_manifest::parse_value(value).map_err(|e| {
+            eprint!("{}", transaction_manifest::diagnostic::render(value, &e));
+            Error::CompileError(e)
+        })?;
+        variables.insert(key.trim().to_owned(), value);
+    }
+    Ok(variables)
+}
+
+/// Collects `NAME=VALUE` manifest parameter arguments.
+fn match_parameters(matches: &ArgMatches, name: &str) -> Result<HashMap<String, String>, Error> {
+    let mut parameters = HashMap::new();
+    for argument in match_args(matches, name)? {
+        let (key, value) = argument
+            .split_once('=')
+            .ok_or_else(|| Error::InvalidDefinition(argument.clone()))?;
+        parameters.insert(key.trim().to_owned(), value.to_owned());
+    }
+    Ok(parameters)
+}
+
+/// Reads the attached blob files, keyed by their hash.
+fn match_blobs(matches: &ArgMatches, name: &str) -> Result<HashMap<H256, Vec<u8>>, Error> {
+    let mut blobs = HashMap::new();
+    for path in match_args(matches, name)? {
+        let content = std::fs::read(path).map_err(Error::IOError)?;
+        blobs.insert(sha256(&content), content);
+    }
+    Ok(blobs)
+}
@@ -30,6 +30,8 @@ pub enum Error {
 
     InvalidSignerPublicKey,
 
+    InvalidDefinition(String),
+
     IOError(io::Error),
 
     DataError(DecodeError),
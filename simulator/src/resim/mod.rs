@@ -1,11 +1,13 @@
 mod cmd_call_function;
 mod cmd_call_method;
 mod cmd_export_abi;
+mod cmd_migrate;
 mod cmd_mint;
 mod cmd_new_account;
 mod cmd_new_resource_fixed;
 mod cmd_new_resource_mutable;
 mod cmd_publish;
+mod cmd_publish_upgrade;
 mod cmd_reset;
 mod cmd_run;
 mod cmd_set_current_epoch;
@@ -21,11 +23,13 @@ mod utils;
 pub use cmd_call_function::*;
 pub use cmd_call_method::*;
 pub use cmd_export_abi::*;
+pub use cmd_migrate::*;
 pub use cmd_mint::*;
 pub use cmd_new_account::*;
 pub use cmd_new_resource_fixed::*;
 pub use cmd_new_resource_mutable::*;
 pub use cmd_publish::*;
+pub use cmd_publish_upgrade::*;
 pub use cmd_reset::*;
 pub use cmd_run::*;
 pub use cmd_set_current_epoch::*;
@@ -45,8 +49,10 @@ pub const CMD_NEW_ACCOUNT: &str = "new-account";
 pub const CMD_NEW_RESOURCE_FIXED: &str = "new-resource-fixed";
 pub const CMD_NEW_RESOURCE_MUTABLE: &str = "new-resource-mutable";
 pub const CMD_MINT: &str = "mint";
+pub const CMD_MIGRATE: &str = "migrate";
 pub const CMD_TRANSFER: &str = "transfer";
 pub const CMD_PUBLISH: &str = "publish";
+pub const CMD_PUBLISH_UPGRADE: &str = "publish-upgrade";
 pub const CMD_RESET: &str = "reset";
 pub const CMD_RUN: &str = "run";
 pub const CMD_SET_DEFAULT_ACCOUNT: &str = "set-default-account";
@@ -71,9 +77,11 @@ where
         .subcommand(make_new_resource_fixed())
         .subcommand(make_new_resource_mutable())
         .subcommand(make_mint())
+        .subcommand(make_migrate())
         .subcommand(make_transfer())
         .subcommand(make_new_account())
         .subcommand(make_publish())
+        .subcommand(make_publish_upgrade())
         .subcommand(make_reset())
         .subcommand(make_run())
         .subcommand(make_set_default_account())
@@ -90,9 +98,11 @@ where
         (CMD_NEW_RESOURCE_FIXED, Some(m)) => handle_new_resource_fixed(m),
         (CMD_NEW_RESOURCE_MUTABLE, Some(m)) => handle_new_resource_mutable(m),
         (CMD_MINT, Some(m)) => handle_mint(m),
+        (CMD_MIGRATE, Some(m)) => handle_migrate(m),
         (CMD_TRANSFER, Some(m)) => handle_transfer(m),
         (CMD_NEW_ACCOUNT, Some(m)) => handle_new_account(m),
         (CMD_PUBLISH, Some(m)) => handle_publish(m),
+        (CMD_PUBLISH_UPGRADE, Some(m)) => handle_publish_upgrade(m),
         (CMD_RESET, Some(m)) => handle_reset(m),
         (CMD_RUN, Some(m)) => handle_run(m),
         (CMD_SET_DEFAULT_ACCOUNT, Some(m)) => handle_set_default_account(m),
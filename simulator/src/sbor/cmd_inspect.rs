@@ -0,0 +1,134 @@
+use std::fs;
+
+use clap::{crate_version, App, Arg, ArgMatches, SubCommand};
+use radix_engine::ledger::*;
+use radix_engine::utils::*;
+use sbor::describe::*;
+use sbor::inspect::*;
+use scrypto::abi;
+
+use crate::sbor::*;
+
+const ARG_INPUT: &str = "INPUT";
+
+const ARG_SCHEMA: &str = "SCHEMA";
+const ARG_ABI: &str = "ABI";
+const ARG_TYPE: &str = "TYPE";
+
+/// Constructs a `inspect` subcommand.
+pub fn make_inspect<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(CMD_INSPECT)
+        .about("Prints an annotated tree of an SBOR payload")
+        .version(crate_version!())
+        .arg(
+            Arg::with_name(ARG_INPUT)
+                .help("Specify the payload file, or the payload in hex.")
+                .required(true),
+        )
+        // options
+        .arg(
+            Arg::with_name(ARG_SCHEMA)
+                .long("schema")
+                .takes_value(true)
+                .help("Specify a JSON file with the type of the payload.")
+                .conflicts_with(ARG_ABI),
+        )
+        .arg(
+            Arg::with_name(ARG_ABI)
+                .long("abi")
+                .takes_value(true)
+                .help("Specify a JSON file with the ABI of a blueprint.")
+                .requires(ARG_TYPE),
+        )
+        .arg(
+            Arg::with_name(ARG_TYPE)
+                .long("type")
+                .takes_value(true)
+                .help("Specify the name of a struct or enum in the ABI.")
+                .requires(ARG_ABI),
+        )
+}
+
+/// Handles a `inspect` request.
+pub fn handle_inspect(matches: &ArgMatches) -> Result<(), Error> {
+    let input = matches
+        .value_of(ARG_INPUT)
+        .ok_or_else(|| Error::MissingArgument(ARG_INPUT.to_owned()))?;
+    let data = match fs::read(input) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            hex::decode(input.trim_start_matches("0x")).map_err(Error::InvalidHex)?
+        }
+        Err(e) => return Err(Error::IOError(e)),
+    };
+
+    let schema = if let Some(path) = matches.value_of(ARG_SCHEMA) {
+        let json = fs::read_to_string(path).map_err(Error::IOError)?;
+        Some(serde_json::from_str::<Type>(&json).map_err(Error::JSONError)?)
+    } else if let Some(path) = matches.value_of(ARG_ABI) {
+        let json = fs::read_to_string(path).map_err(Error::IOError)?;
+        let abi: abi::Blueprint = serde_json::from_str(&json).map_err(Error::JSONError)?;
+        let name = matches.value_of(ARG_TYPE).unwrap();
+        Some(find_type(&abi, name).ok_or_else(|| Error::TypeNotFound(name.to_owned()))?)
+    } else {
+        None
+    };
+
+    let ledger = InMemoryLedger::new();
+    let inspection = inspect_with(&data, schema.as_ref(), &|ty, data| {
+        format_custom(ty, data, &ledger, &mut Vec::new()).ok()
+    });
+    print!("{}", inspection);
+
+    match inspection.error {
+        Some((offset, e)) => Err(Error::DecodeError(offset, e)),
+        None => Ok(()),
+    }
+}
+
+/// Finds a struct or enum by name in the inputs and outputs of a blueprint.
+fn find_type(abi: &abi::Blueprint, name: &str) -> Option<Type> {
+    let functions = abi.functions.iter().map(|f| (&f.inputs, &f.output));
+    let methods = abi.methods.iter().map(|m| (&m.inputs, &m.output));
+    functions
+        .chain(methods)
+        .flat_map(|(inputs, output)| inputs.iter().chain(Some(output)))
+        .find_map(|ty| find_named(ty, name))
+}
+
+fn find_named(ty: &Type, name: &str) -> Option<Type> {
+    let children: Vec<&Type> = match ty {
+        Type::Struct { name: n, .. } | Type::Enum { name: n, .. } if n == name => {
+            return Some(ty.clone());
+        }
+        Type::Struct {
+            fields, generics, ..
+        } => fields_types(fields).chain(generics).collect(),
+        Type::Enum {
+            variants, generics, ..
+        } => variants
+            .iter()
+            .flat_map(|v| fields_types(&v.fields))
+            .chain(generics)
+            .collect(),
+        Type::Option { value } | Type::Box { value } => vec![value],
+        Type::Array { element, .. }
+        | Type::Vec { element }
+        | Type::TreeSet { element }
+        | Type::HashSet { element } => vec![element],
+        Type::Tuple { elements } => elements.iter().collect(),
+        Type::Result { okay, error } => vec![okay, error],
+        Type::TreeMap { key, value } | Type::HashMap { key, value } => vec![key, value],
+        Type::Custom { generics, .. } => generics.iter().collect(),
+        _ => Vec::new(),
+    };
+    children.into_iter().find_map(|ty| find_named(ty, name))
+}
+
+fn fields_types(fields: &Fields) -> Box<dyn Iterator<Item = &Type> + '_> {
+    match fields {
+        Fields::Named { named } => Box::new(named.iter().map(|(_, ty)| ty)),
+        Fields::Unnamed { unnamed } => Box::new(unnamed.iter()),
+        Fields::Unit => Box::new(std::iter::empty()),
+    }
+}
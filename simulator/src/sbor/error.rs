@@ -0,0 +1,21 @@
+use std::io;
+
+use sbor::DecodeError;
+
+/// Represents a sbor CLI error.
+#[derive(Debug)]
+pub enum Error {
+    MissingArgument(String),
+
+    MissingSubCommand,
+
+    IOError(io::Error),
+
+    JSONError(serde_json::Error),
+
+    InvalidHex(hex::FromHexError),
+
+    TypeNotFound(String),
+
+    DecodeError(usize, DecodeError),
+}
@@ -0,0 +1,26 @@
+mod cmd_inspect;
+mod error;
+
+pub use cmd_inspect::*;
+pub use error::*;
+
+pub const CMD_INSPECT: &str = "inspect";
+
+/// Runs sbor CLI.
+pub fn run<I, T>(args: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let app = clap::App::new("SBOR")
+        .name("sbor")
+        .about("Inspect SBOR payloads.")
+        .version(clap::crate_version!())
+        .subcommand(make_inspect());
+    let matches = app.get_matches_from(args);
+
+    match matches.subcommand() {
+        (CMD_INSPECT, Some(m)) => handle_inspect(m),
+        _ => Err(Error::MissingSubCommand),
+    }
+}
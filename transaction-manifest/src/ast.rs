@@ -1,10 +1,40 @@
+use crate::lexer::Span;
+use sbor::describe::Discriminator;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Transaction {
+    /// The template parameters declared at the top of the manifest
+    pub parameters: Vec<Parameter>,
     pub instructions: Vec<Instruction>,
+    /// The source location of each instruction
+    pub spans: Vec<InstructionSpan>,
+}
+
+/// A `$name: Type` declaration of a value to be filled in when the manifest is compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    pub ty: Type,
+    /// The span of the whole declaration
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionSpan {
+    /// The span of the whole instruction
+    pub span: Span,
+    /// The spans of the instruction operands, in order
+    pub args: Vec<Span>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Instruction {
+    /// Binds a name to a value, for use by later instructions
+    Let {
+        name: String,
+        value: Value,
+    },
+
     DeclareTempBucket,
 
     DeclareTempBucketRef,
@@ -15,12 +45,31 @@ pub enum Instruction {
         to: Value,
     },
 
+    TakeAllFromContext {
+        resource_address: Value,
+        to: Value,
+    },
+
+    ReturnToContext {
+        bucket: Value,
+    },
+
     BorrowFromContext {
         amount: Value,
         resource_address: Value,
         to: Value,
     },
 
+    CreateBucketRefFromBucket {
+        bucket: Value,
+        to: Value,
+    },
+
+    CloneBucketRef {
+        bucket_ref: Value,
+        to: Value,
+    },
+
     CallFunction {
         package_address: Value,
         blueprint_name: Value,
@@ -34,11 +83,29 @@ pub enum Instruction {
         args: Vec<Value>,
     },
 
+    AssertContextContains {
+        amount: Value,
+        resource_address: Value,
+    },
+
+    AssertBucketAmountAtLeast {
+        bucket: Value,
+        amount: Value,
+    },
+
     DropAllBucketRefs,
 
     DepositAllBuckets {
         account: Value,
     },
+
+    PublishPackage {
+        code: Value,
+    },
+
+    End {
+        signers: Value,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -59,6 +126,8 @@ pub enum Type {
     String,
     Struct,
     Enum,
+    EnumU16,
+    EnumString,
     Option,
     Box,
     Array,
@@ -74,13 +143,16 @@ pub enum Type {
 
     /* Custom types */
     Decimal,
-    BigDecimal,
     Address,
     Hash,
     Bucket,
     BucketRef,
     LazyMap,
     Vault,
+
+    /* Binary data */
+    Bytes,
+    Blob,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -99,7 +171,7 @@ pub enum Value {
     U128(u128),
     String(String),
     Struct(Fields),
-    Enum(u8, Fields),
+    Enum(Discriminator, Fields),
     Option(Box<Option<Value>>),
     Box(Box<Value>),
     Array(Type, Vec<Value>),
@@ -113,16 +185,91 @@ pub enum Value {
     HashMap(Type, Type, Vec<Value>),
 
     Decimal(Box<Value>),
-    BigDecimal(Box<Value>),
     Address(Box<Value>),
     Hash(Box<Value>),
     Bucket(Box<Value>),
     BucketRef(Box<Value>),
     LazyMap(Box<Value>),
     Vault(Box<Value>),
+
+    /* Binary data */
+    /// Raw bytes, written as a hex string
+    Bytes(Box<Value>),
+    /// A blob attached alongside the manifest, referred to by the hex SHA-256 hash of its content
+    Blob(Box<Value>),
+
+    /* References, substituted before compilation */
+    /// A `$name` reference to a `LET` constant or a variable supplied from outside the manifest
+    Variable(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fields {
+    Named(Vec<Value>),
+    Unnamed(Vec<Value>),
+    Unit,
+}
+
+impl Instruction {
+    /// Returns the operands of this instruction, in the order of their spans.
+    pub fn values_mut(&mut self) -> Vec<&mut Value> {
+        match self {
+            Instruction::Let { value, .. } => vec![value],
+            Instruction::DeclareTempBucket
+            | Instruction::DeclareTempBucketRef
+            | Instruction::DropAllBucketRefs => vec![],
+            Instruction::TakeFromContext {
+                amount,
+                resource_address,
+                to,
+            }
+            | Instruction::BorrowFromContext {
+                amount,
+                resource_address,
+                to,
+            } => vec![amount, resource_address, to],
+            Instruction::TakeAllFromContext {
+                resource_address,
+                to,
+            } => vec![resource_address, to],
+            Instruction::ReturnToContext { bucket } => vec![bucket],
+            Instruction::CreateBucketRefFromBucket { bucket, to } => vec![bucket, to],
+            Instruction::CloneBucketRef { bucket_ref, to } => vec![bucket_ref, to],
+            Instruction::CallFunction {
+                package_address,
+                blueprint_name,
+                function,
+                args,
+            } => {
+                let mut values = vec![package_address, blueprint_name, function];
+                values.extend(args.iter_mut());
+                values
+            }
+            Instruction::CallMethod {
+                component_address,
+                method,
+                args,
+            } => {
+                let mut values = vec![component_address, method];
+                values.extend(args.iter_mut());
+                values
+            }
+            Instruction::AssertContextContains {
+                amount,
+                resource_address,
+            } => vec![amount, resource_address],
+            Instruction::AssertBucketAmountAtLeast { bucket, amount } => vec![bucket, amount],
+            Instruction::DepositAllBuckets { account } => vec![account],
+            Instruction::PublishPackage { code } => vec![code],
+            Instruction::End { signers } => vec![signers],
+        }
+    }
 }
 
 impl Value {
+    /// Returns the type of this value.
+    ///
+    /// Panics on variable references, which are substituted before any type is inspected.
     pub const fn kind(&self) -> Type {
         match self {
             Value::Unit => Type::Unit,
@@ -139,7 +286,11 @@ impl Value {
             Value::U128(_) => Type::U128,
             Value::String(_) => Type::String,
             Value::Struct(_) => Type::Struct,
-            Value::Enum(_, _) => Type::Enum,
+            Value::Enum(discriminator, _) => match discriminator {
+                Discriminator::U8(_) => Type::Enum,
+                Discriminator::U16(_) => Type::EnumU16,
+                Discriminator::String(_) => Type::EnumString,
+            },
             Value::Option(_) => Type::Option,
             Value::Box(_) => Type::Box,
             Value::Array(_, _) => Type::Array,
@@ -151,13 +302,15 @@ impl Value {
             Value::HashSet(_, _) => Type::HashSet,
             Value::HashMap(_, _, _) => Type::HashMap,
             Value::Decimal(_) => Type::Decimal,
-            Value::BigDecimal(_) => Type::BigDecimal,
             Value::Address(_) => Type::Address,
             Value::Hash(_) => Type::Hash,
             Value::Bucket(_) => Type::Bucket,
             Value::BucketRef(_) => Type::BucketRef,
             Value::LazyMap(_) => Type::LazyMap,
             Value::Vault(_) => Type::Vault,
+            Value::Bytes(_) => Type::Bytes,
+            Value::Blob(_) => Type::Blob,
+            Value::Variable(_) => panic!("unresolved variable"),
         }
     }
 }
@@ -1,22 +1,49 @@
 use crate::ast;
+use crate::lexer::Span;
 use radix_engine::transaction::*;
 use sbor::any::{encode_any, Fields, Value};
 use sbor::type_id::*;
 use sbor::Encoder;
 use scrypto::buffer::*;
 use scrypto::types::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum CompilerError {
+pub struct CompilerError {
+    pub kind: CompilerErrorKind,
+    /// The span of the offending instruction or operand
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompilerErrorKind {
     WrongTypeOfValue {
         expected_type: Vec<ast::Type>,
         actual: ast::Value,
     },
     InvalidAddress(String),
     InvalidDecimal(String),
+    InvalidAmount(String),
     OddNumberOfElements(usize),
+    FailedToExportAbi(Address),
+    FunctionNotFound(String),
+    MethodNotFound(String),
+    WrongNumberOfArguments {
+        expected: usize,
+        actual: usize,
+    },
+    ArgumentTypeMismatch {
+        index: usize,
+        expected: sbor::describe::Type,
+    },
+    UndefinedVariable(String),
+    VariableAlreadyDefined(String),
+    InvalidHash(String),
+    InvalidBytes(String),
+    BlobNotFound(String),
+    MissingParameter(String),
+    InvalidParameter(String, ast::Type),
 }
 
 pub struct NameResolver {
@@ -72,12 +99,266 @@ impl NameResolver {
     }
 }
 
+impl Default for NameResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Binds the declared parameters of a manifest, returning their values along with the variables.
+///
+/// A parameter takes its value either from `args`, as text read according to the declared type,
+/// or from `variables`, as a value that must already have that type.
+pub fn bind_parameters(
+    tx: &ast::Transaction,
+    variables: &HashMap<String, ast::Value>,
+    args: &HashMap<String, String>,
+) -> Result<HashMap<String, ast::Value>, CompilerError> {
+    let mut bound = variables.clone();
+    let mut declared = HashSet::new();
+
+    for parameter in &tx.parameters {
+        let error = |kind| CompilerError {
+            kind,
+            span: parameter.span.clone(),
+        };
+        if !declared.insert(&parameter.name) {
+            return Err(error(CompilerErrorKind::VariableAlreadyDefined(
+                parameter.name.clone(),
+            )));
+        }
+
+        let value = match (args.get(&parameter.name), variables.get(&parameter.name)) {
+            (Some(text), _) => parameter_value(parameter.ty, text),
+            (None, Some(value)) => Some(value.clone()),
+            (None, None) => {
+                return Err(error(CompilerErrorKind::MissingParameter(
+                    parameter.name.clone(),
+                )));
+            }
+        };
+        match value {
+            Some(value) if has_type(&value, parameter.ty) => {
+                bound.insert(parameter.name.clone(), value);
+            }
+            _ => {
+                return Err(error(CompilerErrorKind::InvalidParameter(
+                    parameter.name.clone(),
+                    parameter.ty,
+                )));
+            }
+        }
+    }
+
+    Ok(bound)
+}
+
+/// Reads a parameter argument, which is either a manifest value or the text of one of the given
+/// type, such as `100` for a `Decimal`.
+fn parameter_value(ty: ast::Type, text: &str) -> Option<ast::Value> {
+    if let Ok(value) = crate::parse_value(text) {
+        if matches!(value, ast::Value::Variable(_)) || value.kind() == ty {
+            return Some(value);
+        }
+    }
+
+    let string = || ast::Value::String(text.to_owned()).into();
+    match ty {
+        ast::Type::Bool => text.parse().ok().map(ast::Value::Bool),
+        ast::Type::I8 => text.parse().ok().map(ast::Value::I8),
+        ast::Type::I16 => text.parse().ok().map(ast::Value::I16),
+        ast::Type::I32 => text.parse().ok().map(ast::Value::I32),
+        ast::Type::I64 => text.parse().ok().map(ast::Value::I64),
+        ast::Type::I128 => text.parse().ok().map(ast::Value::I128),
+        ast::Type::U8 => text.parse().ok().map(ast::Value::U8),
+        ast::Type::U16 => text.parse().ok().map(ast::Value::U16),
+        ast::Type::U32 => text.parse().ok().map(ast::Value::U32),
+        ast::Type::U64 => text.parse().ok().map(ast::Value::U64),
+        ast::Type::U128 => text.parse().ok().map(ast::Value::U128),
+        ast::Type::String => Some(ast::Value::String(text.to_owned())),
+        ast::Type::Decimal => Some(ast::Value::Decimal(string())),
+        ast::Type::Address => Some(ast::Value::Address(string())),
+        ast::Type::Hash => Some(ast::Value::Hash(string())),
+        ast::Type::Bucket => Some(ast::Value::Bucket(string())),
+        ast::Type::BucketRef => Some(ast::Value::BucketRef(string())),
+        ast::Type::LazyMap => Some(ast::Value::LazyMap(string())),
+        ast::Type::Vault => Some(ast::Value::Vault(string())),
+        ast::Type::Bytes => Some(ast::Value::Bytes(string())),
+        ast::Type::Blob => Some(ast::Value::Blob(string())),
+        _ => None,
+    }
+}
+
+/// Checks that a value has the given type and is well-formed, e.g. a `Decimal` holds a number.
+///
+/// Variables are substituted, and blobs resolved, later; they're checked at that point.
+fn has_type(value: &ast::Value, ty: ast::Type) -> bool {
+    if matches!(value, ast::Value::Variable(_)) {
+        return true;
+    }
+    value.kind() == ty
+        && match compile_value(value, None, &mut NameResolver::new()) {
+            Ok(_) => true,
+            Err(CompilerErrorKind::UndefinedVariable(_) | CompilerErrorKind::BlobNotFound(_)) => {
+                true
+            }
+            Err(_) => false,
+        }
+}
+
+/// Replaces `$name` references with the values bound by `LET` or given as variables, and
+/// removes the `LET` bindings.
+///
+/// A name may only be bound once, and must be bound before it's referenced.
+pub fn substitute_variables(
+    tx: &ast::Transaction,
+    variables: &HashMap<String, ast::Value>,
+) -> Result<ast::Transaction, CompilerError> {
+    let mut bound = variables.clone();
+    let mut instructions = Vec::new();
+    let mut spans = Vec::new();
+
+    for (instruction, span) in tx.instructions.iter().zip(&tx.spans) {
+        let mut instruction = instruction.clone();
+        for (value, arg_span) in instruction.values_mut().into_iter().zip(&span.args) {
+            visit_values(value, &mut |v| substitute_value(v, &bound)).map_err(|kind| {
+                CompilerError {
+                    kind,
+                    span: arg_span.clone(),
+                }
+            })?;
+        }
+
+        if let ast::Instruction::Let { name, value } = instruction {
+            if bound.contains_key(&name) {
+                return Err(CompilerError {
+                    kind: CompilerErrorKind::VariableAlreadyDefined(name),
+                    span: span.span.clone(),
+                });
+            }
+            bound.insert(name, value);
+        } else {
+            instructions.push(instruction);
+            spans.push(span.clone());
+        }
+    }
+
+    Ok(ast::Transaction {
+        parameters: tx.parameters.clone(),
+        instructions,
+        spans,
+    })
+}
+
+/// Replaces `Blob("<hash>")` values with the bytes of the attached blob with that hash.
+pub fn resolve_blobs(
+    tx: &ast::Transaction,
+    blobs: &HashMap<H256, Vec<u8>>,
+) -> Result<ast::Transaction, CompilerError> {
+    let mut tx = tx.clone();
+    for (instruction, span) in tx.instructions.iter_mut().zip(&tx.spans) {
+        for (value, arg_span) in instruction.values_mut().into_iter().zip(&span.args) {
+            visit_values(value, &mut |v| resolve_blob(v, blobs)).map_err(|kind| CompilerError {
+                kind,
+                span: arg_span.clone(),
+            })?;
+        }
+    }
+    Ok(tx)
+}
+
+fn substitute_value(
+    value: &mut ast::Value,
+    bound: &HashMap<String, ast::Value>,
+) -> Result<(), CompilerErrorKind> {
+    if let ast::Value::Variable(name) = value {
+        *value = bound
+            .get(name)
+            .cloned()
+            .ok_or_else(|| CompilerErrorKind::UndefinedVariable(name.clone()))?;
+    }
+    Ok(())
+}
+
+fn resolve_blob(
+    value: &mut ast::Value,
+    blobs: &HashMap<H256, Vec<u8>>,
+) -> Result<(), CompilerErrorKind> {
+    if let ast::Value::Blob(inner) = value {
+        let s = match &**inner {
+            ast::Value::String(s) => s,
+            v => {
+                return Err(CompilerErrorKind::WrongTypeOfValue {
+                    expected_type: vec![ast::Type::String],
+                    actual: v.clone(),
+                });
+            }
+        };
+        let hash = H256::from_str(s).map_err(|_| CompilerErrorKind::InvalidHash(s.clone()))?;
+        let bytes = blobs
+            .get(&hash)
+            .ok_or_else(|| CompilerErrorKind::BlobNotFound(s.clone()))?;
+        *value = ast::Value::Bytes(ast::Value::String(hex::encode(bytes)).into());
+    }
+    Ok(())
+}
+
+/// Applies `f` to every value nested within the given one, and then to the value itself.
+///
+/// Values produced by `f` aren't visited again, so a substitution can never recurse forever.
+pub(crate) fn visit_values<F>(value: &mut ast::Value, f: &mut F) -> Result<(), CompilerErrorKind>
+where
+    F: FnMut(&mut ast::Value) -> Result<(), CompilerErrorKind>,
+{
+    match value {
+        ast::Value::Struct(fields) | ast::Value::Enum(_, fields) => match fields {
+            ast::Fields::Named(elements) | ast::Fields::Unnamed(elements) => {
+                for e in elements {
+                    visit_values(e, f)?;
+                }
+            }
+            ast::Fields::Unit => {}
+        },
+        ast::Value::Option(v) => {
+            if let Some(inner) = v.as_mut() {
+                visit_values(inner, f)?;
+            }
+        }
+        ast::Value::Result(v) => match v.as_mut() {
+            Ok(inner) | Err(inner) => visit_values(inner, f)?,
+        },
+        ast::Value::Array(_, elements)
+        | ast::Value::Tuple(elements)
+        | ast::Value::Vec(_, elements)
+        | ast::Value::TreeSet(_, elements)
+        | ast::Value::TreeMap(_, _, elements)
+        | ast::Value::HashSet(_, elements)
+        | ast::Value::HashMap(_, _, elements) => {
+            for e in elements {
+                visit_values(e, f)?;
+            }
+        }
+        ast::Value::Box(inner)
+        | ast::Value::Decimal(inner)
+        | ast::Value::Address(inner)
+        | ast::Value::Hash(inner)
+        | ast::Value::Bucket(inner)
+        | ast::Value::BucketRef(inner)
+        | ast::Value::LazyMap(inner)
+        | ast::Value::Vault(inner)
+        | ast::Value::Bytes(inner)
+        | ast::Value::Blob(inner) => visit_values(inner, f)?,
+        _ => {}
+    }
+    f(value)
+}
+
 pub fn compile_transaction(tx: &ast::Transaction) -> Result<Transaction, CompilerError> {
     let mut name_resolver = NameResolver::new();
     let mut other_instructions = Vec::new();
 
-    for instruction in &tx.instructions {
-        if let Some(i) = compile_instruction(instruction, &mut name_resolver)? {
+    for (instruction, span) in tx.instructions.iter().zip(&tx.spans) {
+        if let Some(i) = compile_instruction(instruction, span, &mut name_resolver)? {
             other_instructions.push(i);
         }
     }
@@ -89,9 +370,16 @@ pub fn compile_transaction(tx: &ast::Transaction) -> Result<Transaction, Compile
 
 pub fn compile_instruction(
     instruction: &ast::Instruction,
+    span: &ast::InstructionSpan,
     resolver: &mut NameResolver,
 ) -> Result<Option<Instruction>, CompilerError> {
+    let at = |i: usize| {
+        let span = span.args[i].clone();
+        move |kind: CompilerErrorKind| CompilerError { kind, span }
+    };
     Ok(match instruction {
+        // Bindings are removed by `substitute_variables`
+        ast::Instruction::Let { .. } => None,
         ast::Instruction::DeclareTempBucket => {
             resolver.new_bucket();
             None
@@ -105,18 +393,38 @@ pub fn compile_instruction(
             resource_address,
             to,
         } => Some(Instruction::TakeFromContext {
-            amount: compile_decimal(amount)?,
-            resource_address: compile_address(resource_address)?,
-            to: compile_bucket(to, resolver)?,
+            amount: compile_amount(amount).map_err(at(0))?,
+            resource_def: compile_address(resource_address).map_err(at(1))?,
+            to: compile_bucket(to, resolver).map_err(at(2))?,
+        }),
+        ast::Instruction::TakeAllFromContext {
+            resource_address,
+            to,
+        } => Some(Instruction::TakeAllFromContext {
+            resource_def: compile_address(resource_address).map_err(at(0))?,
+            to: compile_bucket(to, resolver).map_err(at(1))?,
+        }),
+        ast::Instruction::ReturnToContext { bucket } => Some(Instruction::ReturnToContext {
+            bucket: compile_bucket(bucket, resolver).map_err(at(0))?,
         }),
         ast::Instruction::BorrowFromContext {
             amount,
             resource_address,
             to,
         } => Some(Instruction::BorrowFromContext {
-            amount: compile_decimal(amount)?,
-            resource_address: compile_address(resource_address)?,
-            to: compile_bucket_ref(to, resolver)?,
+            amount: compile_amount(amount).map_err(at(0))?,
+            resource_def: compile_address(resource_address).map_err(at(1))?,
+            to: compile_bucket_ref(to, resolver).map_err(at(2))?,
+        }),
+        ast::Instruction::CreateBucketRefFromBucket { bucket, to } => {
+            Some(Instruction::CreateBucketRefFromBucket {
+                bucket: compile_bucket(bucket, resolver).map_err(at(0))?,
+                to: compile_bucket_ref(to, resolver).map_err(at(1))?,
+            })
+        }
+        ast::Instruction::CloneBucketRef { bucket_ref, to } => Some(Instruction::CloneBucketRef {
+            bucket_ref: compile_bucket_ref(bucket_ref, resolver).map_err(at(0))?,
+            to: compile_bucket_ref(to, resolver).map_err(at(1))?,
         }),
         ast::Instruction::CallFunction {
             package_address,
@@ -124,23 +432,45 @@ pub fn compile_instruction(
             function,
             args,
         } => Some(Instruction::CallFunction {
-            package_address: compile_address(package_address)?,
-            blueprint_name: compile_string(blueprint_name)?,
-            function: compile_string(function)?,
-            args: compile_args(args, resolver)?,
+            package: compile_address(package_address).map_err(at(0))?,
+            name: compile_string(blueprint_name).map_err(at(1))?,
+            function: compile_string(function).map_err(at(2))?,
+            args: compile_args(args, &span.args[3..], resolver)?,
         }),
         ast::Instruction::CallMethod {
             component_address,
             method,
             args,
         } => Some(Instruction::CallMethod {
-            component_address: compile_address(component_address)?,
-            method: compile_string(method)?,
-            args: compile_args(args, resolver)?,
+            component: compile_address(component_address).map_err(at(0))?,
+            method: compile_string(method).map_err(at(1))?,
+            args: compile_args(args, &span.args[2..], resolver)?,
+        }),
+        ast::Instruction::AssertContextContains {
+            amount,
+            resource_address,
+        } => Some(Instruction::AssertContextContains {
+            amount: compile_amount(amount).map_err(at(0))?,
+            resource_def: compile_address(resource_address).map_err(at(1))?,
         }),
+        ast::Instruction::AssertBucketAmountAtLeast { bucket, amount } => {
+            Some(Instruction::AssertBucketAmountAtLeast {
+                bucket: compile_bucket(bucket, resolver).map_err(at(0))?,
+                amount: compile_amount(amount).map_err(at(1))?,
+            })
+        }
         ast::Instruction::DropAllBucketRefs => Some(Instruction::DropAllBucketRefs),
         ast::Instruction::DepositAllBuckets { account } => Some(Instruction::DepositAllBuckets {
-            account: compile_address(account)?,
+            account: compile_address(account).map_err(at(0))?,
+        }),
+        ast::Instruction::PublishPackage { code } => Some(Instruction::CallFunction {
+            package: SYSTEM_PACKAGE,
+            name: "System".to_owned(),
+            function: "publish_package".to_owned(),
+            args: vec![SmartValue::from(compile_bytes(code).map_err(at(0))?)],
+        }),
+        ast::Instruction::End { signers } => Some(Instruction::End {
+            signers: compile_signers(signers).map_err(at(0))?,
         }),
     })
 }
@@ -148,7 +478,7 @@ pub fn compile_instruction(
 #[macro_export]
 macro_rules! invalid_type {
     ( $v:expr, $($exp:expr),+ ) => {
-        Err(CompilerError::WrongTypeOfValue {
+        Err(CompilerErrorKind::WrongTypeOfValue {
             expected_type: vec!($($exp),+),
             actual: $v.clone(),
         })
@@ -156,121 +486,151 @@ macro_rules! invalid_type {
 }
 
 fn compile_args(
-    values: &Vec<ast::Value>,
+    values: &[ast::Value],
+    spans: &[Span],
     resolver: &mut NameResolver,
-) -> Result<Vec<Vec<u8>>, CompilerError> {
+) -> Result<Vec<SmartValue>, CompilerError> {
     let mut result = Vec::new();
-    for v in values {
-        let value = compile_value(v, None, resolver)?;
+    for (v, span) in values.iter().zip(spans) {
+        let value = compile_value(v, None, resolver).map_err(|kind| CompilerError {
+            kind,
+            span: span.clone(),
+        })?;
 
         let mut enc = Encoder::with_type(Vec::new());
         encode_any(None, &value, &mut enc);
-        result.push(enc.into());
+        result.push(SmartValue {
+            encoded: enc.into(),
+        });
     }
     Ok(result)
 }
 
-fn compile_string(value: &ast::Value) -> Result<String, CompilerError> {
+pub(crate) fn compile_string(value: &ast::Value) -> Result<String, CompilerErrorKind> {
     match value {
         ast::Value::String(s) => Ok(s.into()),
-        v @ _ => invalid_type!(v, ast::Type::String),
+        v => invalid_type!(v, ast::Type::String),
     }
 }
 
-fn compile_decimal(value: &ast::Value) -> Result<Decimal, CompilerError> {
+/// Compiles a resource amount, written as a `Decimal` with no fractional part.
+fn compile_amount(value: &ast::Value) -> Result<Amount, CompilerErrorKind> {
     match value {
         ast::Value::Decimal(inner) => match &**inner {
             ast::Value::String(s) => {
-                Decimal::from_str(s).map_err(|_| CompilerError::InvalidDecimal(s.into()))
+                let whole = match s.split_once('.') {
+                    Some((whole, fraction)) if fraction.chars().all(|c| c == '0') => whole,
+                    Some(_) => return Err(CompilerErrorKind::InvalidAmount(s.into())),
+                    None => s,
+                };
+                Amount::from_str(whole).map_err(|_| CompilerErrorKind::InvalidAmount(s.into()))
             }
-            v @ _ => invalid_type!(v, ast::Type::String),
+            v => invalid_type!(v, ast::Type::String),
         },
-        v @ _ => invalid_type!(v, ast::Type::Decimal),
+        v => invalid_type!(v, ast::Type::Decimal),
     }
 }
 
-fn compile_big_decimal(value: &ast::Value) -> Result<BigDecimal, CompilerError> {
+pub(crate) fn compile_address(value: &ast::Value) -> Result<Address, CompilerErrorKind> {
     match value {
-        ast::Value::BigDecimal(inner) => match &**inner {
+        ast::Value::Address(inner) => match &**inner {
             ast::Value::String(s) => {
-                BigDecimal::from_str(s).map_err(|_| CompilerError::InvalidDecimal(s.into()))
+                Address::from_str(s).map_err(|_| CompilerErrorKind::InvalidAddress(s.into()))
             }
-            v @ _ => invalid_type!(v, ast::Type::String),
+            v => invalid_type!(v, ast::Type::String),
         },
-        v @ _ => invalid_type!(v, ast::Type::BigDecimal),
+        v => invalid_type!(v, ast::Type::Address),
     }
 }
 
-fn compile_address(value: &ast::Value) -> Result<Address, CompilerError> {
+fn compile_hash(value: &ast::Value) -> Result<H256, CompilerErrorKind> {
     match value {
-        ast::Value::Address(inner) => match &**inner {
+        ast::Value::Hash(inner) => match &**inner {
             ast::Value::String(s) => {
-                Address::from_str(s).map_err(|_| CompilerError::InvalidAddress(s.into()))
+                H256::from_str(s).map_err(|_| CompilerErrorKind::InvalidDecimal(s.into()))
             }
-            v @ _ => invalid_type!(v, ast::Type::String),
+            v => invalid_type!(v, ast::Type::String),
         },
-        v @ _ => invalid_type!(v, ast::Type::Address),
+        v => invalid_type!(v, ast::Type::Decimal),
     }
 }
 
-fn compile_hash(value: &ast::Value) -> Result<H256, CompilerError> {
+fn compile_signers(value: &ast::Value) -> Result<Vec<Address>, CompilerErrorKind> {
     match value {
-        ast::Value::Hash(inner) => match &**inner {
-            ast::Value::String(s) => {
-                H256::from_str(s).map_err(|_| CompilerError::InvalidDecimal(s.into()))
-            }
-            v @ _ => invalid_type!(v, ast::Type::String),
-        },
-        v @ _ => invalid_type!(v, ast::Type::Decimal),
+        ast::Value::Vec(ast::Type::Address, elements) => {
+            elements.iter().map(compile_address).collect()
+        }
+        v => invalid_type!(v, ast::Type::Vec),
     }
 }
 
-fn compile_bucket(value: &ast::Value, resolver: &mut NameResolver) -> Result<Bid, CompilerError> {
+fn compile_bucket(
+    value: &ast::Value,
+    resolver: &mut NameResolver,
+) -> Result<Bid, CompilerErrorKind> {
     match value {
         ast::Value::Bucket(inner) => match &**inner {
             ast::Value::U32(n) => Ok(Bid(*n)),
             ast::Value::String(s) => Ok(resolver.resolve_bucket(s)),
-            v @ _ => invalid_type!(v, ast::Type::U32, ast::Type::String),
+            v => invalid_type!(v, ast::Type::U32, ast::Type::String),
         },
-        v @ _ => invalid_type!(v, ast::Type::Bucket),
+        v => invalid_type!(v, ast::Type::Bucket),
     }
 }
 
 fn compile_bucket_ref(
     value: &ast::Value,
     resolver: &mut NameResolver,
-) -> Result<Rid, CompilerError> {
+) -> Result<Rid, CompilerErrorKind> {
     match value {
         ast::Value::BucketRef(inner) => match &**inner {
             ast::Value::U32(n) => Ok(Rid(*n)),
             ast::Value::String(s) => Ok(resolver.resolve_bucket_ref(s)),
-            v @ _ => invalid_type!(v, ast::Type::U32, ast::Type::String),
+            v => invalid_type!(v, ast::Type::U32, ast::Type::String),
         },
-        v @ _ => invalid_type!(v, ast::Type::BucketRef),
+        v => invalid_type!(v, ast::Type::BucketRef),
     }
 }
 
-fn compile_lazy_map(value: &ast::Value) -> Result<Mid, CompilerError> {
+fn compile_lazy_map(value: &ast::Value) -> Result<Mid, CompilerErrorKind> {
     match value {
         ast::Value::LazyMap(inner) => match &**inner {
-            ast::Value::String(s) => {
-                Mid::from_str(s).map_err(|_| CompilerError::InvalidDecimal(s.into()))
-            }
-            v @ _ => invalid_type!(v, ast::Type::String),
+            ast::Value::String(s) => hex::decode(s)
+                .ok()
+                .and_then(|v| Mid::try_from(v.as_slice()).ok())
+                .ok_or_else(|| CompilerErrorKind::InvalidDecimal(s.into())),
+            v => invalid_type!(v, ast::Type::String),
         },
-        v @ _ => invalid_type!(v, ast::Type::LazyMap),
+        v => invalid_type!(v, ast::Type::LazyMap),
     }
 }
 
-fn compile_vault(value: &ast::Value) -> Result<Vid, CompilerError> {
+fn compile_vault(value: &ast::Value) -> Result<Vid, CompilerErrorKind> {
     match value {
         ast::Value::Vault(inner) => match &**inner {
+            ast::Value::String(s) => hex::decode(s)
+                .ok()
+                .and_then(|v| Vid::try_from(v.as_slice()).ok())
+                .ok_or_else(|| CompilerErrorKind::InvalidDecimal(s.into())),
+            v => invalid_type!(v, ast::Type::String),
+        },
+        v => invalid_type!(v, ast::Type::Vault),
+    }
+}
+
+fn compile_bytes(value: &ast::Value) -> Result<Vec<u8>, CompilerErrorKind> {
+    match value {
+        ast::Value::Bytes(inner) => match &**inner {
             ast::Value::String(s) => {
-                Vid::from_str(s).map_err(|_| CompilerError::InvalidDecimal(s.into()))
+                hex::decode(s).map_err(|_| CompilerErrorKind::InvalidBytes(s.into()))
             }
-            v @ _ => invalid_type!(v, ast::Type::String),
+            v => invalid_type!(v, ast::Type::String),
+        },
+        ast::Value::Blob(inner) => match &**inner {
+            ast::Value::String(s) => Err(CompilerErrorKind::BlobNotFound(s.into())),
+            v => invalid_type!(v, ast::Type::String),
         },
-        v @ _ => invalid_type!(v, ast::Type::Vault),
+        v => invalid_type!(v, ast::Type::Bytes, ast::Type::Blob),
     }
 }
 
@@ -278,10 +638,13 @@ fn compile_value(
     value: &ast::Value,
     expected: Option<ast::Type>,
     resolver: &mut NameResolver,
-) -> Result<Value, CompilerError> {
+) -> Result<Value, CompilerErrorKind> {
+    if let ast::Value::Variable(name) = value {
+        return Err(CompilerErrorKind::UndefinedVariable(name.clone()));
+    }
     if let Some(ty) = expected {
         if ty != value.kind() {
-            return Err(CompilerError::WrongTypeOfValue {
+            return Err(CompilerErrorKind::WrongTypeOfValue {
                 expected_type: vec![ty],
                 actual: value.clone(),
             });
@@ -303,9 +666,10 @@ fn compile_value(
         ast::Value::U128(v) => Ok(Value::U128(*v)),
         ast::Value::String(v) => Ok(Value::String(v.clone())),
         ast::Value::Struct(fields) => Ok(Value::Struct(compile_fields(fields, resolver)?)),
-        ast::Value::Enum(index, fields) => {
-            Ok(Value::Enum(*index, compile_fields(fields, resolver)?))
-        }
+        ast::Value::Enum(discriminator, fields) => Ok(Value::Enum(
+            discriminator.clone(),
+            compile_fields(fields, resolver)?,
+        )),
         ast::Value::Option(v) => match &**v {
             Some(inner) => Ok(Value::Option(
                 Some(compile_value(inner, None, resolver)?).into(),
@@ -351,10 +715,7 @@ fn compile_value(
             compile_pairs(elements, *key_type, *value_type, resolver)?,
         )),
         ast::Value::Decimal(_) => {
-            compile_decimal(value).map(|v| Value::Custom(SCRYPTO_TYPE_DECIMAL, v.to_vec()))
-        }
-        ast::Value::BigDecimal(_) => {
-            compile_big_decimal(value).map(|v| Value::Custom(SCRYPTO_TYPE_BIG_DECIMAL, v.to_vec()))
+            compile_amount(value).map(|v| Value::Custom(SCRYPTO_TYPE_AMOUNT, v.to_vec()))
         }
         ast::Value::Address(_) => {
             compile_address(value).map(|v| Value::Custom(SCRYPTO_TYPE_ADDRESS, v.to_vec()))
@@ -374,13 +735,16 @@ fn compile_value(
         ast::Value::Vault(_) => {
             compile_vault(value).map(|v| Value::Custom(SCRYPTO_TYPE_VID, v.to_vec()))
         }
+        ast::Value::Bytes(_) | ast::Value::Blob(_) => compile_bytes(value)
+            .map(|v| Value::Vec(TYPE_U8, v.into_iter().map(Value::U8).collect())),
+        ast::Value::Variable(name) => Err(CompilerErrorKind::UndefinedVariable(name.clone())),
     }
 }
 
 fn compile_fields(
     value: &ast::Fields,
     resolver: &mut NameResolver,
-) -> Result<Fields, CompilerError> {
+) -> Result<Fields, CompilerErrorKind> {
     match value {
         ast::Fields::Named(fields) => {
             Ok(Fields::Named(compile_singletons(fields, None, resolver)?))
@@ -396,7 +760,7 @@ fn compile_singletons(
     elements: &Vec<ast::Value>,
     ty: Option<ast::Type>,
     resolver: &mut NameResolver,
-) -> Result<Vec<Value>, CompilerError> {
+) -> Result<Vec<Value>, CompilerErrorKind> {
     let mut result = vec![];
     for element in elements {
         result.push(compile_value(element, ty, resolver)?);
@@ -405,13 +769,13 @@ fn compile_singletons(
 }
 
 fn compile_pairs(
-    elements: &Vec<ast::Value>,
+    elements: &[ast::Value],
     key_type: ast::Type,
     value_type: ast::Type,
     resolver: &mut NameResolver,
-) -> Result<Vec<(Value, Value)>, CompilerError> {
-    if elements.len() % 2 != 0 {
-        return Err(CompilerError::OddNumberOfElements(elements.len()));
+) -> Result<Vec<(Value, Value)>, CompilerErrorKind> {
+    if elements.len() % 2 == 1 {
+        return Err(CompilerErrorKind::OddNumberOfElements(elements.len()));
     }
     let mut result = vec![];
     for i in 0..elements.len() / 2 {
@@ -440,6 +804,8 @@ fn compile_type(ty: &ast::Type) -> u8 {
         ast::Type::String => TYPE_STRING,
         ast::Type::Struct => TYPE_STRUCT,
         ast::Type::Enum => TYPE_ENUM,
+        ast::Type::EnumU16 => TYPE_ENUM_U16,
+        ast::Type::EnumString => TYPE_ENUM_STRING,
         ast::Type::Option => TYPE_OPTION,
         ast::Type::Box => TYPE_BOX,
         ast::Type::Array => TYPE_ARRAY,
@@ -450,14 +816,14 @@ fn compile_type(ty: &ast::Type) -> u8 {
         ast::Type::TreeMap => TYPE_TREE_MAP,
         ast::Type::HashSet => TYPE_HASH_SET,
         ast::Type::HashMap => TYPE_HASH_MAP,
-        ast::Type::Decimal => SCRYPTO_TYPE_DECIMAL,
-        ast::Type::BigDecimal => SCRYPTO_TYPE_BIG_DECIMAL,
+        ast::Type::Decimal => SCRYPTO_TYPE_AMOUNT,
         ast::Type::Address => SCRYPTO_TYPE_ADDRESS,
         ast::Type::Hash => SCRYPTO_TYPE_H256,
         ast::Type::Bucket => SCRYPTO_TYPE_BID,
         ast::Type::BucketRef => SCRYPTO_TYPE_RID,
         ast::Type::LazyMap => SCRYPTO_TYPE_MID,
         ast::Type::Vault => SCRYPTO_TYPE_VID,
+        ast::Type::Bytes | ast::Type::Blob => TYPE_VEC,
     }
 }
 
@@ -466,6 +832,8 @@ mod tests {
     use super::*;
     use crate::lexer::tokenize;
     use crate::parser::Parser;
+    use sbor::describe::Discriminator;
+    use scrypto::utils::sha256;
 
     #[macro_export]
     macro_rules! compile_value_ok {
@@ -480,12 +848,12 @@ mod tests {
     #[macro_export]
     macro_rules! compile_instruction_ok {
         ( $s:expr, $expected:expr, $allocations:expr ) => {{
-            let instruction = Parser::new(tokenize($s).unwrap())
-                .parse_instruction()
+            let tx = Parser::new(tokenize($s).unwrap())
+                .parse_transaction()
                 .unwrap();
             let mut resolver = NameResolver::new();
             assert_eq!(
-                compile_instruction(&instruction, &mut resolver),
+                compile_instruction(&tx.instructions[0], &tx.spans[0], &mut resolver),
                 Ok($expected)
             );
             assert_eq!(resolver.instructions(), $allocations);
@@ -529,16 +897,9 @@ mod tests {
             ]
         );
         compile_value_ok!(
-            r#"Struct((Decimal("1.0"), BigDecimal("2.0"), Hash("aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c"), Vault("aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c00000001"), LazyMap("aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c00000002")))"#,
+            r#"Struct((Decimal("1.0"), Hash("aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c"), Vault("aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c00000001"), LazyMap("aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c00000002")))"#,
             Value::Struct(Fields::Unnamed(vec![
-                Value::Custom(
-                    SCRYPTO_TYPE_DECIMAL,
-                    Decimal::from_str("1.0").unwrap().to_vec()
-                ),
-                Value::Custom(
-                    SCRYPTO_TYPE_BIG_DECIMAL,
-                    BigDecimal::from_str("2.0").unwrap().to_vec()
-                ),
+                Value::Custom(SCRYPTO_TYPE_AMOUNT, Amount::from(1u32).to_vec()),
                 Value::Custom(
                     SCRYPTO_TYPE_H256,
                     H256::from_str(
@@ -549,19 +910,17 @@ mod tests {
                 ),
                 Value::Custom(
                     SCRYPTO_TYPE_VID,
-                    Vid::from_str(
+                    hex::decode(
                         "aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c00000001"
                     )
                     .unwrap()
-                    .to_vec()
                 ),
                 Value::Custom(
                     SCRYPTO_TYPE_MID,
-                    Mid::from_str(
+                    hex::decode(
                         "aa37f5a71083a9aa044fb936678bfd74f848e930d2de482a49a73540ea72aa5c00000002"
                     )
                     .unwrap()
-                    .to_vec()
                 ),
             ])),
             vec![]
@@ -569,15 +928,19 @@ mod tests {
         compile_value_ok!(r#"Struct()"#, Value::Struct(Fields::Unit), vec![]);
         compile_value_ok!(
             r#"Enum(0u8, {})"#,
-            Value::Enum(0, Fields::Named(vec![])),
+            Value::Enum(Discriminator::U8(0), Fields::Named(vec![])),
             vec![]
         );
         compile_value_ok!(
             r#"Enum(1u8, ())"#,
-            Value::Enum(1, Fields::Unnamed(vec![])),
+            Value::Enum(Discriminator::U8(1), Fields::Unnamed(vec![])),
+            vec![]
+        );
+        compile_value_ok!(
+            r#"Enum(2u8)"#,
+            Value::Enum(Discriminator::U8(2), Fields::Unit),
             vec![]
         );
-        compile_value_ok!(r#"Enum(2u8)"#, Value::Enum(2, Fields::Unit), vec![]);
         compile_value_ok!(
             r#"Box(Some("value"))"#,
             Value::Box(Value::Option(Some(Value::String("value".into())).into()).into()),
@@ -632,22 +995,22 @@ mod tests {
     fn test_failures() {
         compile_value_error!(
             r#"Address(100u32)"#,
-            CompilerError::WrongTypeOfValue {
+            CompilerErrorKind::WrongTypeOfValue {
                 expected_type: vec![ast::Type::String],
                 actual: ast::Value::U32(100),
             }
         );
         compile_value_error!(
             r#"Address("invalid_address")"#,
-            CompilerError::InvalidAddress("invalid_address".into())
+            CompilerErrorKind::InvalidAddress("invalid_address".into())
         );
         compile_value_error!(
             r#"Decimal("invalid_decimal")"#,
-            CompilerError::InvalidDecimal("invalid_decimal".into())
+            CompilerErrorKind::InvalidAmount("invalid_decimal".into())
         );
         compile_value_error!(
             r#"HashMap<String, String>("abc")"#,
-            CompilerError::OddNumberOfElements(1)
+            CompilerErrorKind::OddNumberOfElements(1)
         );
     }
 
@@ -666,8 +1029,8 @@ mod tests {
         compile_instruction_ok!(
             r#"TAKE_FROM_CONTEXT  Decimal("1.0")  Address("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d")  Bucket("xrd_bucket");"#,
             Some(Instruction::TakeFromContext {
-                amount: Decimal::from(1),
-                resource_address: Address::from_str(
+                amount: Amount::from(1u32),
+                resource_def: Address::from_str(
                     "03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d"
                 )
                 .unwrap(),
@@ -678,27 +1041,65 @@ mod tests {
         compile_instruction_ok!(
             r#"BORROW_FROM_CONTEXT  Decimal("1.0")  Address("03559905076cb3d4b9312640393a7bc6e1d4e491a8b1b62fa73a94")  BucketRef("admin_auth");"#,
             Some(Instruction::BorrowFromContext {
-                amount: Decimal::from(1),
-                resource_address: Address::from_str(
-                    "03559905076cb3d4b9312640393a7bc6e1d4e491a8b1b62fa73a94".into()
+                amount: Amount::from(1u32),
+                resource_def: Address::from_str(
+                    "03559905076cb3d4b9312640393a7bc6e1d4e491a8b1b62fa73a94"
                 )
                 .unwrap(),
                 to: Rid(0),
             }),
             vec![Instruction::DeclareTempBucketRef]
         );
+        compile_instruction_ok!(
+            r#"TAKE_ALL_FROM_CONTEXT  Address("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d")  Bucket("xrd_bucket");"#,
+            Some(Instruction::TakeAllFromContext {
+                resource_def: Address::from_str(
+                    "03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d"
+                )
+                .unwrap(),
+                to: Bid(0),
+            }),
+            vec![Instruction::DeclareTempBucket]
+        );
+        compile_instruction_ok!(
+            r#"RETURN_TO_CONTEXT  Bucket("xrd_bucket");"#,
+            Some(Instruction::ReturnToContext { bucket: Bid(0) }),
+            vec![Instruction::DeclareTempBucket]
+        );
+        compile_instruction_ok!(
+            r#"CREATE_BUCKET_REF_FROM_BUCKET  Bucket("badge")  BucketRef("badge_ref");"#,
+            Some(Instruction::CreateBucketRefFromBucket {
+                bucket: Bid(0),
+                to: Rid(1),
+            }),
+            vec![
+                Instruction::DeclareTempBucket,
+                Instruction::DeclareTempBucketRef
+            ]
+        );
+        compile_instruction_ok!(
+            r#"CLONE_BUCKET_REF  BucketRef("badge_ref")  BucketRef("badge_ref2");"#,
+            Some(Instruction::CloneBucketRef {
+                bucket_ref: Rid(0),
+                to: Rid(1),
+            }),
+            vec![
+                Instruction::DeclareTempBucketRef,
+                Instruction::DeclareTempBucketRef
+            ]
+        );
         compile_instruction_ok!(
             r#"CALL_FUNCTION  Address("01d1f50010e4102d88aacc347711491f852c515134a9ecf67ba17c")  "Airdrop"  "new"  500u32  HashMap<String, U8>("key", 1u8);"#,
             Some(Instruction::CallFunction {
-                package_address: Address::from_str(
-                    "01d1f50010e4102d88aacc347711491f852c515134a9ecf67ba17c".into()
+                package: Address::from_str(
+                    "01d1f50010e4102d88aacc347711491f852c515134a9ecf67ba17c"
                 )
                 .unwrap(),
-                blueprint_name: "Airdrop".into(),
+                name: "Airdrop".into(),
                 function: "new".into(),
                 args: vec![
-                    scrypto_encode(&500u32),
-                    scrypto_encode(&HashMap::from([("key", 1u8),])),
+                    SmartValue::from(500u32),
+                    SmartValue::from(HashMap::from([("key", 1u8),])),
                 ]
             }),
             vec![]
@@ -706,18 +1107,37 @@ mod tests {
         compile_instruction_ok!(
             r#"CALL_METHOD  Address("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1")  "refill"  Bucket("xrd_bucket")  BucketRef("admin_auth");"#,
             Some(Instruction::CallMethod {
-                component_address: Address::from_str(
-                    "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1".into()
+                component: Address::from_str(
+                    "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1"
                 )
                 .unwrap(),
                 method: "refill".into(),
-                args: vec![scrypto_encode(&Bid(0)), scrypto_encode(&Rid(1))]
+                args: vec![SmartValue::from(Bid(0)), SmartValue::from(Rid(1))]
             }),
             vec![
                 Instruction::DeclareTempBucket,
                 Instruction::DeclareTempBucketRef
             ]
         );
+        compile_instruction_ok!(
+            r#"ASSERT_CONTEXT_CONTAINS  Decimal("95")  Address("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d");"#,
+            Some(Instruction::AssertContextContains {
+                amount: Amount::from(95u32),
+                resource_def: Address::from_str(
+                    "03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d"
+                )
+                .unwrap(),
+            }),
+            vec![]
+        );
+        compile_instruction_ok!(
+            r#"ASSERT_BUCKET_AMOUNT_AT_LEAST  Bucket("xrd_bucket")  Decimal("95");"#,
+            Some(Instruction::AssertBucketAmountAtLeast {
+                bucket: Bid(0),
+                amount: Amount::from(95u32),
+            }),
+            vec![Instruction::DeclareTempBucket]
+        );
         compile_instruction_ok!(
             r#"DROP_ALL_BUCKET_REFS;"#,
             Some(Instruction::DropAllBucketRefs),
@@ -727,11 +1147,223 @@ mod tests {
             r#"DEPOSIT_ALL_BUCKETS  Address("02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de");"#,
             Some(Instruction::DepositAllBuckets {
                 account: Address::from_str(
-                    "02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de".into()
+                    "02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de"
                 )
                 .unwrap(),
             }),
             vec![]
         );
+        compile_instruction_ok!(
+            r#"END  Vec<Address>(Address("04005feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9"));"#,
+            Some(Instruction::End {
+                signers: vec![Address::from_str(
+                    "04005feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9"
+                )
+                .unwrap()],
+            }),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_amounts() {
+        let amount =
+            |s: &str| compile_amount(&Parser::new(tokenize(s).unwrap()).parse_value().unwrap());
+        assert_eq!(amount(r#"Decimal("100")"#), Ok(Amount::from(100u32)));
+        assert_eq!(amount(r#"Decimal("100.00")"#), Ok(Amount::from(100u32)));
+        assert_eq!(
+            amount(r#"Decimal("1.5")"#),
+            Err(CompilerErrorKind::InvalidAmount("1.5".into()))
+        );
+        assert_eq!(
+            amount(r#"Decimal("-1")"#),
+            Err(CompilerErrorKind::InvalidAmount("-1".into()))
+        );
+    }
+
+    #[test]
+    fn test_variables() {
+        let tx = Parser::new(
+            tokenize(
+                r#"LET xrd = Address("030000000000000000000000000000000000000000000000000004");
+                TAKE_ALL_FROM_CONTEXT $xrd Bucket("xrd");
+                DEPOSIT_ALL_BUCKETS Address($account);"#,
+            )
+            .unwrap(),
+        )
+        .parse_transaction()
+        .unwrap();
+        let variables = HashMap::from([(
+            "account".to_owned(),
+            ast::Value::String("02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de".into()),
+        )]);
+        assert_eq!(
+            substitute_variables(&tx, &variables).and_then(|tx| compile_transaction(&tx)),
+            Ok(Transaction {
+                instructions: vec![
+                    Instruction::DeclareTempBucket,
+                    Instruction::TakeAllFromContext {
+                        resource_def: Address::from_str(
+                            "030000000000000000000000000000000000000000000000000004"
+                        )
+                        .unwrap(),
+                        to: Bid(0),
+                    },
+                    Instruction::DepositAllBuckets {
+                        account: Address::from_str(
+                            "02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de"
+                        )
+                        .unwrap(),
+                    },
+                ]
+            })
+        );
+
+        assert_eq!(
+            substitute_variables(&tx, &HashMap::new()),
+            Err(CompilerError {
+                kind: CompilerErrorKind::UndefinedVariable("account".into()),
+                span: Span {
+                    start: 171,
+                    end: 188
+                },
+            })
+        );
+        assert_eq!(
+            substitute_variables(&tx, &HashMap::from([("xrd".to_owned(), ast::Value::Unit)]))
+                .map_err(|e| e.kind),
+            Err(CompilerErrorKind::VariableAlreadyDefined("xrd".into()))
+        );
+    }
+
+    #[test]
+    fn test_blobs() {
+        let code = vec![0x00, 0x61, 0x73, 0x6d];
+        let hash = sha256(&code);
+        let tx = Parser::new(
+            tokenize(&format!(
+                r#"PUBLISH_PACKAGE Blob("{}");
+                CALL_FUNCTION Address("01a405d3129b61e86c51c3168d553d2ffd7a3f0bd2f66b5a3e9876") "Airdrop" "new" Bytes("0061736d");"#,
+                hash
+            ))
+            .unwrap(),
+        )
+        .parse_transaction()
+        .unwrap();
+        let blobs = HashMap::from([(hash, code.clone())]);
+        assert_eq!(
+            resolve_blobs(&tx, &blobs).and_then(|tx| compile_transaction(&tx)),
+            Ok(Transaction {
+                instructions: vec![
+                    Instruction::CallFunction {
+                        package: SYSTEM_PACKAGE,
+                        name: "System".into(),
+                        function: "publish_package".into(),
+                        args: vec![SmartValue::from(code.clone())],
+                    },
+                    Instruction::CallFunction {
+                        package: Address::from_str(
+                            "01a405d3129b61e86c51c3168d553d2ffd7a3f0bd2f66b5a3e9876"
+                        )
+                        .unwrap(),
+                        name: "Airdrop".into(),
+                        function: "new".into(),
+                        args: vec![SmartValue::from(code.clone())],
+                    },
+                ]
+            })
+        );
+
+        assert_eq!(
+            resolve_blobs(&tx, &HashMap::new()).map_err(|e| e.kind),
+            Err(CompilerErrorKind::BlobNotFound(hash.to_string()))
+        );
+        assert_eq!(
+            compile_value(
+                &ast::Value::Bytes(ast::Value::String("0g".into()).into()),
+                None,
+                &mut NameResolver::new()
+            ),
+            Err(CompilerErrorKind::InvalidBytes("0g".into()))
+        );
+    }
+
+    #[test]
+    fn test_parameters() {
+        let tx = Parser::new(
+            tokenize(
+                r#"$amount: Decimal;
+                $trader: Address;
+                $count: U32;
+                CALL_METHOD $trader "buy" $amount $count;"#,
+            )
+            .unwrap(),
+        )
+        .parse_transaction()
+        .unwrap();
+        let args = HashMap::from([
+            ("amount".to_owned(), "100".to_owned()),
+            (
+                "trader".to_owned(),
+                "02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de".to_owned(),
+            ),
+        ]);
+        let variables = HashMap::from([("count".to_owned(), ast::Value::U32(3))]);
+        assert_eq!(
+            bind_parameters(&tx, &variables, &args)
+                .and_then(|bound| substitute_variables(&tx, &bound))
+                .and_then(|tx| compile_transaction(&tx)),
+            Ok(Transaction {
+                instructions: vec![Instruction::CallMethod {
+                    component: Address::from_str(
+                        "02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de"
+                    )
+                    .unwrap(),
+                    method: "buy".into(),
+                    args: vec![
+                        SmartValue::from(Amount::from(100u32)),
+                        SmartValue::from(3u32)
+                    ],
+                }]
+            })
+        );
+
+        assert_eq!(
+            bind_parameters(&tx, &HashMap::new(), &args),
+            Err(CompilerError {
+                kind: CompilerErrorKind::MissingParameter("count".into()),
+                span: Span { start: 68, end: 80 },
+            })
+        );
+        let args = HashMap::from([
+            ("amount".to_owned(), "lots".to_owned()),
+            ("trader".to_owned(), "abc".to_owned()),
+        ]);
+        assert_eq!(
+            bind_parameters(&tx, &variables, &args).map_err(|e| e.kind),
+            Err(CompilerErrorKind::InvalidParameter(
+                "amount".into(),
+                ast::Type::Decimal
+            ))
+        );
+        let variables = HashMap::from([("count".to_owned(), ast::Value::U8(3))]);
+        assert_eq!(
+            bind_parameters(
+                &tx,
+                &variables,
+                &HashMap::from([
+                    ("amount".to_owned(), r#"Decimal("100")"#.to_owned()),
+                    (
+                        "trader".to_owned(),
+                        "02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de".to_owned()
+                    ),
+                ])
+            )
+            .map_err(|e| e.kind),
+            Err(CompilerErrorKind::InvalidParameter(
+                "count".into(),
+                ast::Type::U32
+            ))
+        );
     }
 }
@@ -0,0 +1,803 @@
+use radix_engine::transaction::*;
+use sbor::any::{decode_any, Fields, Value};
+use sbor::describe::Discriminator;
+use sbor::type_id::*;
+use sbor::DecodeError;
+use scrypto::buffer::*;
+use scrypto::types::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+#[derive(Debug, Clone)]
+pub enum DecompileError {
+    InvalidValue(DecodeError),
+    InvalidCustomValue(u8),
+    UnknownType(u8),
+    UnexpectedDeclaration(usize),
+}
+
+/// Assigns manifest names to the buckets and bucket refs declared by a transaction.
+pub struct NameAllocator {
+    bucket_names: HashMap<Bid, String>,
+    bucket_ref_names: HashMap<Rid, String>,
+}
+
+impl NameAllocator {
+    pub fn new() -> Self {
+        Self {
+            bucket_names: HashMap::new(),
+            bucket_ref_names: HashMap::new(),
+        }
+    }
+
+    pub fn name_bucket(&mut self, bid: Bid) {
+        let name = format!("bucket{}", self.bucket_names.len() + 1);
+        self.bucket_names.insert(bid, name);
+    }
+
+    pub fn name_bucket_ref(&mut self, rid: Rid) {
+        let name = format!("bucket_ref{}", self.bucket_ref_names.len() + 1);
+        self.bucket_ref_names.insert(rid, name);
+    }
+
+    pub fn bucket(&self, bid: Bid) -> String {
+        match self.bucket_names.get(&bid) {
+            Some(name) => format!("Bucket({})", quote(name)),
+            None => format!("Bucket({}u32)", bid.0),
+        }
+    }
+
+    pub fn bucket_ref(&self, rid: Rid) -> String {
+        match self.bucket_ref_names.get(&rid) {
+            Some(name) => format!("BucketRef({})", quote(name)),
+            None => format!("BucketRef({}u32)", rid.0),
+        }
+    }
+}
+
+impl Default for NameAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turns a transaction back into manifest text.
+///
+/// Compiling the output yields the original transaction. Buckets and bucket refs are
+/// given names when that preserves their ids, and are declared explicitly otherwise.
+pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
+    // The compiler hoists all declarations to the start of a transaction
+    let mut declarations = Vec::new();
+    for inst in &tx.instructions {
+        match inst {
+            Instruction::DeclareTempBucket | Instruction::DeclareTempBucketRef => {
+                declarations.push(inst.clone());
+            }
+            _ => break,
+        }
+    }
+    let body = &tx.instructions[declarations.len()..];
+    for (i, inst) in body.iter().enumerate() {
+        if let Instruction::DeclareTempBucket | Instruction::DeclareTempBucketRef = inst {
+            return Err(DecompileError::UnexpectedDeclaration(
+                declarations.len() + i,
+            ));
+        }
+    }
+
+    // Names are resolved on first use, so they only preserve ids when every declaration
+    // is used, in the order of declaration.
+    let mut allocator = NameAllocator::new();
+    let mut used = Vec::new();
+    for inst in body {
+        collect_ids(inst, &mut used)?;
+    }
+    let mut first_uses = Vec::new();
+    for id in used {
+        if !first_uses.contains(&id) {
+            first_uses.push(id);
+        }
+    }
+    let expected: Vec<(bool, u32)> = declarations
+        .iter()
+        .enumerate()
+        .map(|(i, inst)| (inst == &Instruction::DeclareTempBucket, i as u32))
+        .collect();
+    let declared: Vec<(bool, u32)> = first_uses
+        .into_iter()
+        .filter(|(is_bucket, n)| expected.get(*n as usize).map(|e| e.0) == Some(*is_bucket))
+        .collect();
+
+    let mut buf = String::new();
+    if declared == expected {
+        for (is_bucket, n) in declared {
+            if is_bucket {
+                allocator.name_bucket(Bid(n));
+            } else {
+                allocator.name_bucket_ref(Rid(n));
+            }
+        }
+    } else {
+        for inst in &declarations {
+            decompile_instruction(&mut buf, inst, &allocator)?;
+        }
+    }
+
+    for inst in body {
+        decompile_instruction(&mut buf, inst, &allocator)?;
+    }
+    Ok(buf)
+}
+
+pub fn decompile_instruction(
+    buf: &mut String,
+    instruction: &Instruction,
+    allocator: &NameAllocator,
+) -> Result<(), DecompileError> {
+    match instruction {
+        Instruction::DeclareTempBucket => {
+            buf.push_str("DECLARE_TEMP_BUCKET;\n");
+        }
+        Instruction::DeclareTempBucketRef => {
+            buf.push_str("DECLARE_TEMP_BUCKET_REF;\n");
+        }
+        Instruction::TakeFromContext {
+            amount,
+            resource_def,
+            to,
+        } => {
+            writeln!(
+                buf,
+                "TAKE_FROM_CONTEXT Decimal({}) Address({}) {};",
+                quote(&amount.to_string()),
+                quote(&resource_def.to_string()),
+                allocator.bucket(*to)
+            )
+            .unwrap();
+        }
+        Instruction::TakeAllFromContext { resource_def, to } => {
+            writeln!(
+                buf,
+                "TAKE_ALL_FROM_CONTEXT Address({}) {};",
+                quote(&resource_def.to_string()),
+                allocator.bucket(*to)
+            )
+            .unwrap();
+        }
+        Instruction::ReturnToContext { bucket } => {
+            writeln!(buf, "RETURN_TO_CONTEXT {};", allocator.bucket(*bucket)).unwrap();
+        }
+        Instruction::BorrowFromContext {
+            amount,
+            resource_def,
+            to,
+        } => {
+            writeln!(
+                buf,
+                "BORROW_FROM_CONTEXT Decimal({}) Address({}) {};",
+                quote(&amount.to_string()),
+                quote(&resource_def.to_string()),
+                allocator.bucket_ref(*to)
+            )
+            .unwrap();
+        }
+        Instruction::CreateBucketRefFromBucket { bucket, to } => {
+            writeln!(
+                buf,
+                "CREATE_BUCKET_REF_FROM_BUCKET {} {};",
+                allocator.bucket(*bucket),
+                allocator.bucket_ref(*to)
+            )
+            .unwrap();
+        }
+        Instruction::CloneBucketRef { bucket_ref, to } => {
+            writeln!(
+                buf,
+                "CLONE_BUCKET_REF {} {};",
+                allocator.bucket_ref(*bucket_ref),
+                allocator.bucket_ref(*to)
+            )
+            .unwrap();
+        }
+        Instruction::CallFunction {
+            package,
+            name,
+            function,
+            args,
+        } if *package == SYSTEM_PACKAGE
+            && name == "System"
+            && function == "publish_package"
+            && args.len() == 1
+            && scrypto_decode::<Vec<u8>>(&args[0].encoded).is_ok() =>
+        {
+            let code = scrypto_decode::<Vec<u8>>(&args[0].encoded).unwrap();
+            writeln!(buf, "PUBLISH_PACKAGE Bytes({});", quote(&hex::encode(code))).unwrap();
+        }
+        Instruction::CallFunction {
+            package,
+            name,
+            function,
+            args,
+        } => {
+            write!(
+                buf,
+                "CALL_FUNCTION Address({}) {} {}",
+                quote(&package.to_string()),
+                quote(name),
+                quote(function)
+            )
+            .unwrap();
+            for arg in args {
+                buf.push(' ');
+                decompile_arg(buf, &arg.encoded, allocator)?;
+            }
+            buf.push_str(";\n");
+        }
+        Instruction::CallMethod {
+            component,
+            method,
+            args,
+        } => {
+            write!(
+                buf,
+                "CALL_METHOD Address({}) {}",
+                quote(&component.to_string()),
+                quote(method)
+            )
+            .unwrap();
+            for arg in args {
+                buf.push(' ');
+                decompile_arg(buf, &arg.encoded, allocator)?;
+            }
+            buf.push_str(";\n");
+        }
+        Instruction::AssertContextContains {
+            amount,
+            resource_def,
+        } => {
+            writeln!(
+                buf,
+                "ASSERT_CONTEXT_CONTAINS Decimal({}) Address({});",
+                quote(&amount.to_string()),
+                quote(&resource_def.to_string())
+            )
+            .unwrap();
+        }
+        Instruction::AssertBucketAmountAtLeast { bucket, amount } => {
+            writeln!(
+                buf,
+                "ASSERT_BUCKET_AMOUNT_AT_LEAST {} Decimal({});",
+                allocator.bucket(*bucket),
+                quote(&amount.to_string())
+            )
+            .unwrap();
+        }
+        Instruction::DropAllBucketRefs => {
+            buf.push_str("DROP_ALL_BUCKET_REFS;\n");
+        }
+        Instruction::DepositAllBuckets { account } => {
+            writeln!(
+                buf,
+                "DEPOSIT_ALL_BUCKETS Address({});",
+                quote(&account.to_string())
+            )
+            .unwrap();
+        }
+        Instruction::End { signers } => {
+            let signers: Vec<String> = signers
+                .iter()
+                .map(|s| format!("Address({})", quote(&s.to_string())))
+                .collect();
+            writeln!(buf, "END Vec<Address>({});", signers.join(", ")).unwrap();
+        }
+    }
+    Ok(())
+}
+
+fn decompile_arg(
+    buf: &mut String,
+    arg: &[u8],
+    allocator: &NameAllocator,
+) -> Result<(), DecompileError> {
+    let value = decode_any(arg).map_err(DecompileError::InvalidValue)?;
+    decompile_value(buf, &value, allocator)
+}
+
+fn decompile_value(
+    buf: &mut String,
+    value: &Value,
+    allocator: &NameAllocator,
+) -> Result<(), DecompileError> {
+    match value {
+        Value::Unit => buf.push_str("()"),
+        Value::Bool(v) => write!(buf, "{}", v).unwrap(),
+        Value::I8(v) => write!(buf, "{}i8", v).unwrap(),
+        Value::I16(v) => write!(buf, "{}i16", v).unwrap(),
+        Value::I32(v) => write!(buf, "{}i32", v).unwrap(),
+        Value::I64(v) => write!(buf, "{}i64", v).unwrap(),
+        Value::I128(v) => write!(buf, "{}i128", v).unwrap(),
+        Value::U8(v) => write!(buf, "{}u8", v).unwrap(),
+        Value::U16(v) => write!(buf, "{}u16", v).unwrap(),
+        Value::U32(v) => write!(buf, "{}u32", v).unwrap(),
+        Value::U64(v) => write!(buf, "{}u64", v).unwrap(),
+        Value::U128(v) => write!(buf, "{}u128", v).unwrap(),
+        Value::String(v) => buf.push_str(&quote(v)),
+        Value::Struct(fields) => {
+            buf.push_str("Struct(");
+            decompile_fields(buf, fields, allocator)?;
+            buf.push(')');
+        }
+        Value::Enum(d, fields) => {
+            write!(buf, "Enum({}", discriminator(d)).unwrap();
+            if !matches!(fields, Fields::Unit) {
+                buf.push_str(", ");
+                decompile_fields(buf, fields, allocator)?;
+            }
+            buf.push(')');
+        }
+        Value::Option(v) => match &**v {
+            Some(inner) => {
+                buf.push_str("Some(");
+                decompile_value(buf, inner, allocator)?;
+                buf.push(')');
+            }
+            None => buf.push_str("None"),
+        },
+        Value::Box(v) => {
+            buf.push_str("Box(");
+            decompile_value(buf, v, allocator)?;
+            buf.push(')');
+        }
+        Value::Array(ty, elements) => {
+            write!(buf, "Array<{}>(", decompile_type(*ty)?).unwrap();
+            decompile_elements(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Value::Tuple(elements) => {
+            buf.push_str("Tuple(");
+            decompile_elements(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Value::Result(v) => {
+            let (prefix, inner) = match &**v {
+                Ok(inner) => ("Ok(", inner),
+                Err(inner) => ("Err(", inner),
+            };
+            buf.push_str(prefix);
+            decompile_value(buf, inner, allocator)?;
+            buf.push(')');
+        }
+        Value::Vec(ty, elements) if *ty == TYPE_U8 => {
+            let bytes: Vec<u8> = elements
+                .iter()
+                .filter_map(|e| match e {
+                    Value::U8(b) => Some(*b),
+                    _ => None,
+                })
+                .collect();
+            write!(buf, "Bytes({})", quote(&hex::encode(bytes))).unwrap();
+        }
+        Value::Vec(ty, elements) => {
+            write!(buf, "Vec<{}>(", decompile_type(*ty)?).unwrap();
+            decompile_elements(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Value::TreeSet(ty, elements) => {
+            write!(buf, "TreeSet<{}>(", decompile_type(*ty)?).unwrap();
+            decompile_elements(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Value::TreeMap(key_type, value_type, elements) => {
+            write!(
+                buf,
+                "TreeMap<{}, {}>(",
+                decompile_type(*key_type)?,
+                decompile_type(*value_type)?
+            )
+            .unwrap();
+            decompile_pairs(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Value::HashSet(ty, elements) => {
+            write!(buf, "HashSet<{}>(", decompile_type(*ty)?).unwrap();
+            decompile_elements(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Value::HashMap(key_type, value_type, elements) => {
+            write!(
+                buf,
+                "HashMap<{}, {}>(",
+                decompile_type(*key_type)?,
+                decompile_type(*value_type)?
+            )
+            .unwrap();
+            decompile_pairs(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Value::Custom(ty, data) => {
+            buf.push_str(&decompile_custom(*ty, data, allocator)?);
+        }
+    }
+    Ok(())
+}
+
+fn decompile_custom(
+    ty: u8,
+    data: &[u8],
+    allocator: &NameAllocator,
+) -> Result<String, DecompileError> {
+    let invalid = DecompileError::InvalidCustomValue(ty);
+    Ok(match ty {
+        SCRYPTO_TYPE_AMOUNT => format!(
+            "Decimal({})",
+            quote(&Amount::try_from(data).map_err(|_| invalid)?.to_string())
+        ),
+        SCRYPTO_TYPE_ADDRESS => format!(
+            "Address({})",
+            quote(&Address::try_from(data).map_err(|_| invalid)?.to_string())
+        ),
+        SCRYPTO_TYPE_H256 => format!(
+            "Hash({})",
+            quote(&H256::try_from(data).map_err(|_| invalid)?.to_string())
+        ),
+        SCRYPTO_TYPE_BID => allocator.bucket(Bid::try_from(data).map_err(|_| invalid)?),
+        SCRYPTO_TYPE_RID => allocator.bucket_ref(Rid::try_from(data).map_err(|_| invalid)?),
+        SCRYPTO_TYPE_MID => format!(
+            "LazyMap({})",
+            quote(&hex::encode(
+                Mid::try_from(data).map_err(|_| invalid)?.to_vec()
+            ))
+        ),
+        SCRYPTO_TYPE_VID => format!(
+            "Vault({})",
+            quote(&hex::encode(
+                Vid::try_from(data).map_err(|_| invalid)?.to_vec()
+            ))
+        ),
+        _ => return Err(DecompileError::UnknownType(ty)),
+    })
+}
+
+fn decompile_fields(
+    buf: &mut String,
+    fields: &Fields,
+    allocator: &NameAllocator,
+) -> Result<(), DecompileError> {
+    match fields {
+        Fields::Named(elements) => {
+            buf.push('{');
+            decompile_elements(buf, elements, allocator)?;
+            buf.push('}');
+        }
+        Fields::Unnamed(elements) => {
+            buf.push('(');
+            decompile_elements(buf, elements, allocator)?;
+            buf.push(')');
+        }
+        Fields::Unit => {}
+    }
+    Ok(())
+}
+
+fn decompile_elements(
+    buf: &mut String,
+    elements: &[Value],
+    allocator: &NameAllocator,
+) -> Result<(), DecompileError> {
+    for (i, element) in elements.iter().enumerate() {
+        if i > 0 {
+            buf.push_str(", ");
+        }
+        decompile_value(buf, element, allocator)?;
+    }
+    Ok(())
+}
+
+fn decompile_pairs(
+    buf: &mut String,
+    elements: &[(Value, Value)],
+    allocator: &NameAllocator,
+) -> Result<(), DecompileError> {
+    for (i, (key, value)) in elements.iter().enumerate() {
+        if i > 0 {
+            buf.push_str(", ");
+        }
+        decompile_value(buf, key, allocator)?;
+        buf.push_str(", ");
+        decompile_value(buf, value, allocator)?;
+    }
+    Ok(())
+}
+
+fn decompile_type(ty: u8) -> Result<&'static str, DecompileError> {
+    Ok(match ty {
+        TYPE_UNIT => "Unit",
+        TYPE_BOOL => "Bool",
+        TYPE_I8 => "I8",
+        TYPE_I16 => "I16",
+        TYPE_I32 => "I32",
+        TYPE_I64 => "I64",
+        TYPE_I128 => "I128",
+        TYPE_U8 => "U8",
+        TYPE_U16 => "U16",
+        TYPE_U32 => "U32",
+        TYPE_U64 => "U64",
+        TYPE_U128 => "U128",
+        TYPE_STRING => "String",
+        TYPE_STRUCT => "Struct",
+        TYPE_ENUM => "Enum",
+        TYPE_ENUM_U16 => "EnumU16",
+        TYPE_ENUM_STRING => "EnumString",
+        TYPE_OPTION => "Option",
+        TYPE_BOX => "Box",
+        TYPE_ARRAY => "Array",
+        TYPE_TUPLE => "Tuple",
+        TYPE_RESULT => "Result",
+        TYPE_VEC => "Vec",
+        TYPE_TREE_SET => "TreeSet",
+        TYPE_TREE_MAP => "TreeMap",
+        TYPE_HASH_SET => "HashSet",
+        TYPE_HASH_MAP => "HashMap",
+        SCRYPTO_TYPE_AMOUNT => "Decimal",
+        SCRYPTO_TYPE_ADDRESS => "Address",
+        SCRYPTO_TYPE_H256 => "Hash",
+        SCRYPTO_TYPE_BID => "Bucket",
+        SCRYPTO_TYPE_RID => "BucketRef",
+        SCRYPTO_TYPE_MID => "LazyMap",
+        SCRYPTO_TYPE_VID => "Vault",
+        _ => return Err(DecompileError::UnknownType(ty)),
+    })
+}
+
+/// Collects the bucket (`true`) and bucket ref (`false`) ids referenced by an instruction.
+fn collect_ids(inst: &Instruction, ids: &mut Vec<(bool, u32)>) -> Result<(), DecompileError> {
+    match inst {
+        Instruction::TakeFromContext { to, .. } | Instruction::TakeAllFromContext { to, .. } => {
+            ids.push((true, to.0))
+        }
+        Instruction::ReturnToContext { bucket } => ids.push((true, bucket.0)),
+        Instruction::CreateBucketRefFromBucket { bucket, to } => {
+            ids.push((true, bucket.0));
+            ids.push((false, to.0));
+        }
+        Instruction::CloneBucketRef { bucket_ref, to } => {
+            ids.push((false, bucket_ref.0));
+            ids.push((false, to.0));
+        }
+        Instruction::AssertBucketAmountAtLeast { bucket, .. } => ids.push((true, bucket.0)),
+        Instruction::BorrowFromContext { to, .. } => ids.push((false, to.0)),
+        Instruction::CallFunction { args, .. } | Instruction::CallMethod { args, .. } => {
+            for arg in args {
+                let value = decode_any(&arg.encoded).map_err(DecompileError::InvalidValue)?;
+                collect_value_ids(&value, ids)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn collect_value_ids(value: &Value, ids: &mut Vec<(bool, u32)>) -> Result<(), DecompileError> {
+    match value {
+        Value::Struct(fields) | Value::Enum(_, fields) => match fields {
+            Fields::Named(elements) | Fields::Unnamed(elements) => {
+                for e in elements {
+                    collect_value_ids(e, ids)?;
+                }
+            }
+            Fields::Unit => {}
+        },
+        Value::Option(v) => {
+            if let Some(inner) = &**v {
+                collect_value_ids(inner, ids)?;
+            }
+        }
+        Value::Box(v) => collect_value_ids(v, ids)?,
+        Value::Result(v) => match &**v {
+            Ok(inner) | Err(inner) => collect_value_ids(inner, ids)?,
+        },
+        Value::Array(_, elements)
+        | Value::Tuple(elements)
+        | Value::Vec(_, elements)
+        | Value::TreeSet(_, elements)
+        | Value::HashSet(_, elements) => {
+            for e in elements {
+                collect_value_ids(e, ids)?;
+            }
+        }
+        Value::TreeMap(_, _, elements) | Value::HashMap(_, _, elements) => {
+            for (k, v) in elements {
+                collect_value_ids(k, ids)?;
+                collect_value_ids(v, ids)?;
+            }
+        }
+        Value::Custom(SCRYPTO_TYPE_BID, data) => {
+            let bid = Bid::try_from(data.as_slice())
+                .map_err(|_| DecompileError::InvalidCustomValue(SCRYPTO_TYPE_BID))?;
+            ids.push((true, bid.0));
+        }
+        Value::Custom(SCRYPTO_TYPE_RID, data) => {
+            let rid = Rid::try_from(data.as_slice())
+                .map_err(|_| DecompileError::InvalidCustomValue(SCRYPTO_TYPE_RID))?;
+            ids.push((false, rid.0));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Quotes a string as a manifest string literal.
+pub(crate) fn quote(s: &str) -> String {
+    let mut buf = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\x08' => buf.push_str("\\b"),
+            '\x0c' => buf.push_str("\\f"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    write!(buf, "\\u{:04x}", unit).unwrap();
+                }
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+    buf
+}
+
+/// Returns an enum discriminator as a manifest literal, e.g. `1u8` or `"Buy"`.
+pub(crate) fn discriminator(d: &Discriminator) -> String {
+    match d {
+        Discriminator::U8(d) => format!("{}u8", d),
+        Discriminator::U16(d) => format!("{}u16", d),
+        Discriminator::String(d) => quote(d),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile;
+    use std::str::FromStr;
+
+    #[macro_export]
+    macro_rules! decompile_ok {
+        ( $tx:expr, $expected:expr ) => {{
+            let tx = $tx;
+            let text = decompile(&tx).unwrap();
+            assert_eq!(text, $expected);
+            assert_eq!(compile(&text), Ok(tx));
+        }};
+    }
+
+    #[test]
+    fn test_named_buckets() {
+        decompile_ok!(
+            Transaction {
+                instructions: vec![
+                    Instruction::DeclareTempBucket,
+                    Instruction::DeclareTempBucketRef,
+                    Instruction::TakeFromContext {
+                        amount: Amount::from(1u32),
+                        resource_def: Address::from_str(
+                            "03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d"
+                        )
+                        .unwrap(),
+                        to: Bid(0),
+                    },
+                    Instruction::CallMethod {
+                        component: Address::from_str(
+                            "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1"
+                        )
+                        .unwrap(),
+                        method: "refill".into(),
+                        args: vec![SmartValue::from(Bid(0)), SmartValue::from(Rid(1))],
+                    },
+                    Instruction::DropAllBucketRefs,
+                    Instruction::End {
+                        signers: vec![Address::from_str(
+                            "04005feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9"
+                        )
+                        .unwrap()],
+                    },
+                ],
+            },
+            "TAKE_FROM_CONTEXT Decimal(\"1\") Address(\"03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d\") Bucket(\"bucket1\");\n\
+             CALL_METHOD Address(\"0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1\") \"refill\" Bucket(\"bucket1\") BucketRef(\"bucket_ref1\");\n\
+             DROP_ALL_BUCKET_REFS;\n\
+             END Vec<Address>(Address(\"04005feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9\"));\n"
+        );
+    }
+
+    #[test]
+    fn test_unnamed_buckets() {
+        decompile_ok!(
+            Transaction {
+                instructions: vec![
+                    Instruction::DeclareTempBucket,
+                    Instruction::DeclareTempBucket,
+                    Instruction::CallMethod {
+                        component: Address::from_str(
+                            "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1"
+                        )
+                        .unwrap(),
+                        method: "swap".into(),
+                        args: vec![SmartValue::from(Bid(1)), SmartValue::from(Bid(0))],
+                    },
+                ],
+            },
+            "DECLARE_TEMP_BUCKET;\n\
+             DECLARE_TEMP_BUCKET;\n\
+             CALL_METHOD Address(\"0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1\") \"swap\" Bucket(1u32) Bucket(0u32);\n"
+        );
+    }
+
+    #[test]
+    fn test_values() {
+        decompile_ok!(
+            Transaction {
+                instructions: vec![Instruction::CallFunction {
+                    package: Address::from_str(
+                        "01d1f50010e4102d88aacc347711491f852c515134a9ecf67ba17c"
+                    )
+                    .unwrap(),
+                    name: "Airdrop".into(),
+                    function: "new".into(),
+                    args: vec![
+                        SmartValue::from(500u32),
+                        SmartValue::from(HashMap::from([("key\n\"", 1u8)])),
+                        SmartValue::from(Some(vec![-1i64, 2i64])),
+                        SmartValue::from((Ok::<u16, ()>(7u16), ())),
+                    ],
+                }],
+            },
+            "CALL_FUNCTION Address(\"01d1f50010e4102d88aacc347711491f852c515134a9ecf67ba17c\") \"Airdrop\" \"new\" 500u32 HashMap<String, U8>(\"key\\n\\\"\", 1u8) Some(Vec<I64>(-1i64, 2i64)) Tuple(Ok(7u16), ());\n"
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        decompile_ok!(
+            Transaction {
+                instructions: vec![
+                    Instruction::CallFunction {
+                        package: SYSTEM_PACKAGE,
+                        name: "System".into(),
+                        function: "publish_package".into(),
+                        args: vec![SmartValue::from(vec![0x00u8, 0x61, 0x73, 0x6d])],
+                    },
+                    Instruction::CallMethod {
+                        component: Address::from_str(
+                            "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1"
+                        )
+                        .unwrap(),
+                        method: "store".into(),
+                        args: vec![SmartValue::from(vec![0xffu8])],
+                    },
+                ],
+            },
+            "PUBLISH_PACKAGE Bytes(\"0061736d\");\n\
+             CALL_METHOD Address(\"0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1\") \"store\" Bytes(\"ff\");\n"
+        );
+    }
+
+    #[test]
+    fn test_unexpected_declaration() {
+        let tx = Transaction {
+            instructions: vec![
+                Instruction::DropAllBucketRefs,
+                Instruction::DeclareTempBucket,
+            ],
+        };
+        assert!(matches!(
+            decompile(&tx),
+            Err(DecompileError::UnexpectedDeclaration(1))
+        ));
+    }
+}
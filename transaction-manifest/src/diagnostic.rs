@@ -0,0 +1,440 @@
+use crate::ast::Value;
+use crate::compiler::{CompilerError, CompilerErrorKind};
+use crate::lexer::{LexerError, Span, Token, TokenKind, INSTRUCTIONS, KEYWORDS};
+use crate::linter::{Lint, LintKind};
+use crate::parser::{Expected, ParserError};
+use crate::CompileError;
+
+/// A human-readable description of a compile error and where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    pub label: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(source: &str, error: &CompileError) -> Self {
+        let eof = Span {
+            start: source.chars().count(),
+            end: source.chars().count(),
+        };
+        match error {
+            CompileError::LexerError(e) => lexer_diagnostic(e, eof),
+            CompileError::ParserError(e) => parser_diagnostic(e, eof),
+            CompileError::CompilerError(e) => compiler_diagnostic(e),
+        }
+    }
+
+    pub fn from_lint(lint: &Lint) -> Self {
+        let (message, label) = match &lint.kind {
+            LintKind::UnusedBucket(name) => (format!("bucket `{}` is never used", name), None),
+            LintKind::BucketConsumedTwice(name) => (
+                format!("bucket `{}` is consumed twice", name),
+                Some("already moved by an earlier instruction".to_owned()),
+            ),
+            LintKind::BucketRefNotDropped(name) => (
+                format!("bucket ref `{}` is never dropped", name),
+                Some("missing `DROP_ALL_BUCKET_REFS`?".to_owned()),
+            ),
+            LintKind::MissingDepositAll => (
+                "manifest doesn't end with `DEPOSIT_ALL_BUCKETS`".to_owned(),
+                Some("leftover resources would fail the transaction".to_owned()),
+            ),
+        };
+        Self {
+            message,
+            span: lint.span.clone(),
+            label,
+        }
+    }
+
+    /// Renders this diagnostic, showing the offending line with a caret underneath.
+    pub fn render(&self, source: &str) -> String {
+        self.render_as("error", source)
+    }
+
+    /// Renders this diagnostic as a warning.
+    pub fn render_warning(&self, source: &str) -> String {
+        self.render_as("warning", source)
+    }
+
+    fn render_as(&self, level: &str, source: &str) -> String {
+        let chars: Vec<char> = source.chars().collect();
+
+        // Locate the line containing the start of the span
+        let start = self.span.start.min(chars.len());
+        let line_start = chars[..start]
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let line_end = chars[start..]
+            .iter()
+            .position(|c| *c == '\n')
+            .map(|i| start + i)
+            .unwrap_or(chars.len());
+        let line_number = chars[..line_start].iter().filter(|c| **c == '\n').count() + 1;
+        let column = start - line_start + 1;
+        let line: String = chars[line_start..line_end]
+            .iter()
+            .collect::<String>()
+            .trim_end_matches('\r')
+            .to_owned();
+
+        // Underline the span, up to the end of the line
+        let width = self.span.end.min(line_end).saturating_sub(start).max(1);
+        let gutter = " ".repeat(line_number.to_string().len());
+
+        let mut buf = format!("{}: {}\n", level, self.message);
+        buf.push_str(&format!("{}--> {}:{}\n", gutter, line_number, column));
+        buf.push_str(&format!("{} |\n", gutter));
+        buf.push_str(&format!("{} | {}\n", line_number, line));
+        buf.push_str(&format!(
+            "{} | {}{}",
+            gutter,
+            " ".repeat(column - 1),
+            "^".repeat(width)
+        ));
+        if let Some(label) = &self.label {
+            buf.push(' ');
+            buf.push_str(label);
+        }
+        buf.push('\n');
+        buf
+    }
+}
+
+/// Renders a compile error against the manifest source.
+pub fn render(source: &str, error: &CompileError) -> String {
+    Diagnostic::new(source, error).render(source)
+}
+
+/// Renders a lint as a warning against the manifest source.
+pub fn render_lint(source: &str, lint: &Lint) -> String {
+    Diagnostic::from_lint(lint).render_warning(source)
+}
+
+fn lexer_diagnostic(error: &LexerError, eof: Span) -> Diagnostic {
+    match error {
+        LexerError::UnexpectedEof => Diagnostic {
+            message: "unexpected end of file".to_owned(),
+            span: eof,
+            label: None,
+        },
+        LexerError::UnexpectedChar(c, position) => Diagnostic {
+            message: format!("unexpected character `{}`", c),
+            span: Span {
+                start: *position,
+                end: *position + 1,
+            },
+            label: None,
+        },
+        LexerError::InvalidNumber(s, span) => Diagnostic {
+            message: format!("invalid number `{}`", s),
+            span: span.clone(),
+            label: Some("out of range for its type".to_owned()),
+        },
+        LexerError::InvalidUnicode(code, position) => Diagnostic {
+            message: format!("invalid unicode code point `{:#x}`", code),
+            span: Span {
+                start: *position,
+                end: *position,
+            },
+            label: None,
+        },
+    }
+}
+
+fn parser_diagnostic(error: &ParserError, eof: Span) -> Diagnostic {
+    match error {
+        ParserError::UnexpectedEof => Diagnostic {
+            message: "unexpected end of file".to_owned(),
+            span: eof,
+            label: Some("missing `;`?".to_owned()),
+        },
+        ParserError::UnexpectedToken {
+            actual:
+                Token {
+                    kind: TokenKind::Identifier(s),
+                    span,
+                },
+            ..
+        } => Diagnostic {
+            message: format!("unknown identifier `{}`", s),
+            span: span.clone(),
+            label: suggest(s).map(|k| format!("did you mean `{}`?", k)),
+        },
+        ParserError::UnexpectedToken { actual, expected } => Diagnostic {
+            message: format!("unexpected {}", describe_token(&actual.kind)),
+            span: actual.span.clone(),
+            label: Some(format!("expected {}", describe_expected(expected))),
+        },
+        ParserError::InvalidNumberOfValues {
+            actual,
+            expected,
+            span,
+        } => Diagnostic {
+            message: format!("expected {} value(s), found {}", expected, actual),
+            span: span.clone(),
+            label: None,
+        },
+        ParserError::InvalidNumberOfTypes {
+            actual,
+            expected,
+            span,
+        } => Diagnostic {
+            message: format!("expected {} type argument(s), found {}", expected, actual),
+            span: span.clone(),
+            label: None,
+        },
+    }
+}
+
+fn compiler_diagnostic(error: &CompilerError) -> Diagnostic {
+    let (message, label) = match &error.kind {
+        CompilerErrorKind::WrongTypeOfValue {
+            expected_type,
+            actual,
+        } => (
+            "mismatched types".to_owned(),
+            Some(format!(
+                "expected {}, found {}",
+                expected_type
+                    .iter()
+                    .map(|t| format!("`{:?}`", t))
+                    .collect::<Vec<String>>()
+                    .join(" or "),
+                describe_kind(actual)
+            )),
+        ),
+        CompilerErrorKind::InvalidAddress(s) => (format!("invalid address `{}`", s), None),
+        CompilerErrorKind::InvalidDecimal(s) => (format!("invalid decimal `{}`", s), None),
+        CompilerErrorKind::InvalidAmount(s) => (
+            format!("invalid amount `{}`", s),
+            Some("resource amounts are whole numbers".to_owned()),
+        ),
+        CompilerErrorKind::OddNumberOfElements(n) => (
+            format!("expected key-value pairs, found {} element(s)", n),
+            None,
+        ),
+        CompilerErrorKind::FailedToExportAbi(address) => {
+            (format!("failed to export the ABI of `{}`", address), None)
+        }
+        CompilerErrorKind::FunctionNotFound(function) => (
+            format!("function `{}` not found in blueprint ABI", function),
+            None,
+        ),
+        CompilerErrorKind::MethodNotFound(method) => (
+            format!("method `{}` not found in blueprint ABI", method),
+            None,
+        ),
+        CompilerErrorKind::WrongNumberOfArguments { expected, actual } => (
+            format!("expected {} argument(s), found {}", expected, actual),
+            None,
+        ),
+        CompilerErrorKind::ArgumentTypeMismatch { index, expected } => (
+            format!("mismatched type for argument {}", index),
+            Some(format!("expected `{:?}`", expected)),
+        ),
+        CompilerErrorKind::UndefinedVariable(name) => {
+            (format!("cannot find variable `${}`", name), None)
+        }
+        CompilerErrorKind::VariableAlreadyDefined(name) => (
+            format!("variable `${}` is defined multiple times", name),
+            None,
+        ),
+        CompilerErrorKind::InvalidHash(s) => (format!("invalid hash `{}`", s), None),
+        CompilerErrorKind::InvalidBytes(s) => (format!("invalid hex bytes `{}`", s), None),
+        CompilerErrorKind::MissingParameter(name) => (
+            format!("missing value for parameter `${}`", name),
+            Some("supply it with `--arg`".to_owned()),
+        ),
+        CompilerErrorKind::InvalidParameter(name, ty) => (
+            format!("invalid value for parameter `${}`", name),
+            Some(format!("expected `{:?}`", ty)),
+        ),
+        CompilerErrorKind::BlobNotFound(hash) => (
+            format!("cannot find blob `{}`", hash),
+            Some("no blob with this hash was attached".to_owned()),
+        ),
+    };
+    Diagnostic {
+        message,
+        span: error.span.clone(),
+        label,
+    }
+}
+
+fn describe_expected(expected: &Expected) -> String {
+    match expected {
+        Expected::Token(kind) => describe_token(kind),
+        Expected::Instruction => "an instruction".to_owned(),
+        Expected::Value => "a value".to_owned(),
+        Expected::Type => "a type".to_owned(),
+        Expected::Fields => "`{`, `(` or `)`".to_owned(),
+        Expected::EnumIndex => "a `u8`, `u16` or string enum discriminator".to_owned(),
+        Expected::Name => "a name".to_owned(),
+        Expected::Eof => "end of input".to_owned(),
+    }
+}
+
+fn describe_kind(value: &Value) -> String {
+    match value {
+        Value::Variable(name) => format!("`${}`", name),
+        v => format!("`{:?}`", v.kind()),
+    }
+}
+
+fn describe_token(kind: &TokenKind) -> String {
+    let text = match kind {
+        TokenKind::BoolLiteral(v) => return format!("`{}`", v),
+        TokenKind::I8Literal(v) => return format!("`{}i8`", v),
+        TokenKind::I16Literal(v) => return format!("`{}i16`", v),
+        TokenKind::I32Literal(v) => return format!("`{}i32`", v),
+        TokenKind::I64Literal(v) => return format!("`{}i64`", v),
+        TokenKind::I128Literal(v) => return format!("`{}i128`", v),
+        TokenKind::U8Literal(v) => return format!("`{}u8`", v),
+        TokenKind::U16Literal(v) => return format!("`{}u16`", v),
+        TokenKind::U32Literal(v) => return format!("`{}u32`", v),
+        TokenKind::U64Literal(v) => return format!("`{}u64`", v),
+        TokenKind::U128Literal(v) => return format!("`{}u128`", v),
+        TokenKind::StringLiteral(v) => return format!("`{:?}`", v),
+        TokenKind::Identifier(v) => return format!("`{}`", v),
+        TokenKind::Variable(v) => return format!("`${}`", v),
+        TokenKind::OpenCurlyBrace => "{",
+        TokenKind::CloseCurlyBrace => "}",
+        TokenKind::OpenParenthesis => "(",
+        TokenKind::CloseParenthesis => ")",
+        TokenKind::LessThan => "<",
+        TokenKind::GreaterThan => ">",
+        TokenKind::Comma => ",",
+        TokenKind::Semicolon => ";",
+        TokenKind::Equals => "=",
+        TokenKind::Colon => ":",
+        // Type and sub-type keywords are spelled as their variant names
+        other => match INSTRUCTIONS.iter().find(|(_, k)| k == other) {
+            Some((name, _)) => name,
+            None => return format!("`{:?}`", other),
+        },
+    };
+    format!("`{}`", text)
+}
+
+/// Finds the closest instruction name or keyword to a misspelled identifier.
+fn suggest(identifier: &str) -> Option<&'static str> {
+    let lowercase = identifier.to_lowercase();
+    INSTRUCTIONS
+        .iter()
+        .chain(KEYWORDS.iter())
+        .map(|(k, _)| (*k, edit_distance(&lowercase, &k.to_lowercase())))
+        .filter(|(k, d)| *d <= (k.len() / 3).max(1))
+        .min_by_key(|(_, d)| *d)
+        .map(|(k, _)| k)
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile;
+
+    macro_rules! render_error {
+        ( $s:expr, $expected:expr ) => {{
+            let error = compile($s).unwrap_err();
+            assert_eq!(render($s, &error), $expected);
+        }};
+    }
+
+    #[test]
+    fn test_suggestion() {
+        assert_eq!(suggest("CALL_METHD"), Some("CALL_METHOD"));
+        assert_eq!(suggest("call_function"), Some("CALL_FUNCTION"));
+        assert_eq!(suggest("Hashmap"), Some("HashMap"));
+        assert_eq!(suggest("Decimel"), Some("Decimal"));
+        assert_eq!(suggest("FOO"), None);
+    }
+
+    #[test]
+    fn test_unknown_identifier() {
+        render_error!(
+            "DROP_ALL_BUCKET_REFS;\nCALL_METHD Address(\"0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1\") \"free\";",
+            "error: unknown identifier `CALL_METHD`\n \
+             --> 2:1\n  \
+             |\n\
+             2 | CALL_METHD Address(\"0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1\") \"free\";\n  \
+             | ^^^^^^^^^^ did you mean `CALL_METHOD`?\n"
+        );
+    }
+
+    #[test]
+    fn test_unexpected_token() {
+        render_error!(
+            "DROP_ALL_BUCKET_REFS\nDROP_ALL_BUCKET_REFS;",
+            "error: unexpected `DROP_ALL_BUCKET_REFS`\n \
+             --> 2:1\n  \
+             |\n\
+             2 | DROP_ALL_BUCKET_REFS;\n  \
+             | ^^^^^^^^^^^^^^^^^^^^ expected `;`\n"
+        );
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        render_error!(
+            "DEPOSIT_ALL_BUCKETS Decimal(\"1.0\");",
+            "error: mismatched types\n \
+             --> 1:21\n  \
+             |\n\
+             1 | DEPOSIT_ALL_BUCKETS Decimal(\"1.0\");\n  \
+             |                     ^^^^^^^^^^^^^^ expected `Address`, found `Decimal`\n"
+        );
+    }
+
+    #[test]
+    fn test_undefined_variable() {
+        render_error!(
+            "// deposit\nDEPOSIT_ALL_BUCKETS $account;",
+            "error: cannot find variable `$account`\n \
+             --> 2:21\n  \
+             |\n\
+             2 | DEPOSIT_ALL_BUCKETS $account;\n  \
+             |                     ^^^^^^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_lint_warning() {
+        let s = "DROP_ALL_BUCKET_REFS;\nCALL_METHOD $account \"free\";";
+        let tx = crate::parser::Parser::new(crate::lexer::tokenize(s).unwrap())
+            .parse_transaction()
+            .unwrap();
+        assert_eq!(
+            render_lint(s, &crate::linter::lint(&tx)[0]),
+            "warning: manifest doesn't end with `DEPOSIT_ALL_BUCKETS`\n \
+             --> 2:1\n  \
+             |\n\
+             2 | CALL_METHOD $account \"free\";\n  \
+             | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ leftover resources would fail the transaction\n"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::ast::{Fields, Instruction, Parameter, Transaction, Value};
+use crate::decompiler::{discriminator, quote};
+use crate::lexer::{tokenize_with_comments, Span};
+use crate::parser::Parser;
+use crate::CompileError;
+use std::fmt::Write;
+
+/// An instruction or comment, in source order.
+enum Item<'a> {
+    Parameter(&'a Parameter),
+    Instruction(&'a Instruction),
+    /// A comment, and whether it's inside an instruction
+    Comment(String, bool),
+}
+
+/// Formats manifest text canonically.
+///
+/// Each instruction is put on its own line with operands separated by single spaces, and runs
+/// of blank lines are collapsed into one. Comments are kept, except that comments within an
+/// instruction are moved before it. Variables are left unresolved.
+pub fn format(s: &str) -> Result<String, CompileError> {
+    let (tokens, comments) = tokenize_with_comments(s).map_err(CompileError::LexerError)?;
+    let tx = Parser::new(tokens)
+        .parse_transaction()
+        .map_err(CompileError::ParserError)?;
+    Ok(format_transaction(&tx, &comments, s))
+}
+
+fn format_transaction(tx: &Transaction, comments: &[Span], source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+
+    // Items are keyed by the start and end of the source they take up
+    let mut items = Vec::new();
+    for parameter in &tx.parameters {
+        items.push((
+            parameter.span.start,
+            parameter.span.end,
+            Item::Parameter(parameter),
+        ));
+    }
+    for (instruction, span) in tx.instructions.iter().zip(&tx.spans) {
+        items.push((
+            span.span.start,
+            span.span.end,
+            Item::Instruction(instruction),
+        ));
+    }
+    for comment in comments {
+        let text = chars[comment.start..comment.end]
+            .iter()
+            .collect::<String>()
+            .trim_end()
+            .to_owned();
+        match tx
+            .spans
+            .iter()
+            .find(|s| s.span.start < comment.start && comment.start < s.span.end)
+        {
+            Some(s) => items.push((s.span.start, s.span.start, Item::Comment(text, true))),
+            None => items.push((comment.start, comment.end, Item::Comment(text, false))),
+        }
+    }
+    items.sort_by_key(|(start, _, item)| (*start, !matches!(item, Item::Comment(..))));
+
+    let mut buf = String::new();
+    let mut last = 0;
+    for (start, end, item) in items {
+        let newlines = chars[last..start].iter().filter(|c| **c == '\n').count();
+        match item {
+            // A comment following an instruction on the same line stays there
+            Item::Comment(text, false) if newlines == 0 && !buf.is_empty() => {
+                buf.pop();
+                writeln!(buf, " {}", text).unwrap();
+            }
+            item => {
+                if newlines > 1 && !buf.is_empty() {
+                    buf.push('\n');
+                }
+                match item {
+                    Item::Parameter(parameter) => {
+                        write!(buf, "${}: {:?};", parameter.name, parameter.ty).unwrap()
+                    }
+                    Item::Instruction(instruction) => format_instruction(&mut buf, instruction),
+                    Item::Comment(text, _) => buf.push_str(&text),
+                }
+                buf.push('\n');
+            }
+        }
+        last = end;
+    }
+    buf
+}
+
+fn format_instruction(buf: &mut String, instruction: &Instruction) {
+    let mut instruction = instruction.clone();
+    if let Instruction::Let { name, value } = &instruction {
+        write!(buf, "LET {} = ", name).unwrap();
+        format_value(buf, value);
+        buf.push(';');
+        return;
+    }
+
+    buf.push_str(keyword(&instruction));
+    for value in instruction.values_mut() {
+        buf.push(' ');
+        format_value(buf, value);
+    }
+    buf.push(';');
+}
+
+fn keyword(instruction: &Instruction) -> &'static str {
+    match instruction {
+        Instruction::Let { .. } => "LET",
+        Instruction::DeclareTempBucket => "DECLARE_TEMP_BUCKET",
+        Instruction::DeclareTempBucketRef => "DECLARE_TEMP_BUCKET_REF",
+        Instruction::TakeFromContext { .. } => "TAKE_FROM_CONTEXT",
+        Instruction::TakeAllFromContext { .. } => "TAKE_ALL_FROM_CONTEXT",
+        Instruction::ReturnToContext { .. } => "RETURN_TO_CONTEXT",
+        Instruction::BorrowFromContext { .. } => "BORROW_FROM_CONTEXT",
+        Instruction::CreateBucketRefFromBucket { .. } => "CREATE_BUCKET_REF_FROM_BUCKET",
+        Instruction::CloneBucketRef { .. } => "CLONE_BUCKET_REF",
+        Instruction::CallFunction { .. } => "CALL_FUNCTION",
+        Instruction::CallMethod { .. } => "CALL_METHOD",
+        Instruction::AssertContextContains { .. } => "ASSERT_CONTEXT_CONTAINS",
+        Instruction::AssertBucketAmountAtLeast { .. } => "ASSERT_BUCKET_AMOUNT_AT_LEAST",
+        Instruction::DropAllBucketRefs => "DROP_ALL_BUCKET_REFS",
+        Instruction::DepositAllBuckets { .. } => "DEPOSIT_ALL_BUCKETS",
+        Instruction::PublishPackage { .. } => "PUBLISH_PACKAGE",
+        Instruction::End { .. } => "END",
+    }
+}
+
+fn format_value(buf: &mut String, value: &Value) {
+    match value {
+        Value::Unit => buf.push_str("()"),
+        Value::Bool(v) => write!(buf, "{}", v).unwrap(),
+        Value::I8(v) => write!(buf, "{}i8", v).unwrap(),
+        Value::I16(v) => write!(buf, "{}i16", v).unwrap(),
+        Value::I32(v) => write!(buf, "{}i32", v).unwrap(),
+        Value::I64(v) => write!(buf, "{}i64", v).unwrap(),
+        Value::I128(v) => write!(buf, "{}i128", v).unwrap(),
+        Value::U8(v) => write!(buf, "{}u8", v).unwrap(),
+        Value::U16(v) => write!(buf, "{}u16", v).unwrap(),
+        Value::U32(v) => write!(buf, "{}u32", v).unwrap(),
+        Value::U64(v) => write!(buf, "{}u64", v).unwrap(),
+        Value::U128(v) => write!(buf, "{}u128", v).unwrap(),
+        Value::String(v) => buf.push_str(&quote(v)),
+        Value::Struct(fields) => {
+            buf.push_str("Struct(");
+            format_fields(buf, fields);
+            buf.push(')');
+        }
+        Value::Enum(d, fields) => {
+            write!(buf, "Enum({}", discriminator(d)).unwrap();
+            if !matches!(fields, Fields::Unit) {
+                buf.push_str(", ");
+                format_fields(buf, fields);
+            }
+            buf.push(')');
+        }
+        Value::Option(v) => match &**v {
+            Some(inner) => {
+                buf.push_str("Some(");
+                format_value(buf, inner);
+                buf.push(')');
+            }
+            None => buf.push_str("None"),
+        },
+        Value::Result(v) => {
+            let (prefix, inner) = match &**v {
+                Ok(inner) => ("Ok(", inner),
+                Err(inner) => ("Err(", inner),
+            };
+            buf.push_str(prefix);
+            format_value(buf, inner);
+            buf.push(')');
+        }
+        Value::Tuple(elements) => {
+            buf.push_str("Tuple(");
+            format_elements(buf, elements);
+            buf.push(')');
+        }
+        Value::Array(ty, elements)
+        | Value::Vec(ty, elements)
+        | Value::TreeSet(ty, elements)
+        | Value::HashSet(ty, elements) => {
+            write!(buf, "{:?}<{:?}>(", value.kind(), ty).unwrap();
+            format_elements(buf, elements);
+            buf.push(')');
+        }
+        Value::TreeMap(key_type, value_type, elements)
+        | Value::HashMap(key_type, value_type, elements) => {
+            write!(buf, "{:?}<{:?}, {:?}>(", value.kind(), key_type, value_type).unwrap();
+            format_elements(buf, elements);
+            buf.push(')');
+        }
+        Value::Box(inner)
+        | Value::Decimal(inner)
+        | Value::Address(inner)
+        | Value::Hash(inner)
+        | Value::Bucket(inner)
+        | Value::BucketRef(inner)
+        | Value::LazyMap(inner)
+        | Value::Vault(inner)
+        | Value::Bytes(inner)
+        | Value::Blob(inner) => {
+            write!(buf, "{:?}(", value.kind()).unwrap();
+            format_value(buf, inner);
+            buf.push(')');
+        }
+        Value::Variable(name) => write!(buf, "${}", name).unwrap(),
+    }
+}
+
+fn format_fields(buf: &mut String, fields: &Fields) {
+    match fields {
+        Fields::Named(elements) => {
+            buf.push('{');
+            format_elements(buf, elements);
+            buf.push('}');
+        }
+        Fields::Unnamed(elements) => {
+            buf.push('(');
+            format_elements(buf, elements);
+            buf.push(')');
+        }
+        Fields::Unit => {}
+    }
+}
+
+fn format_elements(buf: &mut String, elements: &[Value]) {
+    for (i, element) in elements.iter().enumerate() {
+        if i > 0 {
+            buf.push_str(", ");
+        }
+        format_value(buf, element);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            format(
+                "# Withdraw\nCALL_METHOD   Address(\"0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1\")\n    \"withdraw\"  Decimal( \"1.0\" ) Address(\"030000000000000000000000000000000000000000000000000004\");\n\n\n\nTAKE_ALL_FROM_CONTEXT Address(\"030000000000000000000000000000000000000000000000000004\") Bucket(\"xrd\"); // all of it\nDEPOSIT_ALL_BUCKETS $DEFAULT_ACCOUNT;"
+            ),
+            Ok("# Withdraw\n\
+                CALL_METHOD Address(\"0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1\") \"withdraw\" Decimal(\"1.0\") Address(\"030000000000000000000000000000000000000000000000000004\");\n\
+                \n\
+                TAKE_ALL_FROM_CONTEXT Address(\"030000000000000000000000000000000000000000000000000004\") Bucket(\"xrd\"); // all of it\n\
+                DEPOSIT_ALL_BUCKETS $DEFAULT_ACCOUNT;\n"
+                .to_owned())
+        );
+    }
+
+    #[test]
+    fn test_format_parameters() {
+        assert_eq!(
+            format("# Inputs\n$amount :Decimal;$trader: Address;\n\nCALL_METHOD $trader \"buy\" $amount;"),
+            Ok("# Inputs\n\
+                $amount: Decimal;\n\
+                $trader: Address;\n\
+                \n\
+                CALL_METHOD $trader \"buy\" $amount;\n"
+                .to_owned())
+        );
+    }
+
+    #[test]
+    fn test_format_values() {
+        assert_eq!(
+            format(
+                "LET x=Struct({Enum(1u8), Enum(2u8, (1i8))});CALL_METHOD $x \"a\\n\" Vec<U8>(1u8,2u8) HashMap<String,Bucket>(\"a\",Bucket(\"b\")) Some(Ok(())) Bytes(\"00ff\");"
+            ),
+            Ok("LET x = Struct({Enum(1u8), Enum(2u8, (1i8))});\n\
+                CALL_METHOD $x \"a\\n\" Vec<U8>(1u8, 2u8) HashMap<String, Bucket>(\"a\", Bucket(\"b\")) Some(Ok(())) Bytes(\"00ff\");\n"
+                .to_owned())
+        );
+    }
+
+    #[test]
+    fn test_format_is_stable() {
+        let s = "LET a = 1u8; # one\n# inner\nCALL_METHOD $a # also inner\n\"b\";\n\n# end\n";
+        let formatted = format(s).unwrap();
+        assert_eq!(
+            formatted,
+            "LET a = 1u8; # one\n# inner\n# also inner\nCALL_METHOD $a \"b\";\n\n# end\n"
+        );
+        assert_eq!(format(&formatted), Ok(formatted));
+    }
+}
@@ -24,6 +24,10 @@ pub enum TokenKind {
     U128Literal(u128),
     StringLiteral(String),
 
+    /* Names */
+    Identifier(String),
+    Variable(String),
+
     /* Types */
     Unit,
     Bool,
@@ -40,6 +44,8 @@ pub enum TokenKind {
     String,
     Struct,
     Enum,
+    EnumU16,
+    EnumString,
     Option,
     Box,
     Array,
@@ -51,13 +57,14 @@ pub enum TokenKind {
     HashSet,
     HashMap,
     Decimal,
-    BigDecimal,
     Address,
     Hash,
     Bucket,
     BucketRef,
     LazyMap,
     Vault,
+    Bytes,
+    Blob,
 
     /* Sub-types */
     Some,
@@ -74,18 +81,102 @@ pub enum TokenKind {
     GreaterThan,
     Comma,
     Semicolon,
+    Equals,
+    Colon,
 
     /* Instructions */
+    Let,
     DeclareTempBucket,
     DeclareTempBucketRef,
     TakeFromContext,
+    TakeAllFromContext,
+    ReturnToContext,
     BorrowFromContext,
+    CreateBucketRefFromBucket,
+    CloneBucketRef,
     CallFunction,
     CallMethod,
+    AssertContextContains,
+    AssertBucketAmountAtLeast,
     DropAllBucketRefs,
     DepositAllBuckets,
+    PublishPackage,
+    End,
 }
 
+/// Type and value keywords, with the tokens they are lexed into.
+pub const KEYWORDS: [(&str, TokenKind); 42] = [
+    ("true", TokenKind::BoolLiteral(true)),
+    ("false", TokenKind::BoolLiteral(false)),
+    ("Unit", TokenKind::Unit),
+    ("Bool", TokenKind::Bool),
+    ("I8", TokenKind::I8),
+    ("I16", TokenKind::I16),
+    ("I32", TokenKind::I32),
+    ("I64", TokenKind::I64),
+    ("I128", TokenKind::I128),
+    ("U8", TokenKind::U8),
+    ("U16", TokenKind::U16),
+    ("U32", TokenKind::U32),
+    ("U64", TokenKind::U64),
+    ("U128", TokenKind::U128),
+    ("String", TokenKind::String),
+    ("Struct", TokenKind::Struct),
+    ("Enum", TokenKind::Enum),
+    ("EnumU16", TokenKind::EnumU16),
+    ("EnumString", TokenKind::EnumString),
+    ("Option", TokenKind::Option),
+    ("Box", TokenKind::Box),
+    ("Array", TokenKind::Array),
+    ("Tuple", TokenKind::Tuple),
+    ("Result", TokenKind::Result),
+    ("Vec", TokenKind::Vec),
+    ("TreeSet", TokenKind::TreeSet),
+    ("TreeMap", TokenKind::TreeMap),
+    ("HashSet", TokenKind::HashSet),
+    ("HashMap", TokenKind::HashMap),
+    ("Decimal", TokenKind::Decimal),
+    ("Address", TokenKind::Address),
+    ("Hash", TokenKind::Hash),
+    ("Bucket", TokenKind::Bucket),
+    ("BucketRef", TokenKind::BucketRef),
+    ("LazyMap", TokenKind::LazyMap),
+    ("Vault", TokenKind::Vault),
+    ("Bytes", TokenKind::Bytes),
+    ("Blob", TokenKind::Blob),
+    ("Some", TokenKind::Some),
+    ("None", TokenKind::None),
+    ("Ok", TokenKind::Ok),
+    ("Err", TokenKind::Err),
+];
+
+/// Instruction names, with the tokens they are lexed into.
+pub const INSTRUCTIONS: [(&str, TokenKind); 17] = [
+    ("LET", TokenKind::Let),
+    ("DECLARE_TEMP_BUCKET", TokenKind::DeclareTempBucket),
+    ("DECLARE_TEMP_BUCKET_REF", TokenKind::DeclareTempBucketRef),
+    ("TAKE_FROM_CONTEXT", TokenKind::TakeFromContext),
+    ("TAKE_ALL_FROM_CONTEXT", TokenKind::TakeAllFromContext),
+    ("RETURN_TO_CONTEXT", TokenKind::ReturnToContext),
+    ("BORROW_FROM_CONTEXT", TokenKind::BorrowFromContext),
+    (
+        "CREATE_BUCKET_REF_FROM_BUCKET",
+        TokenKind::CreateBucketRefFromBucket,
+    ),
+    ("CLONE_BUCKET_REF", TokenKind::CloneBucketRef),
+    ("CALL_FUNCTION", TokenKind::CallFunction),
+    ("CALL_METHOD", TokenKind::CallMethod),
+    ("ASSERT_CONTEXT_CONTAINS", TokenKind::AssertContextContains),
+    (
+        "ASSERT_BUCKET_AMOUNT_AT_LEAST",
+        TokenKind::AssertBucketAmountAtLeast,
+    ),
+    ("DROP_ALL_BUCKET_REFS", TokenKind::DropAllBucketRefs),
+    ("DEPOSIT_ALL_BUCKETS", TokenKind::DepositAllBuckets),
+    ("PUBLISH_PACKAGE", TokenKind::PublishPackage),
+    ("END", TokenKind::End),
+];
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Token {
     pub kind: TokenKind,
@@ -96,9 +187,8 @@ pub struct Token {
 pub enum LexerError {
     UnexpectedEof,
     UnexpectedChar(char, usize),
-    InvalidNumber(String),
-    InvalidUnicode(u32),
-    UnknownIdentifier(String),
+    InvalidNumber(String, Span),
+    InvalidUnicode(u32, usize),
 }
 
 #[derive(Debug, Clone)]
@@ -107,19 +197,22 @@ pub struct Lexer {
     text: Vec<char>,
     /// A 0-indexed cursor indicating the next char
     current: usize,
+    /// The spans of the comments skipped so far
+    comments: Vec<Span>,
 }
 
 pub fn tokenize(s: &str) -> Result<Vec<Token>, LexerError> {
+    tokenize_with_comments(s).map(|(tokens, _)| tokens)
+}
+
+/// Tokenizes the input text, also returning the spans of its comments.
+pub fn tokenize_with_comments(s: &str) -> Result<(Vec<Token>, Vec<Span>), LexerError> {
     let mut lexer = Lexer::new(s);
     let mut tokens = Vec::new();
-    loop {
-        if let Some(token) = lexer.next_token()? {
-            tokens.push(token);
-        } else {
-            break;
-        }
+    while let Some(token) = lexer.next_token()? {
+        tokens.push(token);
     }
-    Ok(tokens)
+    Ok((tokens, lexer.comments))
 }
 
 impl Lexer {
@@ -127,6 +220,7 @@ impl Lexer {
         Self {
             text: text.chars().collect(),
             current: 0,
+            comments: Vec::new(),
         }
     }
 
@@ -155,14 +249,16 @@ impl Lexer {
 
     pub fn next_token(&mut self) -> Result<Option<Token>, LexerError> {
         // skip comment and whitespace
-        let mut in_comment = false;
         while !self.is_eof() {
-            if in_comment {
-                if self.advance()? == '\n' {
-                    in_comment = false;
+            if self.peek()? == '#' || self.text[self.current..].starts_with(&['/', '/']) {
+                let start = self.current;
+                while !self.is_eof() && self.peek()? != '\n' {
+                    self.advance()?;
                 }
-            } else if self.peek()? == '#' {
-                in_comment = true;
+                self.comments.push(Span {
+                    start,
+                    end: self.current,
+                });
             } else if Self::is_whitespace(self.peek()?) {
                 self.advance()?;
             } else {
@@ -180,7 +276,10 @@ impl Lexer {
             '-' | '0'..='9' => self.tokenize_number(),
             '"' => self.tokenize_string(),
             'a'..='z' | 'A'..='Z' => self.tokenize_identifier(),
-            '{' | '}' | '(' | ')' | '<' | '>' | ',' | ';' => self.tokenize_punctuation(),
+            '$' => self.tokenize_variable(),
+            '{' | '}' | '(' | ')' | '<' | '>' | ',' | ';' | '=' | ':' => {
+                self.tokenize_punctuation()
+            }
             _ => Err(LexerError::UnexpectedChar(
                 self.text[self.current],
                 self.current,
@@ -218,41 +317,41 @@ impl Lexer {
             'i' => match self.advance()? {
                 '1' => match self.advance()? {
                     '2' => match self.advance()? {
-                        '8' => Self::parse_int(&s, "i128", TokenKind::I128Literal),
+                        '8' => self.parse_int(start, &s, "i128", TokenKind::I128Literal),
                         _ => Err(self.unexpected_char()),
                     },
-                    '6' => Self::parse_int(&s, "i16", TokenKind::I16Literal),
+                    '6' => self.parse_int(start, &s, "i16", TokenKind::I16Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '3' => match self.advance()? {
-                    '2' => Self::parse_int(&s, "i32", TokenKind::I32Literal),
+                    '2' => self.parse_int(start, &s, "i32", TokenKind::I32Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '6' => match self.advance()? {
-                    '4' => Self::parse_int(&s, "i64", TokenKind::I64Literal),
+                    '4' => self.parse_int(start, &s, "i64", TokenKind::I64Literal),
                     _ => Err(self.unexpected_char()),
                 },
-                '8' => Self::parse_int(&s, "i8", TokenKind::I8Literal),
+                '8' => self.parse_int(start, &s, "i8", TokenKind::I8Literal),
                 _ => Err(self.unexpected_char()),
             },
             'u' => match self.advance()? {
                 '1' => match self.advance()? {
                     '2' => match self.advance()? {
-                        '8' => Self::parse_int(&s, "u128", TokenKind::U128Literal),
+                        '8' => self.parse_int(start, &s, "u128", TokenKind::U128Literal),
                         _ => Err(self.unexpected_char()),
                     },
-                    '6' => Self::parse_int(&s, "u16", TokenKind::U16Literal),
+                    '6' => self.parse_int(start, &s, "u16", TokenKind::U16Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '3' => match self.advance()? {
-                    '2' => Self::parse_int(&s, "u32", TokenKind::U32Literal),
+                    '2' => self.parse_int(start, &s, "u32", TokenKind::U32Literal),
                     _ => Err(self.unexpected_char()),
                 },
                 '6' => match self.advance()? {
-                    '4' => Self::parse_int(&s, "u64", TokenKind::U64Literal),
+                    '4' => self.parse_int(start, &s, "u64", TokenKind::U64Literal),
                     _ => Err(self.unexpected_char()),
                 },
-                '8' => Self::parse_int(&s, "u8", TokenKind::U8Literal),
+                '8' => self.parse_int(start, &s, "u8", TokenKind::U8Literal),
                 _ => Err(self.unexpected_char()),
             },
             _ => Err(self.unexpected_char()),
@@ -261,13 +360,21 @@ impl Lexer {
     }
 
     fn parse_int<T: FromStr>(
+        &self,
+        start: usize,
         int: &str,
         ty: &str,
         map: fn(T) -> TokenKind,
     ) -> Result<TokenKind, LexerError> {
-        int.parse::<T>()
-            .map(map)
-            .map_err(|_| LexerError::InvalidNumber(format!("{}{}", int, ty)))
+        int.parse::<T>().map(map).map_err(|_| {
+            LexerError::InvalidNumber(
+                format!("{}{}", int, ty),
+                Span {
+                    start,
+                    end: self.current,
+                },
+            )
+        })
     }
 
     fn tokenize_string(&mut self) -> Result<Token, LexerError> {
@@ -290,7 +397,7 @@ impl Lexer {
                     't' => s.push('\t'),
                     'u' => {
                         let mut unicode = self.read_utf16_unit()?;
-                        if unicode >= 0xD800 && unicode <= 0xDFFF {
+                        if (0xD800..=0xDFFF).contains(&unicode) {
                             if self.advance()? == '\\' && self.advance()? == 'u' {
                                 unicode = 0x10000
                                     + ((unicode - 0xD800) << 10)
@@ -299,7 +406,10 @@ impl Lexer {
                                 return Err(self.unexpected_char());
                             }
                         }
-                        s.push(char::from_u32(unicode).ok_or(LexerError::InvalidUnicode(unicode))?);
+                        s.push(
+                            char::from_u32(unicode)
+                                .ok_or(LexerError::InvalidUnicode(unicode, self.current))?,
+                        );
                     }
                     _ => {
                         return Err(self.unexpected_char());
@@ -329,67 +439,39 @@ impl Lexer {
         Ok(code)
     }
 
-    fn tokenize_identifier(&mut self) -> Result<Token, LexerError> {
-        let start = self.current;
-
+    fn read_name(&mut self) -> Result<String, LexerError> {
         let mut id = String::from(self.advance()?);
         while !self.is_eof() && (self.peek()?.is_ascii_alphanumeric() || self.peek()? == '_') {
             id.push(self.advance()?);
         }
+        Ok(id)
+    }
 
-        match id.as_str() {
-            "true" => Ok(TokenKind::BoolLiteral(true)),
-            "false" => Ok(TokenKind::BoolLiteral(false)),
-
-            "Unit" => Ok(TokenKind::Unit),
-            "Bool" => Ok(TokenKind::Bool),
-            "I8" => Ok(TokenKind::I8),
-            "I16" => Ok(TokenKind::I16),
-            "I32" => Ok(TokenKind::I32),
-            "I128" => Ok(TokenKind::I128),
-            "U8" => Ok(TokenKind::U8),
-            "U32" => Ok(TokenKind::U32),
-            "U64" => Ok(TokenKind::U64),
-            "U128" => Ok(TokenKind::U128),
-            "String" => Ok(TokenKind::String),
-            "Struct" => Ok(TokenKind::Struct),
-            "Enum" => Ok(TokenKind::Enum),
-            "Option" => Ok(TokenKind::Option),
-            "Box" => Ok(TokenKind::Box),
-            "Array" => Ok(TokenKind::Array),
-            "Tuple" => Ok(TokenKind::Tuple),
-            "Result" => Ok(TokenKind::Result),
-            "Vec" => Ok(TokenKind::Vec),
-            "TreeSet" => Ok(TokenKind::TreeSet),
-            "TreeMap" => Ok(TokenKind::TreeMap),
-            "HashSet" => Ok(TokenKind::HashSet),
-            "HashMap" => Ok(TokenKind::HashMap),
-            "Decimal" => Ok(TokenKind::Decimal),
-            "BigDecimal" => Ok(TokenKind::BigDecimal),
-            "Address" => Ok(TokenKind::Address),
-            "Hash" => Ok(TokenKind::Hash),
-            "Bucket" => Ok(TokenKind::Bucket),
-            "BucketRef" => Ok(TokenKind::BucketRef),
-            "LazyMap" => Ok(TokenKind::LazyMap),
-            "Vault" => Ok(TokenKind::Vault),
-
-            "Some" => Ok(TokenKind::Some),
-            "None" => Ok(TokenKind::None),
-            "Ok" => Ok(TokenKind::Ok),
-            "Err" => Ok(TokenKind::Err),
-
-            "DECLARE_TEMP_BUCKET" => Ok(TokenKind::DeclareTempBucket),
-            "DECLARE_TEMP_BUCKET_REF" => Ok(TokenKind::DeclareTempBucketRef),
-            "TAKE_FROM_CONTEXT" => Ok(TokenKind::TakeFromContext),
-            "BORROW_FROM_CONTEXT" => Ok(TokenKind::BorrowFromContext),
-            "CALL_FUNCTION" => Ok(TokenKind::CallFunction),
-            "CALL_METHOD" => Ok(TokenKind::CallMethod),
-            "DROP_ALL_BUCKET_REFS" => Ok(TokenKind::DropAllBucketRefs),
-            "DEPOSIT_ALL_BUCKETS" => Ok(TokenKind::DepositAllBuckets),
-
-            s @ _ => Err(LexerError::UnknownIdentifier(s.into())),
+    fn tokenize_variable(&mut self) -> Result<Token, LexerError> {
+        let start = self.current;
+        assert_eq!(self.advance()?, '$');
+        if !self.peek()?.is_ascii_alphabetic() {
+            self.advance()?;
+            return Err(self.unexpected_char());
         }
-        .map(|kind| self.new_token(kind, start))
+        let name = self.read_name()?;
+        Ok(self.new_token(TokenKind::Variable(name), start))
+    }
+
+    fn tokenize_identifier(&mut self) -> Result<Token, LexerError> {
+        let start = self.current;
+        let id = self.read_name()?;
+
+        let kind = match KEYWORDS
+            .iter()
+            .chain(INSTRUCTIONS.iter())
+            .find(|(keyword, _)| *keyword == id)
+        {
+            Some((_, kind)) => kind.clone(),
+            // the parser decides where a name may appear
+            None => TokenKind::Identifier(id),
+        };
+        Ok(self.new_token(kind, start))
     }
 
     fn tokenize_punctuation(&mut self) -> Result<Token, LexerError> {
@@ -404,6 +486,8 @@ impl Lexer {
             '>' => TokenKind::GreaterThan,
             ',' => TokenKind::Comma,
             ';' => TokenKind::Semicolon,
+            '=' => TokenKind::Equals,
+            ':' => TokenKind::Colon,
             _ => {
                 return Err(self.unexpected_char());
             }
@@ -457,7 +541,7 @@ mod tests {
                     }
                     Err(e) => {
                         assert_eq!(e, $expected);
-                        return;
+                        break;
                     }
                 }
             }
@@ -475,9 +559,9 @@ mod tests {
     fn test_bool() {
         lex_ok!("true", vec![TokenKind::BoolLiteral(true)]);
         lex_ok!("false", vec![TokenKind::BoolLiteral(false)]);
-        lex_error!(
+        lex_ok!(
             "false123u8",
-            LexerError::UnknownIdentifier("false123u8".into())
+            vec![TokenKind::Identifier("false123u8".into())]
         );
     }
 
@@ -507,6 +591,39 @@ mod tests {
             "# multiple\n# line\nCALL_FUNCTION",
             vec![TokenKind::CallFunction,]
         );
+        lex_ok!("// 1u8", Vec::<TokenKind>::new());
+        lex_ok!(
+            "1u8 // comment\n2u8",
+            vec![TokenKind::U8Literal(1), TokenKind::U8Literal(2)]
+        );
+        lex_error!("/ 1u8", LexerError::UnexpectedChar('/', 0));
+        assert_eq!(
+            tokenize_with_comments("# a\n1u8 // b").map(|(_, comments)| comments),
+            Ok(vec![Span { start: 0, end: 3 }, Span { start: 8, end: 12 }])
+        );
+    }
+
+    #[test]
+    fn test_let() {
+        lex_ok!(
+            r#"LET xrd = $DEFAULT_ACCOUNT;"#,
+            vec![
+                TokenKind::Let,
+                TokenKind::Identifier("xrd".into()),
+                TokenKind::Equals,
+                TokenKind::Variable("DEFAULT_ACCOUNT".into()),
+                TokenKind::Semicolon,
+            ]
+        );
+        lex_ok!(
+            "xrd = LET",
+            vec![
+                TokenKind::Identifier("xrd".into()),
+                TokenKind::Equals,
+                TokenKind::Let,
+            ]
+        );
+        lex_error!("$ xrd", LexerError::UnexpectedChar(' ', 1));
     }
 
     #[test]
@@ -0,0 +1,87 @@
+pub mod ast;
+pub mod compiler;
+pub mod decompiler;
+pub mod diagnostic;
+pub mod formatter;
+pub mod lexer;
+pub mod linter;
+pub mod parser;
+pub mod typechecker;
+
+use radix_engine::transaction::{AbiProvider, Transaction};
+use scrypto::types::H256;
+use std::collections::HashMap;
+
+pub use decompiler::decompile;
+pub use formatter::format;
+pub use linter::lint;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileError {
+    LexerError(lexer::LexerError),
+    ParserError(parser::ParserError),
+    CompilerError(compiler::CompilerError),
+}
+
+/// Inputs supplied alongside manifest text.
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    /// The values of `$name` variables
+    pub variables: HashMap<String, ast::Value>,
+    /// The text of template parameter values, read according to their declared types
+    pub args: HashMap<String, String>,
+    /// The blobs referred to by `Blob("<hash>")`, keyed by the SHA-256 hash of their content
+    pub blobs: HashMap<H256, Vec<u8>>,
+}
+
+/// Compiles manifest text into a transaction.
+pub fn compile(s: &str) -> Result<Transaction, CompileError> {
+    compile_with_env(s, &Environment::default())
+}
+
+/// Compiles manifest text into a transaction, resolving variables and blobs from the environment.
+pub fn compile_with_env(s: &str, env: &Environment) -> Result<Transaction, CompileError> {
+    let tx = parse(s, env)?;
+    compiler::compile_transaction(&tx).map_err(CompileError::CompilerError)
+}
+
+/// Compiles manifest text into a transaction, resolving variables and blobs from the environment
+/// and checking call arguments against blueprint ABIs.
+pub fn compile_with_abi<A: AbiProvider>(
+    s: &str,
+    env: &Environment,
+    abi_provider: &A,
+) -> Result<Transaction, CompileError> {
+    let mut tx = parse(s, env)?;
+    typechecker::check_transaction(&mut tx, abi_provider).map_err(CompileError::CompilerError)?;
+    compiler::compile_transaction(&tx).map_err(CompileError::CompilerError)
+}
+
+/// Parses a single manifest value, such as a variable given on the command line.
+pub fn parse_value(s: &str) -> Result<ast::Value, CompileError> {
+    let tokens = lexer::tokenize(s).map_err(CompileError::LexerError)?;
+    let mut parser = parser::Parser::new(tokens);
+    let value = parser.parse_value().map_err(CompileError::ParserError)?;
+    if !parser.is_eof() {
+        let token = parser.peek().map_err(CompileError::ParserError)?;
+        return Err(CompileError::ParserError(
+            parser::ParserError::UnexpectedToken {
+                actual: token,
+                expected: parser::Expected::Eof,
+            },
+        ));
+    }
+    Ok(value)
+}
+
+fn parse(s: &str, env: &Environment) -> Result<ast::Transaction, CompileError> {
+    let tokens = lexer::tokenize(s).map_err(CompileError::LexerError)?;
+    let tx = parser::Parser::new(tokens)
+        .parse_transaction()
+        .map_err(CompileError::ParserError)?;
+    let variables = compiler::bind_parameters(&tx, &env.variables, &env.args)
+        .map_err(CompileError::CompilerError)?;
+    let tx =
+        compiler::substitute_variables(&tx, &variables).map_err(CompileError::CompilerError)?;
+    compiler::resolve_blobs(&tx, &env.blobs).map_err(CompileError::CompilerError)
+}
@@ -0,0 +1,273 @@
+use crate::ast::{Instruction, Transaction, Value};
+use crate::compiler::visit_values;
+use crate::lexer::Span;
+use std::collections::HashMap;
+
+/// A likely mistake in a manifest, which doesn't stop it from compiling.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub kind: LintKind,
+    /// The span of the offending instruction or operand
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintKind {
+    UnusedBucket(String),
+    BucketConsumedTwice(String),
+    BucketRefNotDropped(String),
+    MissingDepositAll,
+}
+
+struct BucketState {
+    declared: Span,
+    used: bool,
+    consumed: bool,
+}
+
+#[derive(Default)]
+struct Linter {
+    lints: Vec<Lint>,
+    buckets: HashMap<String, BucketState>,
+    bucket_refs: HashMap<String, Span>,
+}
+
+/// Checks a parsed manifest for likely mistakes, returning them in source order.
+///
+/// Buckets and bucket refs are tracked by name; those named through variables are only matched
+/// by variable name.
+pub fn lint(tx: &Transaction) -> Vec<Lint> {
+    let mut linter = Linter::default();
+
+    for (instruction, span) in tx.instructions.iter().zip(&tx.spans) {
+        let args = &span.args;
+        match instruction {
+            Instruction::TakeFromContext { to, .. }
+            | Instruction::TakeAllFromContext { to, .. } => {
+                linter.declare_bucket(to, &args[args.len() - 1])
+            }
+            Instruction::ReturnToContext { bucket } => linter.consume_bucket(bucket, &args[0]),
+            Instruction::AssertBucketAmountAtLeast { bucket, .. } => linter.use_bucket(bucket),
+            Instruction::CreateBucketRefFromBucket { bucket, to } => {
+                linter.use_bucket(bucket);
+                linter.create_bucket_ref(to, &args[1]);
+            }
+            Instruction::BorrowFromContext { to, .. } => linter.create_bucket_ref(to, &args[2]),
+            Instruction::CloneBucketRef { to, .. } => linter.create_bucket_ref(to, &args[1]),
+            Instruction::CallFunction { .. } | Instruction::CallMethod { .. } => {
+                // Buckets and bucket refs passed to a call, however nested, are moved into it
+                let mut instruction = instruction.clone();
+                for (value, arg_span) in instruction.values_mut().into_iter().zip(args) {
+                    let mut moved = Vec::new();
+                    visit_values(value, &mut |v| {
+                        moved.push(v.clone());
+                        Ok(())
+                    })
+                    .unwrap();
+                    for v in moved {
+                        match v {
+                            Value::Bucket(_) => linter.consume_bucket(&v, arg_span),
+                            Value::BucketRef(_) => linter.drop_bucket_ref(&v),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            Instruction::DropAllBucketRefs => linter.bucket_refs.clear(),
+            _ => {}
+        }
+    }
+
+    let mut lints = linter.lints;
+    for (name, state) in linter.buckets {
+        if !state.used {
+            lints.push(Lint {
+                kind: LintKind::UnusedBucket(name),
+                span: state.declared,
+            });
+        }
+    }
+    for (name, span) in linter.bucket_refs {
+        lints.push(Lint {
+            kind: LintKind::BucketRefNotDropped(name),
+            span,
+        });
+    }
+    if let Some((last, span)) = tx
+        .instructions
+        .iter()
+        .zip(&tx.spans)
+        .rev()
+        .find(|(i, _)| !matches!(i, Instruction::Let { .. } | Instruction::End { .. }))
+    {
+        if !matches!(last, Instruction::DepositAllBuckets { .. }) {
+            lints.push(Lint {
+                kind: LintKind::MissingDepositAll,
+                span: span.span.clone(),
+            });
+        }
+    }
+
+    lints.sort_by_key(|lint| lint.span.start);
+    lints
+}
+
+impl Linter {
+    fn declare_bucket(&mut self, value: &Value, span: &Span) {
+        if let Some(name) = name_of(value) {
+            // Taking into a bucket that's still held adds to it
+            match self.buckets.get(&name) {
+                Some(state) if !state.consumed => {}
+                _ => {
+                    self.buckets.insert(
+                        name,
+                        BucketState {
+                            declared: span.clone(),
+                            used: false,
+                            consumed: false,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn use_bucket(&mut self, value: &Value) {
+        if let Some(state) = name_of(value).and_then(|name| self.buckets.get_mut(&name)) {
+            state.used = true;
+        }
+    }
+
+    fn consume_bucket(&mut self, value: &Value, span: &Span) {
+        if let Some(name) = name_of(value) {
+            if let Some(state) = self.buckets.get_mut(&name) {
+                if state.consumed {
+                    self.lints.push(Lint {
+                        kind: LintKind::BucketConsumedTwice(name),
+                        span: span.clone(),
+                    });
+                } else {
+                    state.used = true;
+                    state.consumed = true;
+                }
+            }
+        }
+    }
+
+    fn create_bucket_ref(&mut self, value: &Value, span: &Span) {
+        if let Some(name) = name_of(value) {
+            self.bucket_refs.insert(name, span.clone());
+        }
+    }
+
+    fn drop_bucket_ref(&mut self, value: &Value) {
+        if let Some(name) = name_of(value) {
+            self.bucket_refs.remove(&name);
+        }
+    }
+}
+
+/// Returns the name of a bucket or bucket ref, prefixing names given by variables with `$`.
+fn name_of(value: &Value) -> Option<String> {
+    match value {
+        Value::Bucket(inner) | Value::BucketRef(inner) => match &**inner {
+            Value::String(name) => Some(name.clone()),
+            Value::Variable(name) => Some(format!("${}", name)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+    use crate::parser::Parser;
+
+    #[macro_export]
+    macro_rules! lint_kinds {
+        ( $s:expr ) => {{
+            let tx = Parser::new(tokenize($s).unwrap())
+                .parse_transaction()
+                .unwrap();
+            lint(&tx)
+                .into_iter()
+                .map(|lint| lint.kind)
+                .collect::<Vec<LintKind>>()
+        }};
+    }
+
+    #[test]
+    fn test_clean() {
+        assert_eq!(
+            lint_kinds!(
+                r#"TAKE_ALL_FROM_CONTEXT Address("030000000000000000000000000000000000000000000000000004") Bucket("xrd");
+                CREATE_BUCKET_REF_FROM_BUCKET Bucket("xrd") BucketRef("proof");
+                CALL_METHOD Address("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1") "swap" Vec<Bucket>(Bucket("xrd")) BucketRef("proof");
+                DEPOSIT_ALL_BUCKETS $DEFAULT_ACCOUNT;
+                END Vec<Address>(Address("04005feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9"));"#
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_buckets() {
+        assert_eq!(
+            lint_kinds!(
+                r#"TAKE_ALL_FROM_CONTEXT Address("030000000000000000000000000000000000000000000000000004") Bucket("xrd");
+                TAKE_ALL_FROM_CONTEXT Address("030000000000000000000000000000000000000000000000000004") Bucket("unused");
+                CALL_METHOD Address("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1") "deposit" Bucket("xrd");
+                RETURN_TO_CONTEXT Bucket("xrd");
+                DEPOSIT_ALL_BUCKETS $DEFAULT_ACCOUNT;"#
+            ),
+            vec![
+                LintKind::UnusedBucket("unused".into()),
+                LintKind::BucketConsumedTwice("xrd".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bucket_refs() {
+        let tx = Parser::new(
+            tokenize(
+                r#"BORROW_FROM_CONTEXT Decimal("1") Address("030000000000000000000000000000000000000000000000000004") BucketRef("a");
+                CLONE_BUCKET_REF BucketRef("a") BucketRef("b");
+                CALL_METHOD Address("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1") "check" BucketRef("a");"#,
+            )
+            .unwrap(),
+        )
+        .parse_transaction()
+        .unwrap();
+        assert_eq!(
+            lint(&tx),
+            vec![
+                Lint {
+                    kind: LintKind::BucketRefNotDropped("b".into()),
+                    span: Span {
+                        start: 163,
+                        end: 177
+                    },
+                },
+                Lint {
+                    kind: LintKind::MissingDepositAll,
+                    span: Span {
+                        start: 195,
+                        end: 296
+                    },
+                },
+            ]
+        );
+
+        assert_eq!(
+            lint_kinds!(
+                r#"BORROW_FROM_CONTEXT Decimal("1") Address("030000000000000000000000000000000000000000000000000004") BucketRef("a");
+                DROP_ALL_BUCKET_REFS;
+                DEPOSIT_ALL_BUCKETS $DEFAULT_ACCOUNT;"#
+            ),
+            vec![]
+        );
+    }
+}
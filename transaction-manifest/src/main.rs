@@ -1,6 +1,194 @@
-pub mod ast;
-pub mod compiler;
-pub mod lexer;
-pub mod parser;
+use clap::{crate_version, App, Arg, ArgMatches, SubCommand};
+use scrypto::utils::sha256;
+use std::io;
+use transaction_manifest::diagnostic::{render, render_lint};
+use transaction_manifest::{compile_with_env, format, lint, CompileError, Environment};
 
-fn main() {}
+const CMD_FMT: &str = "fmt";
+const CMD_CHECK: &str = "check";
+const CMD_LINT: &str = "lint";
+
+const ARG_MANIFEST: &str = "MANIFEST";
+const ARG_MANIFESTS: &str = "MANIFESTS";
+const ARG_CHECK: &str = "CHECK";
+const ARG_DEFINE: &str = "DEFINE";
+const ARG_BLOB: &str = "BLOB";
+const ARG_ARG: &str = "ARG";
+
+/// Represents a manifest tool error.
+#[derive(Debug)]
+pub enum Error {
+    MissingSubCommand,
+
+    MissingArgument(String),
+
+    InvalidDefinition(String),
+
+    IOError(io::Error),
+
+    CompileError(CompileError),
+
+    NotFormatted(Vec<String>),
+
+    LintsFound(usize),
+}
+
+pub fn main() -> Result<(), Error> {
+    let app = App::new("Transaction Manifest")
+        .name("transaction-manifest")
+        .about("Format, check and lint transaction manifests.")
+        .version(crate_version!())
+        .subcommand(
+            SubCommand::with_name(CMD_FMT)
+                .about("Formats manifests in place")
+                .arg(
+                    Arg::with_name(ARG_MANIFESTS)
+                        .help("Specify the manifest paths.")
+                        .required(true)
+                        .multiple(true),
+                )
+                .arg(
+                    Arg::with_name(ARG_CHECK)
+                        .long("check")
+                        .help("Report unformatted manifests instead of rewriting them."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(CMD_CHECK)
+                .about("Compiles a manifest and reports any errors")
+                .arg(
+                    Arg::with_name(ARG_MANIFEST)
+                        .help("Specify the manifest path.")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name(ARG_DEFINE)
+                        .long("define")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Define a manifest variable, e.g. NAME=Address(\"...\")."),
+                )
+                .arg(
+                    Arg::with_name(ARG_BLOB)
+                        .long("blob")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Attach a file, referred to as Blob(\"<sha256 hash>\")."),
+                )
+                .arg(
+                    Arg::with_name(ARG_ARG)
+                        .long("arg")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Fill in a manifest parameter, e.g. amount=100."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(CMD_LINT)
+                .about("Warns about likely mistakes in a manifest")
+                .arg(
+                    Arg::with_name(ARG_MANIFEST)
+                        .help("Specify the manifest path.")
+                        .required(true),
+                ),
+        );
+    let matches = app.get_matches();
+
+    match matches.subcommand() {
+        (CMD_FMT, Some(m)) => handle_fmt(m),
+        (CMD_CHECK, Some(m)) => handle_check(m),
+        (CMD_LINT, Some(m)) => handle_lint(m),
+        _ => Err(Error::MissingSubCommand),
+    }
+}
+
+/// Handles a `fmt` request.
+fn handle_fmt(matches: &ArgMatches) -> Result<(), Error> {
+    let check = matches.is_present(ARG_CHECK);
+
+    let mut unformatted = Vec::new();
+    for path in matches.values_of(ARG_MANIFESTS).into_iter().flatten() {
+        let manifest = read_manifest(path)?;
+        let formatted = format(&manifest).map_err(|e| report(&manifest, e))?;
+        if formatted != manifest {
+            if check {
+                println!("{}", path);
+                unformatted.push(path.to_owned());
+            } else {
+                std::fs::write(path, formatted).map_err(Error::IOError)?;
+            }
+        }
+    }
+
+    if unformatted.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::NotFormatted(unformatted))
+    }
+}
+
+/// Handles a `check` request.
+fn handle_check(matches: &ArgMatches) -> Result<(), Error> {
+    let manifest = read_manifest(match_arg(matches, ARG_MANIFEST)?)?;
+
+    let mut env = Environment::default();
+    for definition in matches.values_of(ARG_DEFINE).into_iter().flatten() {
+        let (key, value) = definition
+            .split_once('=')
+            .ok_or_else(|| Error::InvalidDefinition(definition.to_owned()))?;
+        let value = transaction_manifest::parse_value(value).map_err(|e| report(value, e))?;
+        env.variables.insert(key.trim().to_owned(), value);
+    }
+    for argument in matches.values_of(ARG_ARG).into_iter().flatten() {
+        let (key, value) = argument
+            .split_once('=')
+            .ok_or_else(|| Error::InvalidDefinition(argument.to_owned()))?;
+        env.args.insert(key.trim().to_owned(), value.to_owned());
+    }
+    for path in matches.values_of(ARG_BLOB).into_iter().flatten() {
+        let content = std::fs::read(path).map_err(Error::IOError)?;
+        env.blobs.insert(sha256(&content), content);
+    }
+
+    compile_with_env(&manifest, &env).map_err(|e| report(&manifest, e))?;
+    Ok(())
+}
+
+/// Handles a `lint` request.
+fn handle_lint(matches: &ArgMatches) -> Result<(), Error> {
+    let manifest = read_manifest(match_arg(matches, ARG_MANIFEST)?)?;
+    let tokens = transaction_manifest::lexer::tokenize(&manifest)
+        .map_err(|e| report(&manifest, CompileError::LexerError(e)))?;
+    let tx = transaction_manifest::parser::Parser::new(tokens)
+        .parse_transaction()
+        .map_err(|e| report(&manifest, CompileError::ParserError(e)))?;
+
+    let lints = lint(&tx);
+    for l in &lints {
+        eprint!("{}", render_lint(&manifest, l));
+    }
+    if lints.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::LintsFound(lints.len()))
+    }
+}
+
+fn match_arg<'a>(matches: &'a ArgMatches, name: &str) -> Result<&'a str, Error> {
+    matches
+        .value_of(name)
+        .ok_or_else(|| Error::MissingArgument(name.to_owned()))
+}
+
+fn read_manifest(path: &str) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(Error::IOError)
+}
+
+/// Prints a compile error against its source.
+fn report(source: &str, error: CompileError) -> Error {
+    eprint!("{}", render(source, &error));
+    Error::CompileError(error)
+}
@@ -1,17 +1,44 @@
-use crate::ast::{Instruction, Transaction, Type, Value};
-use crate::lexer::{Token, TokenKind};
+use crate::ast::{Fields, Instruction, InstructionSpan, Parameter, Transaction, Type, Value};
+use crate::lexer::{Span, Token, TokenKind};
+use sbor::describe::Discriminator;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParserError {
     UnexpectedEof,
-    UnexpectedToken(Token),
-    InvalidNumberOfValues { actual: usize, expected: usize },
-    InvalidNumberOfTypes { actual: usize, expected: usize },
+    UnexpectedToken {
+        actual: Token,
+        expected: Expected,
+    },
+    InvalidNumberOfValues {
+        actual: usize,
+        expected: usize,
+        span: Span,
+    },
+    InvalidNumberOfTypes {
+        actual: usize,
+        expected: usize,
+        span: Span,
+    },
+}
+
+/// Describes what the parser was expecting when it hit an unexpected token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    Token(TokenKind),
+    Instruction,
+    Name,
+    Value,
+    Type,
+    Fields,
+    EnumIndex,
+    Eof,
 }
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// The spans of the operands of the instruction being parsed
+    arg_spans: Vec<Span>,
 }
 
 #[macro_export]
@@ -27,14 +54,21 @@ macro_rules! advance_match {
     ( $self:expr, $expected:expr ) => {{
         let token = $self.advance()?;
         if token.kind != $expected {
-            return Err(ParserError::UnexpectedToken(token));
+            return Err(ParserError::UnexpectedToken {
+                actual: token,
+                expected: Expected::Token($expected),
+            });
         }
     }};
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, current: 0 }
+        Self {
+            tokens,
+            current: 0,
+            arg_spans: Vec::new(),
+        }
     }
 
     pub fn is_eof(&self) -> bool {
@@ -55,65 +89,171 @@ impl Parser {
     }
 
     pub fn parse_transaction(&mut self) -> Result<Transaction, ParserError> {
+        let mut parameters = Vec::<Parameter>::new();
         let mut instructions = Vec::<Instruction>::new();
+        let mut spans = Vec::<InstructionSpan>::new();
+
+        // Parameters may only be declared before the first instruction
+        while !self.is_eof() && matches!(self.peek()?.kind, TokenKind::Variable(_)) {
+            parameters.push(self.parse_parameter()?);
+        }
 
         while !self.is_eof() {
+            let start = self.peek()?.span.start;
             instructions.push(self.parse_instruction()?);
+            spans.push(InstructionSpan {
+                span: self.span_from(start),
+                args: self.arg_spans.clone(),
+            });
         }
 
-        Ok(Transaction { instructions })
+        Ok(Transaction {
+            parameters,
+            instructions,
+            spans,
+        })
+    }
+
+    /// Parses a `$name: Type;` parameter declaration.
+    pub fn parse_parameter(&mut self) -> Result<Parameter, ParserError> {
+        let token = self.advance()?;
+        let name = match token.kind {
+            TokenKind::Variable(name) => name,
+            _ => {
+                return Err(ParserError::UnexpectedToken {
+                    actual: token,
+                    expected: Expected::Name,
+                });
+            }
+        };
+        advance_match!(self, TokenKind::Colon);
+        let ty = self.parse_type()?;
+        advance_match!(self, TokenKind::Semicolon);
+        Ok(Parameter {
+            name,
+            ty,
+            span: self.span_from(token.span.start),
+        })
     }
 
     pub fn parse_instruction(&mut self) -> Result<Instruction, ParserError> {
+        self.arg_spans.clear();
         let token = self.advance()?;
         let instruction = match token.kind {
+            TokenKind::Let => {
+                let name = match self.advance()? {
+                    Token {
+                        kind: TokenKind::Identifier(name),
+                        ..
+                    } => name,
+                    token => {
+                        return Err(ParserError::UnexpectedToken {
+                            actual: token,
+                            expected: Expected::Name,
+                        });
+                    }
+                };
+                advance_match!(self, TokenKind::Equals);
+                Instruction::Let {
+                    name,
+                    value: self.parse_arg()?,
+                }
+            }
             TokenKind::DeclareTempBucket => Instruction::DeclareTempBucket,
             TokenKind::DeclareTempBucketRef => Instruction::DeclareTempBucketRef,
             TokenKind::TakeFromContext => Instruction::TakeFromContext {
-                amount: self.parse_value()?,
-                resource_address: self.parse_value()?,
-                to: self.parse_value()?,
+                amount: self.parse_arg()?,
+                resource_address: self.parse_arg()?,
+                to: self.parse_arg()?,
+            },
+            TokenKind::TakeAllFromContext => Instruction::TakeAllFromContext {
+                resource_address: self.parse_arg()?,
+                to: self.parse_arg()?,
+            },
+            TokenKind::ReturnToContext => Instruction::ReturnToContext {
+                bucket: self.parse_arg()?,
             },
             TokenKind::BorrowFromContext => Instruction::BorrowFromContext {
-                amount: self.parse_value()?,
-                resource_address: self.parse_value()?,
-                to: self.parse_value()?,
+                amount: self.parse_arg()?,
+                resource_address: self.parse_arg()?,
+                to: self.parse_arg()?,
+            },
+            TokenKind::CreateBucketRefFromBucket => Instruction::CreateBucketRefFromBucket {
+                bucket: self.parse_arg()?,
+                to: self.parse_arg()?,
+            },
+            TokenKind::CloneBucketRef => Instruction::CloneBucketRef {
+                bucket_ref: self.parse_arg()?,
+                to: self.parse_arg()?,
             },
             TokenKind::CallFunction => Instruction::CallFunction {
-                package_address: self.parse_value()?,
-                blueprint_name: self.parse_value()?,
-                function: self.parse_value()?,
+                package_address: self.parse_arg()?,
+                blueprint_name: self.parse_arg()?,
+                function: self.parse_arg()?,
                 args: {
                     let mut values = vec![];
                     while self.peek()?.kind != TokenKind::Semicolon {
-                        values.push(self.parse_value()?);
+                        values.push(self.parse_arg()?);
                     }
                     values
                 },
             },
             TokenKind::CallMethod => Instruction::CallMethod {
-                component_address: self.parse_value()?,
-                method: self.parse_value()?,
+                component_address: self.parse_arg()?,
+                method: self.parse_arg()?,
                 args: {
                     let mut values = vec![];
                     while self.peek()?.kind != TokenKind::Semicolon {
-                        values.push(self.parse_value()?);
+                        values.push(self.parse_arg()?);
                     }
                     values
                 },
             },
+            TokenKind::AssertContextContains => Instruction::AssertContextContains {
+                amount: self.parse_arg()?,
+                resource_address: self.parse_arg()?,
+            },
+            TokenKind::AssertBucketAmountAtLeast => Instruction::AssertBucketAmountAtLeast {
+                bucket: self.parse_arg()?,
+                amount: self.parse_arg()?,
+            },
             TokenKind::DropAllBucketRefs => Instruction::DropAllBucketRefs,
             TokenKind::DepositAllBuckets => Instruction::DepositAllBuckets {
-                account: self.parse_value()?,
+                account: self.parse_arg()?,
+            },
+            TokenKind::PublishPackage => Instruction::PublishPackage {
+                code: self.parse_arg()?,
+            },
+            TokenKind::End => Instruction::End {
+                signers: self.parse_arg()?,
             },
             _ => {
-                return Err(ParserError::UnexpectedToken(token));
+                return Err(ParserError::UnexpectedToken {
+                    actual: token,
+                    expected: Expected::Instruction,
+                });
             }
         };
         advance_match!(self, TokenKind::Semicolon);
         Ok(instruction)
     }
 
+    /// Parses an instruction operand, recording its span.
+    fn parse_arg(&mut self) -> Result<Value, ParserError> {
+        let start = self.peek()?.span.start;
+        let value = self.parse_value()?;
+        self.arg_spans.push(self.span_from(start));
+        Ok(value)
+    }
+
+    /// Returns the span from the given position to the end of the last consumed token.
+    fn span_from(&self, start: usize) -> Span {
+        Span {
+            start,
+            end: self.tokens[self.current - 1].span.end,
+        }
+    }
+
     pub fn parse_value(&mut self) -> Result<Value, ParserError> {
         let token = self.peek()?;
         match token.kind {
@@ -134,6 +274,7 @@ impl Parser {
             TokenKind::I64Literal(value) => advance_ok!(self, Value::I64(value)),
             TokenKind::I128Literal(value) => advance_ok!(self, Value::I128(value)),
             TokenKind::StringLiteral(value) => advance_ok!(self, Value::String(value)),
+            TokenKind::Variable(name) => advance_ok!(self, Value::Variable(name)),
             TokenKind::Struct => self.parse_struct(),
             TokenKind::Enum => self.parse_enum(),
             TokenKind::Some | TokenKind::None => self.parse_option(),
@@ -147,14 +288,18 @@ impl Parser {
             TokenKind::HashSet => self.parse_hash_set(),
             TokenKind::HashMap => self.parse_hash_map(),
             TokenKind::Decimal
-            | TokenKind::BigDecimal
             | TokenKind::Address
             | TokenKind::Hash
             | TokenKind::Bucket
             | TokenKind::BucketRef
             | TokenKind::LazyMap
-            | TokenKind::Vault => self.parse_scrypto_types(),
-            _ => Err(ParserError::UnexpectedToken(token)),
+            | TokenKind::Vault
+            | TokenKind::Bytes
+            | TokenKind::Blob => self.parse_scrypto_types(),
+            _ => Err(ParserError::UnexpectedToken {
+                actual: token,
+                expected: Expected::Value,
+            }),
         }
     }
     pub fn parse_struct(&mut self) -> Result<Value, ParserError> {
@@ -172,7 +317,10 @@ impl Parser {
                 ),
                 TokenKind::CloseParenthesis => Fields::Unit,
                 _ => {
-                    return Err(ParserError::UnexpectedToken(t));
+                    return Err(ParserError::UnexpectedToken {
+                        actual: t,
+                        expected: Expected::Fields,
+                    });
                 }
             }
         };
@@ -185,12 +333,18 @@ impl Parser {
         advance_match!(self, TokenKind::Enum);
         advance_match!(self, TokenKind::OpenParenthesis);
 
-        // parse the index
+        // parse the discriminator
         let token = self.advance()?;
-        let index = if let TokenKind::U8Literal(value) = token.kind {
-            value
-        } else {
-            return Err(ParserError::UnexpectedToken(token));
+        let discriminator = match token.kind {
+            TokenKind::U8Literal(value) => Discriminator::U8(value),
+            TokenKind::U16Literal(value) => Discriminator::U16(value),
+            TokenKind::StringLiteral(value) => Discriminator::String(value),
+            _ => {
+                return Err(ParserError::UnexpectedToken {
+                    actual: token,
+                    expected: Expected::EnumIndex,
+                });
+            }
         };
 
         // parse named/unnamed fields
@@ -205,7 +359,10 @@ impl Parser {
                     self.parse_values_any(TokenKind::OpenParenthesis, TokenKind::CloseParenthesis)?,
                 ),
                 _ => {
-                    return Err(ParserError::UnexpectedToken(t));
+                    return Err(ParserError::UnexpectedToken {
+                        actual: t,
+                        expected: Expected::Fields,
+                    });
                 }
             }
         } else {
@@ -213,7 +370,7 @@ impl Parser {
         };
 
         advance_match!(self, TokenKind::CloseParenthesis);
-        Ok(Value::Enum(index, fields))
+        Ok(Value::Enum(discriminator, fields))
     }
 
     pub fn parse_option(&mut self) -> Result<Value, ParserError> {
@@ -221,7 +378,10 @@ impl Parser {
         match token.kind {
             TokenKind::Some => Ok(Value::Option(Some(self.parse_values_one()?).into())),
             TokenKind::None => Ok(Value::Option(None.into())),
-            _ => Err(ParserError::UnexpectedToken(token)),
+            _ => Err(ParserError::UnexpectedToken {
+                actual: token,
+                expected: Expected::Token(TokenKind::Some),
+            }),
         }
     }
 
@@ -252,7 +412,10 @@ impl Parser {
         match token.kind {
             TokenKind::Ok => Ok(Value::Result(Ok(self.parse_values_one()?).into())),
             TokenKind::Err => Ok(Value::Result(Err(self.parse_values_one()?).into())),
-            _ => Err(ParserError::UnexpectedToken(token)),
+            _ => Err(ParserError::UnexpectedToken {
+                actual: token,
+                expected: Expected::Token(TokenKind::Ok),
+            }),
         }
     }
 
@@ -309,14 +472,18 @@ impl Parser {
         let token = self.advance()?;
         match token.kind {
             TokenKind::Decimal => Ok(Value::Decimal(self.parse_values_one()?.into())),
-            TokenKind::BigDecimal => Ok(Value::BigDecimal(self.parse_values_one()?.into())),
             TokenKind::Address => Ok(Value::Address(self.parse_values_one()?.into())),
             TokenKind::Hash => Ok(Value::Hash(self.parse_values_one()?.into())),
             TokenKind::Bucket => Ok(Value::Bucket(self.parse_values_one()?.into())),
             TokenKind::BucketRef => Ok(Value::BucketRef(self.parse_values_one()?.into())),
             TokenKind::LazyMap => Ok(Value::LazyMap(self.parse_values_one()?.into())),
             TokenKind::Vault => Ok(Value::Vault(self.parse_values_one()?.into())),
-            _ => Err(ParserError::UnexpectedToken(token)),
+            TokenKind::Bytes => Ok(Value::Bytes(self.parse_values_one()?.into())),
+            TokenKind::Blob => Ok(Value::Blob(self.parse_values_one()?.into())),
+            _ => Err(ParserError::UnexpectedToken {
+                actual: token,
+                expected: Expected::Value,
+            }),
         }
     }
 
@@ -339,12 +506,14 @@ impl Parser {
     }
 
     fn parse_values_one(&mut self) -> Result<Value, ParserError> {
+        let start = self.peek()?.span.start;
         let values =
             self.parse_values_any(TokenKind::OpenParenthesis, TokenKind::CloseParenthesis)?;
         if values.len() != 1 {
             Err(ParserError::InvalidNumberOfValues {
                 actual: values.len(),
                 expected: 1,
+                span: self.span_from(start),
             })
         } else {
             Ok(values[0].clone())
@@ -352,6 +521,7 @@ impl Parser {
     }
 
     fn parse_generics(&mut self, n: usize) -> Result<Vec<Type>, ParserError> {
+        let start = self.peek()?.span.start;
         advance_match!(self, TokenKind::LessThan);
         let mut types = Vec::new();
         while self.peek()?.kind != TokenKind::GreaterThan {
@@ -366,6 +536,7 @@ impl Parser {
             Err(ParserError::InvalidNumberOfTypes {
                 expected: n,
                 actual: types.len(),
+                span: self.span_from(start),
             })
         } else {
             Ok(types)
@@ -390,6 +561,8 @@ impl Parser {
             TokenKind::String => Ok(Type::String),
             TokenKind::Struct => Ok(Type::Struct),
             TokenKind::Enum => Ok(Type::Enum),
+            TokenKind::EnumU16 => Ok(Type::EnumU16),
+            TokenKind::EnumString => Ok(Type::EnumString),
             TokenKind::Option => Ok(Type::Option),
             TokenKind::Box => Ok(Type::Box),
             TokenKind::Array => Ok(Type::Array),
@@ -401,14 +574,18 @@ impl Parser {
             TokenKind::HashSet => Ok(Type::HashSet),
             TokenKind::HashMap => Ok(Type::HashMap),
             TokenKind::Decimal => Ok(Type::Decimal),
-            TokenKind::BigDecimal => Ok(Type::BigDecimal),
             TokenKind::Address => Ok(Type::Address),
             TokenKind::Hash => Ok(Type::Hash),
             TokenKind::Bucket => Ok(Type::Bucket),
             TokenKind::BucketRef => Ok(Type::BucketRef),
             TokenKind::LazyMap => Ok(Type::LazyMap),
             TokenKind::Vault => Ok(Type::Vault),
-            _ => Err(ParserError::UnexpectedToken(token)),
+            TokenKind::Bytes => Ok(Type::Bytes),
+            TokenKind::Blob => Ok(Type::Blob),
+            _ => Err(ParserError::UnexpectedToken {
+                actual: token,
+                expected: Expected::Type,
+            }),
         }
     }
 }
@@ -493,18 +670,32 @@ mod tests {
         parse_value_ok!(
             r#"Enum(0u8, {"Hello", 123u8})"#,
             Value::Enum(
-                0,
+                Discriminator::U8(0),
                 Fields::Named(vec![Value::String("Hello".into()), Value::U8(123)]),
             )
         );
         parse_value_ok!(
             r#"Enum(0u8, ("Hello", 123u8))"#,
             Value::Enum(
-                0,
+                Discriminator::U8(0),
                 Fields::Unnamed(vec![Value::String("Hello".into()), Value::U8(123)]),
             )
         );
-        parse_value_ok!(r#"Enum(0u8)"#, Value::Enum(0, Fields::Unit,));
+        parse_value_ok!(
+            r#"Enum(0u8)"#,
+            Value::Enum(Discriminator::U8(0), Fields::Unit,)
+        );
+        parse_value_ok!(
+            r#"Enum(300u16)"#,
+            Value::Enum(Discriminator::U16(300), Fields::Unit,)
+        );
+        parse_value_ok!(
+            r#"Enum("Buy", (1u8))"#,
+            Value::Enum(
+                Discriminator::String("Buy".into()),
+                Fields::Unnamed(vec![Value::U8(1)]),
+            )
+        );
     }
 
     #[test]
@@ -604,32 +795,87 @@ mod tests {
         parse_value_error!(r#"Enum(0u8"#, ParserError::UnexpectedEof);
         parse_value_error!(
             r#"Enum(0u8}"#,
-            ParserError::UnexpectedToken(Token {
-                kind: TokenKind::CloseCurlyBrace,
-                span: Span {
-                    start: (1, 10),
-                    end: (1, 10)
-                }
-            })
+            ParserError::UnexpectedToken {
+                actual: Token {
+                    kind: TokenKind::CloseCurlyBrace,
+                    span: Span { start: 8, end: 9 }
+                },
+                expected: Expected::Token(TokenKind::CloseParenthesis)
+            }
         );
         parse_value_error!(
             r#"Address("abc", "def")"#,
             ParserError::InvalidNumberOfValues {
                 actual: 2,
-                expected: 1
+                expected: 1,
+                span: Span { start: 7, end: 21 }
             }
         );
         parse_value_error!(
             r#"Vec<String, String>("abc", "def")"#,
             ParserError::InvalidNumberOfTypes {
                 actual: 2,
-                expected: 1
+                expected: 1,
+                span: Span { start: 3, end: 19 }
             }
         );
     }
 
+    #[test]
+    fn test_parameters() {
+        let tx = Parser::new(
+            tokenize("$amount: Decimal;\n$trader: Address;\nDROP_ALL_BUCKET_REFS;").unwrap(),
+        )
+        .parse_transaction()
+        .unwrap();
+        assert_eq!(
+            tx.parameters,
+            vec![
+                Parameter {
+                    name: "amount".into(),
+                    ty: Type::Decimal,
+                    span: Span { start: 0, end: 17 }
+                },
+                Parameter {
+                    name: "trader".into(),
+                    ty: Type::Address,
+                    span: Span { start: 18, end: 35 }
+                },
+            ]
+        );
+        assert_eq!(tx.instructions, vec![Instruction::DropAllBucketRefs]);
+
+        assert_eq!(
+            Parser::new(tokenize("DROP_ALL_BUCKET_REFS;\n$amount: Decimal;").unwrap())
+                .parse_transaction(),
+            Err(ParserError::UnexpectedToken {
+                actual: Token {
+                    kind: TokenKind::Variable("amount".into()),
+                    span: Span { start: 22, end: 29 }
+                },
+                expected: Expected::Instruction
+            })
+        );
+    }
+
     #[test]
     fn test_transaction() {
+        parse_instruction_ok!(
+            r#"LET xrd = Address("030000000000000000000000000000000000000000000000000004");"#,
+            Instruction::Let {
+                name: "xrd".into(),
+                value: Value::Address(
+                    Value::String("030000000000000000000000000000000000000000000000000004".into())
+                        .into()
+                ),
+            }
+        );
+        parse_instruction_ok!(
+            r#"DEPOSIT_ALL_BUCKETS $DEFAULT_ACCOUNT;"#,
+            Instruction::DepositAllBuckets {
+                account: Value::Variable("DEFAULT_ACCOUNT".into()),
+            }
+        );
         parse_instruction_ok!(r#"DECLARE_TEMP_BUCKET;"#, Instruction::DeclareTempBucket);
         parse_instruction_ok!(
             r#"DECLARE_TEMP_BUCKET_REF;"#,
@@ -657,6 +903,36 @@ mod tests {
                 to: Value::BucketRef(Value::String("admin_auth".into()).into()),
             }
         );
+        parse_instruction_ok!(
+            r#"TAKE_ALL_FROM_CONTEXT  Address("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d")  Bucket("xrd_bucket");"#,
+            Instruction::TakeAllFromContext {
+                resource_address: Value::Address(
+                    Value::String("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d".into())
+                        .into()
+                ),
+                to: Value::Bucket(Value::String("xrd_bucket".into()).into()),
+            }
+        );
+        parse_instruction_ok!(
+            r#"RETURN_TO_CONTEXT  Bucket("xrd_bucket");"#,
+            Instruction::ReturnToContext {
+                bucket: Value::Bucket(Value::String("xrd_bucket".into()).into()),
+            }
+        );
+        parse_instruction_ok!(
+            r#"CREATE_BUCKET_REF_FROM_BUCKET  Bucket("badge")  BucketRef("badge_ref");"#,
+            Instruction::CreateBucketRefFromBucket {
+                bucket: Value::Bucket(Value::String("badge".into()).into()),
+                to: Value::BucketRef(Value::String("badge_ref".into()).into()),
+            }
+        );
+        parse_instruction_ok!(
+            r#"CLONE_BUCKET_REF  BucketRef("badge_ref")  BucketRef("badge_ref2");"#,
+            Instruction::CloneBucketRef {
+                bucket_ref: Value::BucketRef(Value::String("badge_ref".into()).into()),
+                to: Value::BucketRef(Value::String("badge_ref2".into()).into()),
+            }
+        );
         parse_instruction_ok!(
             r#"CALL_FUNCTION  Address("01d1f50010e4102d88aacc347711491f852c515134a9ecf67ba17c")  "Airdrop"  "new"  500u32  HashMap<String, U8>("key", 1u8);"#,
             Instruction::CallFunction {
@@ -690,6 +966,23 @@ mod tests {
                 ]
             }
         );
+        parse_instruction_ok!(
+            r#"ASSERT_CONTEXT_CONTAINS  Decimal("95")  Address("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d");"#,
+            Instruction::AssertContextContains {
+                amount: Value::Decimal(Value::String("95".into()).into()),
+                resource_address: Value::Address(
+                    Value::String("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d".into())
+                        .into()
+                ),
+            }
+        );
+        parse_instruction_ok!(
+            r#"ASSERT_BUCKET_AMOUNT_AT_LEAST  Bucket("xrd_bucket")  Decimal("95");"#,
+            Instruction::AssertBucketAmountAtLeast {
+                bucket: Value::Bucket(Value::String("xrd_bucket".into()).into()),
+                amount: Value::Decimal(Value::String("95".into()).into()),
+            }
+        );
         parse_instruction_ok!(r#"DROP_ALL_BUCKET_REFS;"#, Instruction::DropAllBucketRefs);
         parse_instruction_ok!(
             r#"DEPOSIT_ALL_BUCKETS  Address("02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de");"#,
@@ -700,5 +993,37 @@ mod tests {
                 ),
             }
         );
+        parse_instruction_ok!(
+            r#"PUBLISH_PACKAGE  Blob("36dae540b7889956f1f1d8d46ba23e5e44bf5723aef2a8e6b698686c02583618");"#,
+            Instruction::PublishPackage {
+                code: Value::Blob(
+                    Value::String(
+                        "36dae540b7889956f1f1d8d46ba23e5e44bf5723aef2a8e6b698686c02583618".into()
+                    )
+                    .into()
+                ),
+            }
+        );
+        parse_instruction_ok!(
+            r#"PUBLISH_PACKAGE  Bytes("0061736d");"#,
+            Instruction::PublishPackage {
+                code: Value::Bytes(Value::String("0061736d".into()).into()),
+            }
+        );
+        parse_instruction_ok!(
+            r#"END  Vec<Address>(Address("04005feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9"));"#,
+            Instruction::End {
+                signers: Value::Vec(
+                    Type::Address,
+                    vec![Value::Address(
+                        Value::String(
+                            "04005feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9"
+                                .into()
+                        )
+                        .into()
+                    )]
+                ),
+            }
+        );
     }
 }
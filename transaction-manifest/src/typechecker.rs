@@ -0,0 +1,513 @@
+use crate::ast;
+use crate::compiler::{compile_address, compile_string, CompilerError, CompilerErrorKind};
+use crate::lexer::Span;
+use radix_engine::transaction::*;
+use sbor::describe::{Discriminator, Fields, Type};
+use scrypto::abi;
+use scrypto::buffer::*;
+use std::convert::TryFrom;
+
+/// Checks the arguments of every call against the ABI of its target.
+///
+/// Literals are coerced to the expected type where no information is lost, e.g. `1u32`
+/// is accepted for a `u64` parameter, and `"1.0"` for a `Decimal` parameter.
+pub fn check_transaction<A: AbiProvider>(
+    tx: &mut ast::Transaction,
+    abi_provider: &A,
+) -> Result<(), CompilerError> {
+    for (instruction, span) in tx.instructions.iter_mut().zip(&tx.spans) {
+        check_instruction(instruction, span, abi_provider)?;
+    }
+    Ok(())
+}
+
+pub fn check_instruction<A: AbiProvider>(
+    instruction: &mut ast::Instruction,
+    span: &ast::InstructionSpan,
+    abi_provider: &A,
+) -> Result<(), CompilerError> {
+    let at = |span: &Span| {
+        let span = span.clone();
+        move |kind: CompilerErrorKind| CompilerError { kind, span }
+    };
+
+    match instruction {
+        ast::Instruction::CallFunction {
+            package_address,
+            blueprint_name,
+            function,
+            args,
+        } => {
+            let package = compile_address(package_address).map_err(at(&span.args[0]))?;
+            let name = compile_string(blueprint_name).map_err(at(&span.args[1]))?;
+            let function = compile_string(function).map_err(at(&span.args[2]))?;
+            let inputs = abi_provider
+                .export_abi(package, name, false)
+                .map_err(|_| CompilerErrorKind::FailedToExportAbi(package))
+                .and_then(|abi| find_function(&abi, &function))
+                .map_err(at(&span.span))?;
+            check_args(args, &inputs, span, 3)
+        }
+        ast::Instruction::CallMethod {
+            component_address,
+            method,
+            args,
+        } => {
+            let component = compile_address(component_address).map_err(at(&span.args[0]))?;
+            let method = compile_string(method).map_err(at(&span.args[1]))?;
+            let inputs = abi_provider
+                .export_abi_component(component, false)
+                .map_err(|_| CompilerErrorKind::FailedToExportAbi(component))
+                .and_then(|abi| find_method(&abi, &method))
+                .map_err(at(&span.span))?;
+            check_args(args, &inputs, span, 2)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn find_function(abi: &abi::Blueprint, function: &str) -> Result<Vec<Type>, CompilerErrorKind> {
+    abi.functions
+        .iter()
+        .find(|f| f.name == function)
+        .map(|f| f.inputs.clone())
+        .ok_or_else(|| CompilerErrorKind::FunctionNotFound(function.to_owned()))
+}
+
+fn find_method(abi: &abi::Blueprint, method: &str) -> Result<Vec<Type>, CompilerErrorKind> {
+    abi.methods
+        .iter()
+        .find(|m| m.name == method)
+        .map(|m| m.inputs.clone())
+        .ok_or_else(|| CompilerErrorKind::MethodNotFound(method.to_owned()))
+}
+
+/// Checks call arguments, where `offset` is the number of operands before the arguments.
+fn check_args(
+    args: &mut [ast::Value],
+    inputs: &[Type],
+    span: &ast::InstructionSpan,
+    offset: usize,
+) -> Result<(), CompilerError> {
+    if args.len() != inputs.len() {
+        return Err(CompilerError {
+            kind: CompilerErrorKind::WrongNumberOfArguments {
+                expected: inputs.len(),
+                actual: args.len(),
+            },
+            span: span.span.clone(),
+        });
+    }
+
+    for (i, (arg, ty)) in args.iter_mut().zip(inputs).enumerate() {
+        if !check_value(arg, ty) {
+            return Err(CompilerError {
+                kind: CompilerErrorKind::ArgumentTypeMismatch {
+                    index: i,
+                    expected: ty.clone(),
+                },
+                span: span.args[offset + i].clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks a value against the expected type, coercing it if necessary.
+fn check_value(value: &mut ast::Value, ty: &Type) -> bool {
+    if let Some(coerced) = coerce_literal(value, ty) {
+        *value = coerced;
+        return true;
+    }
+
+    match (value, ty) {
+        (ast::Value::Unit, Type::Unit)
+        | (ast::Value::Bool(_), Type::Bool)
+        | (ast::Value::String(_), Type::String) => true,
+        (ast::Value::Option(v), Type::Option { value }) => match v.as_mut() {
+            Some(inner) => check_value(inner, value),
+            None => true,
+        },
+        (ast::Value::Box(v), Type::Box { value }) => check_value(v, value),
+        (ast::Value::Array(t, elements), Type::Array { element, length }) => {
+            elements.len() == *length as usize && check_elements(t, elements, element)
+        }
+        (ast::Value::Tuple(elements), Type::Tuple { elements: types }) => {
+            elements.len() == types.len()
+                && elements
+                    .iter_mut()
+                    .zip(types)
+                    .all(|(e, t)| check_value(e, t))
+        }
+        (ast::Value::Result(v), Type::Result { okay, error }) => match v.as_mut() {
+            Ok(inner) => check_value(inner, okay),
+            Err(inner) => check_value(inner, error),
+        },
+        (ast::Value::Struct(fields), Type::Struct { fields: types, .. }) => {
+            check_fields(fields, types)
+        }
+        (ast::Value::Enum(discriminator, fields), Type::Enum { variants, .. }) => variants
+            .iter()
+            .enumerate()
+            .find(|(i, v)| v.discriminator_at(*i) == *discriminator)
+            .map(|(_, v)| check_fields(fields, &v.fields))
+            .unwrap_or(false),
+        (ast::Value::Bytes(_) | ast::Value::Blob(_), Type::Vec { element }) => {
+            matches!(**element, Type::U8)
+        }
+        (ast::Value::Vec(t, elements), Type::Vec { element })
+        | (ast::Value::TreeSet(t, elements), Type::TreeSet { element })
+        | (ast::Value::HashSet(t, elements), Type::HashSet { element }) => {
+            check_elements(t, elements, element)
+        }
+        (ast::Value::TreeMap(k, v, elements), Type::TreeMap { key, value })
+        | (ast::Value::HashMap(k, v, elements), Type::HashMap { key, value }) => {
+            elements.len() % 2 == 0
+                && elements.chunks_mut(2).all(|pair| {
+                    let (first, second) = pair.split_at_mut(1);
+                    check_value(&mut first[0], key) && check_value(&mut second[0], value)
+                })
+                && retype(k, key)
+                && retype(v, value)
+        }
+        (value, Type::Custom { name, .. }) => match custom_type(name) {
+            Some(t) => value.kind() == t,
+            // Types unknown to the manifest can't be checked
+            None => true,
+        },
+        (value, ty) => {
+            let kind = value.kind();
+            is_integer(kind) && integer_type(ty) == Some(kind)
+        }
+    }
+}
+
+fn check_elements(declared: &mut ast::Type, elements: &mut [ast::Value], ty: &Type) -> bool {
+    elements.iter_mut().all(|e| check_value(e, ty)) && retype(declared, ty)
+}
+
+fn check_fields(fields: &mut ast::Fields, types: &Fields) -> bool {
+    match (fields, types) {
+        (ast::Fields::Named(values), Fields::Named { named }) => {
+            values.len() == named.len()
+                && values
+                    .iter_mut()
+                    .zip(named)
+                    .all(|(v, (_, t))| check_value(v, t))
+        }
+        (ast::Fields::Unnamed(values), Fields::Unnamed { unnamed }) => {
+            values.len() == unnamed.len()
+                && values
+                    .iter_mut()
+                    .zip(unnamed)
+                    .all(|(v, t)| check_value(v, t))
+        }
+        (ast::Fields::Unit, Fields::Unit) => true,
+        _ => false,
+    }
+}
+
+/// Updates the declared element type of a collection after its elements are coerced.
+fn retype(declared: &mut ast::Type, ty: &Type) -> bool {
+    match ast_type(ty) {
+        Some(t) => {
+            *declared = t;
+            true
+        }
+        None => true,
+    }
+}
+
+/// Converts a literal of the wrong type into the expected type, if that's lossless.
+fn coerce_literal(value: &ast::Value, ty: &Type) -> Option<ast::Value> {
+    let expected = ast_type(ty)?;
+    if value.kind() == expected {
+        return None;
+    }
+
+    match value {
+        ast::Value::String(s) => match expected {
+            ast::Type::Decimal => Some(ast::Value::Decimal(ast::Value::String(s.clone()).into())),
+            ast::Type::Address => Some(ast::Value::Address(ast::Value::String(s.clone()).into())),
+            ast::Type::Hash => Some(ast::Value::Hash(ast::Value::String(s.clone()).into())),
+            ast::Type::Bucket => Some(ast::Value::Bucket(ast::Value::String(s.clone()).into())),
+            ast::Type::BucketRef => {
+                Some(ast::Value::BucketRef(ast::Value::String(s.clone()).into()))
+            }
+            _ => None,
+        },
+        _ => {
+            let n = integer_value(value)?;
+            match expected {
+                ast::Type::I8 => i8::try_from(n).ok().map(ast::Value::I8),
+                ast::Type::I16 => i16::try_from(n).ok().map(ast::Value::I16),
+                ast::Type::I32 => i32::try_from(n).ok().map(ast::Value::I32),
+                ast::Type::I64 => i64::try_from(n).ok().map(ast::Value::I64),
+                ast::Type::I128 => i128::try_from(n).ok().map(ast::Value::I128),
+                ast::Type::U8 => u8::try_from(n).ok().map(ast::Value::U8),
+                ast::Type::U16 => u16::try_from(n).ok().map(ast::Value::U16),
+                ast::Type::U32 => u32::try_from(n).ok().map(ast::Value::U32),
+                ast::Type::U64 => u64::try_from(n).ok().map(ast::Value::U64),
+                ast::Type::U128 => u128::try_from(n).ok().map(ast::Value::U128),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// An integer literal, widened so that any integer type fits.
+#[derive(Debug, Clone, Copy)]
+enum Integer {
+    Signed(i128),
+    Unsigned(u128),
+}
+
+macro_rules! impl_try_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Integer> for $t {
+                type Error = ();
+
+                fn try_from(n: Integer) -> Result<Self, Self::Error> {
+                    match n {
+                        Integer::Signed(v) => <$t>::try_from(v).map_err(|_| ()),
+                        Integer::Unsigned(v) => <$t>::try_from(v).map_err(|_| ()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+fn integer_value(value: &ast::Value) -> Option<Integer> {
+    match value {
+        ast::Value::I8(v) => Some(Integer::Signed(*v as i128)),
+        ast::Value::I16(v) => Some(Integer::Signed(*v as i128)),
+        ast::Value::I32(v) => Some(Integer::Signed(*v as i128)),
+        ast::Value::I64(v) => Some(Integer::Signed(*v as i128)),
+        ast::Value::I128(v) => Some(Integer::Signed(*v)),
+        ast::Value::U8(v) => Some(Integer::Unsigned(*v as u128)),
+        ast::Value::U16(v) => Some(Integer::Unsigned(*v as u128)),
+        ast::Value::U32(v) => Some(Integer::Unsigned(*v as u128)),
+        ast::Value::U64(v) => Some(Integer::Unsigned(*v as u128)),
+        ast::Value::U128(v) => Some(Integer::Unsigned(*v)),
+        _ => None,
+    }
+}
+
+fn is_integer(kind: ast::Type) -> bool {
+    matches!(
+        kind,
+        ast::Type::I8
+            | ast::Type::I16
+            | ast::Type::I32
+            | ast::Type::I64
+            | ast::Type::I128
+            | ast::Type::U8
+            | ast::Type::U16
+            | ast::Type::U32
+            | ast::Type::U64
+            | ast::Type::U128
+    )
+}
+
+fn integer_type(ty: &Type) -> Option<ast::Type> {
+    ast_type(ty).filter(|t| is_integer(*t))
+}
+
+/// Maps an ABI type to the corresponding manifest type.
+fn ast_type(ty: &Type) -> Option<ast::Type> {
+    Some(match ty {
+        Type::Unit => ast::Type::Unit,
+        Type::Bool => ast::Type::Bool,
+        Type::I8 => ast::Type::I8,
+        Type::I16 => ast::Type::I16,
+        Type::I32 => ast::Type::I32,
+        Type::I64 => ast::Type::I64,
+        Type::I128 => ast::Type::I128,
+        Type::U8 => ast::Type::U8,
+        Type::U16 => ast::Type::U16,
+        Type::U32 => ast::Type::U32,
+        Type::U64 => ast::Type::U64,
+        Type::U128 => ast::Type::U128,
+        Type::String => ast::Type::String,
+        Type::Option { .. } => ast::Type::Option,
+        Type::Box { .. } => ast::Type::Box,
+        Type::Array { .. } => ast::Type::Array,
+        Type::Tuple { .. } => ast::Type::Tuple,
+        Type::Struct { .. } => ast::Type::Struct,
+        Type::Enum { variants, .. } => match variants.first().map(|v| v.discriminator_at(0)) {
+            Some(Discriminator::U16(_)) => ast::Type::EnumU16,
+            Some(Discriminator::String(_)) => ast::Type::EnumString,
+            _ => ast::Type::Enum,
+        },
+        Type::Result { .. } => ast::Type::Result,
+        Type::Vec { .. } => ast::Type::Vec,
+        Type::TreeSet { .. } => ast::Type::TreeSet,
+        Type::TreeMap { .. } => ast::Type::TreeMap,
+        Type::HashSet { .. } => ast::Type::HashSet,
+        Type::HashMap { .. } => ast::Type::HashMap,
+        Type::Custom { name, .. } => return custom_type(name),
+    })
+}
+
+fn custom_type(name: &str) -> Option<ast::Type> {
+    match name {
+        SCRYPTO_NAME_AMOUNT => Some(ast::Type::Decimal),
+        SCRYPTO_NAME_ADDRESS
+        | SCRYPTO_NAME_PACKAGE
+        | SCRYPTO_NAME_COMPONENT
+        | SCRYPTO_NAME_RESOURCE_DEF
+        | SCRYPTO_NAME_ACCOUNT => Some(ast::Type::Address),
+        SCRYPTO_NAME_H256 => Some(ast::Type::Hash),
+        SCRYPTO_NAME_BID | SCRYPTO_NAME_BUCKET => Some(ast::Type::Bucket),
+        SCRYPTO_NAME_RID | SCRYPTO_NAME_BUCKET_REF => Some(ast::Type::BucketRef),
+        SCRYPTO_NAME_MID | SCRYPTO_NAME_LAZY_MAP => Some(ast::Type::LazyMap),
+        SCRYPTO_NAME_VID | SCRYPTO_NAME_VAULT => Some(ast::Type::Vault),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+    use crate::parser::Parser;
+    use sbor::describe::Variant;
+
+    #[macro_export]
+    macro_rules! check_value_ok {
+        ( $s:expr, $ty:expr, $expected:expr ) => {{
+            let mut value = Parser::new(tokenize($s).unwrap()).parse_value().unwrap();
+            assert!(check_value(&mut value, &$ty));
+            let expected = Parser::new(tokenize($expected).unwrap())
+                .parse_value()
+                .unwrap();
+            assert_eq!(value, expected);
+        }};
+    }
+
+    #[macro_export]
+    macro_rules! check_value_error {
+        ( $s:expr, $ty:expr ) => {{
+            let mut value = Parser::new(tokenize($s).unwrap()).parse_value().unwrap();
+            assert!(!check_value(&mut value, &$ty));
+        }};
+    }
+
+    fn custom(name: &str) -> Type {
+        Type::Custom {
+            name: name.to_owned(),
+            generics: vec![],
+        }
+    }
+
+    #[test]
+    fn test_exact_types() {
+        check_value_ok!("1u32", Type::U32, "1u32");
+        check_value_ok!(r#""abc""#, Type::String, r#""abc""#);
+        check_value_ok!(
+            r#"Decimal("1.0")"#,
+            custom(SCRYPTO_NAME_AMOUNT),
+            r#"Decimal("1.0")"#
+        );
+        check_value_ok!(
+            r#"Tuple(Some(1u8), Err("e"))"#,
+            Type::Tuple {
+                elements: vec![
+                    Type::Option {
+                        value: Box::new(Type::U8)
+                    },
+                    Type::Result {
+                        okay: Box::new(Type::U8),
+                        error: Box::new(Type::String)
+                    },
+                ]
+            },
+            r#"Tuple(Some(1u8), Err("e"))"#
+        );
+        check_value_ok!(
+            r#"Enum(1u8, (true))"#,
+            Type::Enum {
+                name: "E".to_owned(),
+                variants: vec![
+                    Variant {
+                        name: "A".to_owned(),
+                        fields: Fields::Unit,
+                        discriminator: None,
+                    },
+                    Variant {
+                        name: "B".to_owned(),
+                        fields: Fields::Unnamed {
+                            unnamed: vec![Type::Bool]
+                        },
+                        discriminator: None,
+                    },
+                ],
+                generics: vec![],
+            },
+            r#"Enum(1u8, (true))"#
+        );
+    }
+
+    #[test]
+    fn test_coercion() {
+        check_value_ok!("1u32", Type::U64, "1u64");
+        check_value_ok!("-1i128", Type::I8, "-1i8");
+        check_value_ok!(r#""1.5""#, custom(SCRYPTO_NAME_AMOUNT), r#"Decimal("1.5")"#);
+        check_value_ok!(r#""xrd""#, custom(SCRYPTO_NAME_BUCKET), r#"Bucket("xrd")"#);
+        check_value_ok!(
+            "Vec<U8>(1u8, 2u8)",
+            Type::Vec {
+                element: Box::new(Type::U32)
+            },
+            "Vec<U32>(1u32, 2u32)"
+        );
+        check_value_ok!(
+            r#"HashMap<U8, String>(1u8, "a")"#,
+            Type::HashMap {
+                key: Box::new(Type::U16),
+                value: Box::new(Type::String)
+            },
+            r#"HashMap<U16, String>(1u16, "a")"#
+        );
+        check_value_ok!(
+            r#"Bytes("0102")"#,
+            Type::Vec {
+                element: Box::new(Type::U8)
+            },
+            r#"Bytes("0102")"#
+        );
+    }
+
+    #[test]
+    fn test_mismatches() {
+        check_value_error!("256u32", Type::U8);
+        check_value_error!("-1i8", Type::U64);
+        check_value_error!(r#""abc""#, Type::U32);
+        check_value_error!("true", custom(SCRYPTO_NAME_ADDRESS));
+        check_value_error!(
+            r#"Bytes("0102")"#,
+            Type::Vec {
+                element: Box::new(Type::U32)
+            }
+        );
+        check_value_error!(
+            "Array<U8>(1u8)",
+            Type::Array {
+                element: Box::new(Type::U8),
+                length: 2
+            }
+        );
+        check_value_error!(
+            "Enum(2u8)",
+            Type::Enum {
+                name: "E".to_owned(),
+                variants: vec![],
+                generics: vec![],
+            }
+        );
+    }
+}
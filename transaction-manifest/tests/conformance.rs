@@ -0,0 +1,380 @@
+use radix_engine::engine::*;
+use radix_engine::ledger::*;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+use transaction_manifest::{compile, compile_with_abi, compile_with_env, decompile, Environment};
+
+/// Compiles a manifest, checks that it survives a decompile round trip, and runs it.
+fn run<L: Ledger>(executor: &mut TransactionExecutor<L>, manifest: &str) -> Receipt {
+    run_with_env(executor, manifest, &Environment::default())
+}
+
+fn run_with_env<L: Ledger>(
+    executor: &mut TransactionExecutor<L>,
+    manifest: &str,
+    env: &Environment,
+) -> Receipt {
+    let transaction = compile_with_env(manifest, env).unwrap();
+    let text = decompile(&transaction).unwrap();
+    assert_eq!(compile(&text), Ok(transaction.clone()), "{}", text);
+    executor.run(transaction, true).unwrap()
+}
+
+/// Identifies the variant of an instruction.
+///
+/// The match is exhaustive, so a new engine instruction doesn't build until it's covered here.
+fn variant(instruction: &Instruction) -> usize {
+    match instruction {
+        Instruction::DeclareTempBucket => 0,
+        Instruction::DeclareTempBucketRef => 1,
+        Instruction::TakeFromContext { .. } => 2,
+        Instruction::TakeAllFromContext { .. } => 3,
+        Instruction::ReturnToContext { .. } => 4,
+        Instruction::BorrowFromContext { .. } => 5,
+        Instruction::CreateBucketRefFromBucket { .. } => 6,
+        Instruction::CloneBucketRef { .. } => 7,
+        Instruction::CallFunction { .. } => 8,
+        Instruction::CallMethod { .. } => 9,
+        Instruction::AssertContextContains { .. } => 10,
+        Instruction::AssertBucketAmountAtLeast { .. } => 11,
+        Instruction::DropAllBucketRefs => 12,
+        Instruction::DepositAllBuckets { .. } => 13,
+        Instruction::End { .. } => 14,
+    }
+}
+
+#[test]
+fn test_every_instruction_is_expressible() {
+    let address =
+        Address::from_str("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1").unwrap();
+    let tx = Transaction {
+        instructions: vec![
+            Instruction::DeclareTempBucket,
+            Instruction::DeclareTempBucketRef,
+            Instruction::DeclareTempBucketRef,
+            Instruction::TakeFromContext {
+                amount: 1.into(),
+                resource_def: RADIX_TOKEN,
+                to: Bid(0),
+            },
+            Instruction::TakeAllFromContext {
+                resource_def: RADIX_TOKEN,
+                to: Bid(0),
+            },
+            Instruction::ReturnToContext { bucket: Bid(0) },
+            Instruction::BorrowFromContext {
+                amount: 1.into(),
+                resource_def: RADIX_TOKEN,
+                to: Rid(1),
+            },
+            Instruction::CreateBucketRefFromBucket {
+                bucket: Bid(0),
+                to: Rid(1),
+            },
+            Instruction::CloneBucketRef {
+                bucket_ref: Rid(1),
+                to: Rid(2),
+            },
+            Instruction::CallFunction {
+                package: address,
+                name: "Blueprint".to_owned(),
+                function: "new".to_owned(),
+                args: vec![SmartValue::from(1u32), SmartValue::from(Rid(2))],
+            },
+            Instruction::CallMethod {
+                component: address,
+                method: "deposit".to_owned(),
+                args: vec![SmartValue::from(Bid(0))],
+            },
+            Instruction::AssertContextContains {
+                amount: 1.into(),
+                resource_def: RADIX_TOKEN,
+            },
+            Instruction::AssertBucketAmountAtLeast {
+                bucket: Bid(0),
+                amount: 1.into(),
+            },
+            Instruction::DropAllBucketRefs,
+            Instruction::DepositAllBuckets { account: address },
+            Instruction::End {
+                signers: vec![Address::PublicKey([4; 33])],
+            },
+        ],
+    };
+
+    let variants: HashSet<usize> = tx.instructions.iter().map(variant).collect();
+    assert_eq!(variants, (0..15).collect());
+
+    let text = decompile(&tx).unwrap();
+    assert_eq!(compile(&text), Ok(tx), "{}", text);
+}
+
+#[test]
+fn test_context_instructions() {
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+
+    let receipt = run(
+        &mut executor,
+        &format!(
+            r#"CALL_METHOD Address("{account}") "withdraw" Decimal("10") Address("{xrd}");
+            ASSERT_CONTEXT_CONTAINS Decimal("10") Address("{xrd}");
+            TAKE_FROM_CONTEXT Decimal("4") Address("{xrd}") Bucket("some");
+            TAKE_ALL_FROM_CONTEXT Address("{xrd}") Bucket("rest");
+            ASSERT_BUCKET_AMOUNT_AT_LEAST Bucket("some") Decimal("4");
+            ASSERT_BUCKET_AMOUNT_AT_LEAST Bucket("rest") Decimal("6");
+            RETURN_TO_CONTEXT Bucket("rest");
+            CALL_METHOD Address("{account}") "deposit" Bucket("some");
+            DEPOSIT_ALL_BUCKETS Address("{account}");
+            END Vec<Address>(Address("{key}"));"#,
+            account = account,
+            key = key,
+            xrd = RADIX_TOKEN
+        ),
+    );
+    assert!(receipt.success);
+
+    // Assertions fail the transaction rather than being skipped
+    let receipt = run(
+        &mut executor,
+        &format!(
+            r#"CALL_METHOD Address("{account}") "withdraw" Decimal("10") Address("{xrd}");
+            ASSERT_CONTEXT_CONTAINS Decimal("11") Address("{xrd}");
+            DEPOSIT_ALL_BUCKETS Address("{account}");
+            END Vec<Address>(Address("{key}"));"#,
+            account = account,
+            key = key,
+            xrd = RADIX_TOKEN
+        ),
+    );
+    assert!(!receipt.success);
+    assert!(matches!(
+        receipt.results.last(),
+        Some(Err(RuntimeError::ContextAssertionFailed(..)))
+    ));
+}
+
+#[test]
+fn test_bucket_ref_instructions() {
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+
+    // Bucket refs left in the context are dropped explicitly, which unlocks their buckets
+    let receipt = run(
+        &mut executor,
+        &format!(
+            r#"CALL_METHOD Address("{account}") "withdraw" Decimal("10") Address("{xrd}");
+            BORROW_FROM_CONTEXT Decimal("5") Address("{xrd}") BucketRef("borrowed");
+            TAKE_ALL_FROM_CONTEXT Address("{xrd}") Bucket("xrd");
+            CREATE_BUCKET_REF_FROM_BUCKET Bucket("xrd") BucketRef("proof");
+            CLONE_BUCKET_REF BucketRef("proof") BucketRef("copy");
+            DROP_ALL_BUCKET_REFS;
+            RETURN_TO_CONTEXT Bucket("xrd");
+            DEPOSIT_ALL_BUCKETS Address("{account}");
+            END Vec<Address>(Address("{key}"));"#,
+            account = account,
+            key = key,
+            xrd = RADIX_TOKEN
+        ),
+    );
+    assert!(receipt.success);
+
+    // A bucket is unlocked back under its name, so the context doesn't take from it
+    let receipt = run(
+        &mut executor,
+        &format!(
+            r#"CALL_METHOD Address("{account}") "withdraw" Decimal("10") Address("{xrd}");
+            TAKE_ALL_FROM_CONTEXT Address("{xrd}") Bucket("xrd");
+            CREATE_BUCKET_REF_FROM_BUCKET Bucket("xrd") BucketRef("proof");
+            DROP_ALL_BUCKET_REFS;
+            CALL_METHOD Address("{account}") "withdraw" Decimal("5") Address("{xrd}");
+            TAKE_ALL_FROM_CONTEXT Address("{xrd}") Bucket("change");
+            ASSERT_BUCKET_AMOUNT_AT_LEAST Bucket("xrd") Decimal("10");
+            CALL_METHOD Address("{account}") "deposit" Bucket("xrd");
+            CALL_METHOD Address("{account}") "deposit" Bucket("change");
+            END Vec<Address>(Address("{key}"));"#,
+            account = account,
+            key = key,
+            xrd = RADIX_TOKEN
+        ),
+    );
+    assert!(receipt.success);
+
+    // Without dropping them, the transaction fails its resource check
+    let receipt = run(
+        &mut executor,
+        &format!(
+            r#"CALL_METHOD Address("{account}") "withdraw" Decimal("10") Address("{xrd}");
+            BORROW_FROM_CONTEXT Decimal("5") Address("{xrd}") BucketRef("borrowed");
+            DEPOSIT_ALL_BUCKETS Address("{account}");
+            END Vec<Address>(Address("{key}"));"#,
+            account = account,
+            key = key,
+            xrd = RADIX_TOKEN
+        ),
+    );
+    assert!(!receipt.success);
+    assert!(matches!(
+        receipt.results.last(),
+        Some(Err(RuntimeError::ResourceCheckFailure))
+    ));
+}
+
+#[test]
+fn test_call_instructions() {
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+
+    let code = include_bytes!("../../assets/account.wasm").to_vec();
+    let hash = sha256(&code);
+    let env = Environment {
+        blobs: HashMap::from([(hash, code)]),
+        ..Default::default()
+    };
+    let receipt = run_with_env(
+        &mut executor,
+        &format!(
+            r#"PUBLISH_PACKAGE Blob("{hash}");
+            END Vec<Address>(Address("{key}"));"#,
+            hash = hash,
+            key = key
+        ),
+        &env,
+    );
+    assert!(receipt.success);
+    let package = receipt.package(0).unwrap();
+
+    let receipt = run(
+        &mut executor,
+        &format!(
+            r#"CALL_FUNCTION Address("{package}") "Account" "new" Address("{key}");
+            END Vec<Address>(Address("{key}"));"#,
+            package = package,
+            key = key
+        ),
+    );
+    assert!(receipt.success);
+    let other = receipt.component(0).unwrap();
+
+    let receipt = run(
+        &mut executor,
+        &format!(
+            r#"CALL_METHOD Address("{account}") "withdraw" Decimal("10") Address("{xrd}");
+            TAKE_ALL_FROM_CONTEXT Address("{xrd}") Bucket("xrd");
+            CALL_METHOD Address("{other}") "deposit_batch" Vec<Bucket>(Bucket("xrd"));
+            END Vec<Address>(Address("{key}"));"#,
+            account = account,
+            other = other,
+            key = key,
+            xrd = RADIX_TOKEN
+        ),
+    );
+    assert!(receipt.success);
+}
+
+#[test]
+fn test_every_instruction_executes() {
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+
+    let transaction = compile(&format!(
+        r#"CALL_FUNCTION Address("{package}") "Account" "new" Address("{key}");
+        CALL_METHOD Address("{account}") "withdraw" Decimal("10") Address("{xrd}");
+        ASSERT_CONTEXT_CONTAINS Decimal("10") Address("{xrd}");
+        BORROW_FROM_CONTEXT Decimal("1") Address("{xrd}") BucketRef("borrowed");
+        TAKE_FROM_CONTEXT Decimal("4") Address("{xrd}") Bucket("some");
+        TAKE_ALL_FROM_CONTEXT Address("{xrd}") Bucket("rest");
+        ASSERT_BUCKET_AMOUNT_AT_LEAST Bucket("some") Decimal("4");
+        CREATE_BUCKET_REF_FROM_BUCKET Bucket("some") BucketRef("proof");
+        CLONE_BUCKET_REF BucketRef("proof") BucketRef("copy");
+        DROP_ALL_BUCKET_REFS;
+        RETURN_TO_CONTEXT Bucket("rest");
+        CALL_METHOD Address("{account}") "deposit" Bucket("some");
+        DEPOSIT_ALL_BUCKETS Address("{account}");
+        END Vec<Address>(Address("{key}"));"#,
+        package = ACCOUNT_PACKAGE,
+        account = account,
+        key = key,
+        xrd = RADIX_TOKEN
+    ))
+    .unwrap();
+
+    let variants: HashSet<usize> = transaction.instructions.iter().map(variant).collect();
+    assert_eq!(variants, (0..15).collect());
+
+    let receipt = executor.run(transaction, true).unwrap();
+    assert!(receipt.success, "{:?}", receipt);
+}
+
+#[test]
+fn test_abi_checked_manifest() {
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+
+    // Variables are substituted before arguments are checked against the ABI
+    let env = Environment {
+        variables: HashMap::from([(
+            "account".to_owned(),
+            transaction_manifest::parse_value(&format!(r#"Address("{}")"#, account)).unwrap(),
+        )]),
+        ..Default::default()
+    };
+    let manifest = format!(
+        r#"LET amount = Decimal("10");
+        CALL_METHOD $account "withdraw" $amount Address("{xrd}");
+        DEPOSIT_ALL_BUCKETS $account;
+        END Vec<Address>(Address("{key}"));"#,
+        key = key,
+        xrd = RADIX_TOKEN
+    );
+    let transaction = compile_with_abi(&manifest, &env, &executor).unwrap();
+    assert_eq!(compile_with_env(&manifest, &env), Ok(transaction.clone()));
+    assert!(executor.run(transaction, true).unwrap().success);
+}
+
+#[test]
+fn test_builder_and_manifest_agree() {
+    let mut ledger = InMemoryLedger::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, 0, 0);
+    let key = executor.new_public_key();
+    let account = executor.create_account(key);
+
+    let built = TransactionBuilder::new(&executor)
+        .declare_bucket(|builder, bid| {
+            builder.declare_bucket_ref(|builder, rid| {
+                builder
+                    .take_all_from_context(RADIX_TOKEN, bid)
+                    .assert_bucket_amount_at_least(bid, 0.into())
+                    .create_bucket_ref_from_bucket(bid, rid)
+                    .drop_all_bucket_refs()
+                    .assert_context_contains(0.into(), RADIX_TOKEN)
+            })
+        })
+        .deposit_all(account)
+        .build(vec![key])
+        .unwrap();
+    let compiled = compile(&format!(
+        r#"TAKE_ALL_FROM_CONTEXT Address("{xrd}") Bucket("xrd");
+        ASSERT_BUCKET_AMOUNT_AT_LEAST Bucket("xrd") Decimal("0");
+        CREATE_BUCKET_REF_FROM_BUCKET Bucket("xrd") BucketRef("proof");
+        DROP_ALL_BUCKET_REFS;
+        ASSERT_CONTEXT_CONTAINS Decimal("0") Address("{xrd}");
+        DEPOSIT_ALL_BUCKETS Address("{account}");
+        END Vec<Address>(Address("{key}"));"#,
+        account = account,
+        key = key,
+        xrd = RADIX_TOKEN
+    ))
+    .unwrap();
+    assert_eq!(compiled, built);
+}
@@ -0,0 +1,127 @@
+use proptest::prelude::*;
+use radix_engine::transaction::*;
+use scrypto::types::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+use transaction_manifest::{compile, decompile};
+
+const ADDRESS: &str = "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1";
+
+fn arb_arg() -> impl Strategy<Value = SmartValue> {
+    prop_oneof![
+        any::<bool>().prop_map(SmartValue::from),
+        any::<i8>().prop_map(SmartValue::from),
+        any::<i64>().prop_map(SmartValue::from),
+        any::<i128>().prop_map(SmartValue::from),
+        any::<u16>().prop_map(SmartValue::from),
+        any::<u128>().prop_map(SmartValue::from),
+        any::<String>().prop_map(SmartValue::from),
+        any::<Vec<u32>>().prop_map(SmartValue::from),
+        any::<Vec<u8>>().prop_map(SmartValue::from),
+        any::<Option<(u8, String)>>().prop_map(SmartValue::from),
+        any::<HashMap<String, u64>>().prop_map(SmartValue::from),
+        any::<u64>().prop_map(|v| SmartValue::from(Amount::from(v))),
+        (0u32..8).prop_map(|n| SmartValue::from(Bid(n))),
+        (0u32..8).prop_map(|n| SmartValue::from(Rid(n))),
+    ]
+}
+
+fn arb_instruction() -> impl Strategy<Value = Instruction> {
+    prop_oneof![
+        (any::<u64>(), 0u32..8).prop_map(|(amount, n)| Instruction::TakeFromContext {
+            amount: Amount::from(amount),
+            resource_def: Address::from_str(ADDRESS).unwrap(),
+            to: Bid(n),
+        }),
+        (any::<u64>(), 0u32..8).prop_map(|(amount, n)| Instruction::BorrowFromContext {
+            amount: Amount::from(amount),
+            resource_def: Address::from_str(ADDRESS).unwrap(),
+            to: Rid(n),
+        }),
+        (0u32..8).prop_map(|n| Instruction::TakeAllFromContext {
+            resource_def: Address::from_str(ADDRESS).unwrap(),
+            to: Bid(n),
+        }),
+        (0u32..8).prop_map(|n| Instruction::ReturnToContext { bucket: Bid(n) }),
+        (0u32..8, 0u32..8).prop_map(|(b, r)| Instruction::CreateBucketRefFromBucket {
+            bucket: Bid(b),
+            to: Rid(r),
+        }),
+        (0u32..8, 0u32..8).prop_map(|(r, to)| Instruction::CloneBucketRef {
+            bucket_ref: Rid(r),
+            to: Rid(to),
+        }),
+        ("[a-z_]{1,10}", prop::collection::vec(arb_arg(), 0..4)).prop_map(|(name, args)| {
+            Instruction::CallFunction {
+                package: Address::from_str(ADDRESS).unwrap(),
+                name: name.clone(),
+                function: name,
+                args,
+            }
+        }),
+        (any::<String>(), prop::collection::vec(arb_arg(), 0..4)).prop_map(|(method, args)| {
+            Instruction::CallMethod {
+                component: Address::from_str(ADDRESS).unwrap(),
+                method,
+                args,
+            }
+        }),
+        any::<u64>().prop_map(|amount| Instruction::AssertContextContains {
+            amount: Amount::from(amount),
+            resource_def: Address::from_str(ADDRESS).unwrap(),
+        }),
+        (0u32..8, any::<u64>()).prop_map(|(n, amount)| Instruction::AssertBucketAmountAtLeast {
+            bucket: Bid(n),
+            amount: Amount::from(amount),
+        }),
+        Just(Instruction::DropAllBucketRefs),
+        Just(Instruction::DepositAllBuckets {
+            account: Address::from_str(ADDRESS).unwrap(),
+        }),
+        (0u8..4).prop_map(|n| Instruction::End {
+            signers: (0..n).map(|i| Address::PublicKey([i; 33])).collect(),
+        }),
+        any::<Vec<u8>>().prop_map(|code| Instruction::CallFunction {
+            package: SYSTEM_PACKAGE,
+            name: "System".to_owned(),
+            function: "publish_package".to_owned(),
+            args: vec![SmartValue::from(code)],
+        }),
+    ]
+}
+
+fn arb_transaction() -> impl Strategy<Value = Transaction> {
+    (
+        prop::collection::vec(any::<bool>(), 0..8),
+        prop::collection::vec(arb_instruction(), 0..8),
+    )
+        .prop_map(|(declarations, body)| {
+            let mut instructions: Vec<Instruction> = declarations
+                .into_iter()
+                .map(|is_bucket| {
+                    if is_bucket {
+                        Instruction::DeclareTempBucket
+                    } else {
+                        Instruction::DeclareTempBucketRef
+                    }
+                })
+                .collect();
+            instructions.extend(body);
+            Transaction { instructions }
+        })
+}
+
+proptest! {
+    #[test]
+    fn test_round_trip(tx in arb_transaction()) {
+        let text = decompile(&tx).unwrap();
+        prop_assert_eq!(compile(&text), Ok(tx), "{}", text);
+    }
+
+    #[test]
+    fn test_decompile_is_stable(tx in arb_transaction()) {
+        let text = decompile(&tx).unwrap();
+        let recompiled = compile(&text).unwrap();
+        prop_assert_eq!(decompile(&recompiled).unwrap(), text);
+    }
+}